@@ -0,0 +1,616 @@
+use crate::saves::TRASH_DIR;
+use crate::{
+    BackupLayout, BackupPolicy, BackupTimestamp, Error, HashAlgo, OverflowStrategy, SlotFilter,
+    Steeve, WatchMode,
+};
+use log::warn;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default upper limit for the number of backups kept for each save.
+pub(crate) const DEFAULT_MAX_BACKUPS_LIMIT: usize = 1000;
+
+/// Default number of pruned backups kept in the trash.
+const DEFAULT_MAX_TRASH: usize = 100;
+
+/// Default time to wait after the game exits before syncing.
+pub(crate) const DEFAULT_GAME_EXIT_GRACE: Duration = Duration::from_secs(5);
+
+/// Default time to wait for writes to settle before syncing.
+pub(crate) const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Default time a save must stay unchanged before it is copied.
+const DEFAULT_SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Default time a changed save in the shared folder must stay unchanged before it is pulled.
+const DEFAULT_REMOTE_SETTLE_TIME: Duration = Duration::from_secs(10);
+
+/// Default number of retries when the save directories cannot be discovered yet.
+const DEFAULT_DISCOVERY_RETRIES: usize = 4;
+
+/// Default delay before the first discovery retry. It doubles after each retry.
+const DEFAULT_DISCOVERY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default time limit for the sync command.
+const DEFAULT_ON_SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default staging directory name, a hidden subdirectory of each save directory.
+const DEFAULT_STAGING_DIR: &str = ".steeve-staging";
+
+/// Configures and builds a [`Steeve`] sync service.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use steeve_sync::Steeve;
+/// let steeve = Steeve::builder().max_backups(10).trash(true).build()?;
+/// # Ok::<(), steeve_sync::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct SteeveBuilder {
+    pub(crate) wait_for_game_exit: bool,
+    pub(crate) game_exit_grace: Duration,
+    pub(crate) steam_max_backups: usize,
+    pub(crate) xbox_max_backups: usize,
+    pub(crate) max_backups_limit: usize,
+    pub(crate) trash: bool,
+    pub(crate) max_trash: usize,
+    pub(crate) follow_links: bool,
+    pub(crate) watch: bool,
+    pub(crate) initial_sync: bool,
+    pub(crate) watch_mode: WatchMode,
+    pub(crate) sync_interval: Duration,
+    pub(crate) debounce: Duration,
+    pub(crate) settle_time: Duration,
+    pub(crate) mtime_tolerance: Duration,
+    pub(crate) overflow: OverflowStrategy,
+    pub(crate) backup_policy: BackupPolicy,
+    pub(crate) backup_timestamp: BackupTimestamp,
+    pub(crate) compress_backups: bool,
+    pub(crate) backup_layout: BackupLayout,
+    pub(crate) steam_backup_subdir: String,
+    pub(crate) xbox_backup_subdir: String,
+    pub(crate) hash_algo: HashAlgo,
+    pub(crate) slot_filter: SlotFilter,
+    pub(crate) slot_pairs: Vec<(String, String)>,
+    pub(crate) staging_dir: Option<PathBuf>,
+    pub(crate) check_versions: bool,
+    pub(crate) verify_syncs: bool,
+    pub(crate) dry_run: bool,
+    pub(crate) sync_to_steam: bool,
+    pub(crate) sync_to_xbox: bool,
+    pub(crate) steam_save_dir: Option<PathBuf>,
+    pub(crate) xbox_save_dir: Option<PathBuf>,
+    pub(crate) remote_dirs: Vec<PathBuf>,
+    pub(crate) remote_settle_time: Duration,
+    pub(crate) project_dirs: (String, String, String),
+    pub(crate) discovery_retries: usize,
+    pub(crate) discovery_interval: Duration,
+    pub(crate) on_sync: Option<String>,
+    pub(crate) on_sync_timeout: Duration,
+}
+
+impl Default for SteeveBuilder {
+    fn default() -> Self {
+        Self {
+            wait_for_game_exit: false,
+            game_exit_grace: DEFAULT_GAME_EXIT_GRACE,
+            steam_max_backups: 25,
+            xbox_max_backups: 25,
+            max_backups_limit: DEFAULT_MAX_BACKUPS_LIMIT,
+            trash: false,
+            max_trash: DEFAULT_MAX_TRASH,
+            follow_links: false,
+            watch: true,
+            initial_sync: true,
+            watch_mode: WatchMode::default(),
+            sync_interval: Duration::ZERO,
+            debounce: DEFAULT_DEBOUNCE,
+            settle_time: DEFAULT_SETTLE_TIME,
+            mtime_tolerance: Duration::ZERO,
+            overflow: OverflowStrategy::default(),
+            backup_policy: BackupPolicy::default(),
+            backup_timestamp: BackupTimestamp::default(),
+            compress_backups: false,
+            backup_layout: BackupLayout::default(),
+            steam_backup_subdir: "Steam".to_string(),
+            xbox_backup_subdir: "Xbox".to_string(),
+            hash_algo: HashAlgo::default(),
+            slot_filter: SlotFilter::default(),
+            slot_pairs: Vec::new(),
+            staging_dir: None,
+            check_versions: true,
+            verify_syncs: false,
+            dry_run: false,
+            sync_to_steam: true,
+            sync_to_xbox: true,
+            steam_save_dir: None,
+            xbox_save_dir: None,
+            remote_dirs: Vec::new(),
+            remote_settle_time: DEFAULT_REMOTE_SETTLE_TIME,
+            project_dirs: (
+                "org".to_string(),
+                "KodeWerx".to_string(),
+                "SteeveSync".to_string(),
+            ),
+            discovery_retries: DEFAULT_DISCOVERY_RETRIES,
+            discovery_interval: DEFAULT_DISCOVERY_INTERVAL,
+            on_sync: None,
+            on_sync_timeout: DEFAULT_ON_SYNC_TIMEOUT,
+        }
+    }
+}
+
+impl SteeveBuilder {
+    /// Set the maximum number of backups to retain for both saves.
+    pub fn max_backups(mut self, max_backups: usize) -> Self {
+        self.steam_max_backups = max_backups;
+        self.xbox_max_backups = max_backups;
+        self
+    }
+
+    /// Set the maximum number of backups to retain for the Steam save.
+    pub fn steam_max_backups(mut self, max_backups: usize) -> Self {
+        self.steam_max_backups = max_backups;
+        self
+    }
+
+    /// Set the maximum number of backups to retain for the Xbox save.
+    pub fn xbox_max_backups(mut self, max_backups: usize) -> Self {
+        self.xbox_max_backups = max_backups;
+        self
+    }
+
+    /// Set the most backups that may be retained for each save.
+    ///
+    /// This guards against caps so large that every backup de-dupe and prune walks thousands of
+    /// files. Defaults to 1000.
+    pub fn max_backups_limit(mut self, limit: usize) -> Self {
+        self.max_backups_limit = limit;
+        self
+    }
+
+    /// Move pruned backups into a `Trash` folder instead of deleting them.
+    ///
+    /// The trash keeps up to [`SteeveBuilder::max_trash`] files, independent of the backup cap.
+    pub fn trash(mut self, enable: bool) -> Self {
+        self.trash = enable;
+        self
+    }
+
+    /// Set the maximum number of pruned backups to keep in the trash.
+    pub fn max_trash(mut self, max_trash: usize) -> Self {
+        self.max_trash = max_trash;
+        self
+    }
+
+    /// Hold off syncing while either edition of the game is running. Disabled by default.
+    ///
+    /// Changes are not synced while the game runs, nor for [`SteeveBuilder::game_exit_grace`]
+    /// after it exits, because the save may still be flushed for a moment after the process is
+    /// gone. Both saves are then synced once, so the save that the game wrote last wins.
+    pub fn wait_for_game_exit(mut self, enable: bool) -> Self {
+        self.wait_for_game_exit = enable;
+        self
+    }
+
+    /// Set how long to wait after the game exits before syncing, 5 seconds by default.
+    ///
+    /// Only used with [`SteeveBuilder::wait_for_game_exit`].
+    pub fn game_exit_grace(mut self, grace: Duration) -> Self {
+        self.game_exit_grace = grace;
+        self
+    }
+
+    /// Follow symbolic links (and junctions) inside the save directories when looking for saves.
+    ///
+    /// A save directory that is itself linked to another location, such as a larger drive or a
+    /// cloud-synced folder, is always resolved to its target, which is then walked, watched and
+    /// copied to. This option is for links further down. Link loops are detected and skipped.
+    pub fn follow_links(mut self, follow_links: bool) -> Self {
+        self.follow_links = follow_links;
+        self
+    }
+
+    /// Set whether saves are backed up before they are overwritten.
+    ///
+    /// See [`BackupPolicy::Disabled`] for the risks of turning backups off.
+    pub fn backup_policy(mut self, backup_policy: BackupPolicy) -> Self {
+        self.backup_policy = backup_policy;
+        self
+    }
+
+    /// Set the timestamp format used in new backup file names.
+    ///
+    /// Existing backups are recognized in either format.
+    pub fn backup_timestamp(mut self, backup_timestamp: BackupTimestamp) -> Self {
+        self.backup_timestamp = backup_timestamp;
+        self
+    }
+
+    /// Compress new backups with gzip, adding `.gz` to their file names.
+    ///
+    /// Compressed and uncompressed backups can be mixed; both are de-duped by the save they
+    /// contain, and restored transparently. Disabled by default, so older versions can still
+    /// restore the backups.
+    pub fn compress_backups(mut self, enable: bool) -> Self {
+        self.compress_backups = enable;
+        self
+    }
+
+    /// Set how backups are arranged in the backup directory.
+    pub fn backup_layout(mut self, backup_layout: BackupLayout) -> Self {
+        self.backup_layout = backup_layout;
+        self
+    }
+
+    /// Set the name of the Steam backup subdirectory, `Steam` by default.
+    ///
+    /// Only used with [`BackupLayout::PerSide`].
+    pub fn steam_backup_subdir(mut self, name: impl Into<String>) -> Self {
+        self.steam_backup_subdir = name.into();
+        self
+    }
+
+    /// Set the name of the Xbox backup subdirectory, `Xbox` by default.
+    ///
+    /// Only used with [`BackupLayout::PerSide`].
+    pub fn xbox_backup_subdir(mut self, name: impl Into<String>) -> Self {
+        self.xbox_backup_subdir = name.into();
+        self
+    }
+
+    /// Set the hash algorithm used to detect duplicate backups.
+    pub fn hash_algo(mut self, hash_algo: HashAlgo) -> Self {
+        self.hash_algo = hash_algo;
+        self
+    }
+
+    /// Set which save slots take part in syncing. All slots are synced by default.
+    ///
+    /// Excluded slots are left alone on both sides.
+    pub fn slot_filter(mut self, slot_filter: SlotFilter) -> Self {
+        self.slot_filter = slot_filter;
+        self
+    }
+
+    /// Sync a Steam save slot with an Xbox save slot, named like in [`SlotFilter`].
+    ///
+    /// Steam saves are named after the Steam ID and Xbox saves after a random GUID, so there is
+    /// no way to tell which saves belong together when a side has more than one. A single save
+    /// on each side is synced without pairing. Unpaired slots are only synced with the only
+    /// unpaired save on the other side, if there is just one. Can be called more than once.
+    pub fn pair_slots(
+        mut self,
+        steam_slot: impl Into<String>,
+        xbox_slot: impl Into<String>,
+    ) -> Self {
+        self.slot_pairs.push((steam_slot.into(), xbox_slot.into()));
+        self
+    }
+
+    /// Copy saves into a staging directory first, and move them over the destination once the
+    /// copy is verified.
+    ///
+    /// This keeps the game from reading a partially written save. The staging directory is
+    /// `.steeve-staging` inside each save directory by default, so the final move stays on the
+    /// same file system.
+    pub fn staging(mut self, enable: bool) -> Self {
+        self.staging_dir = enable.then(|| PathBuf::from(DEFAULT_STAGING_DIR));
+        self
+    }
+
+    /// Enable staging, with this staging directory.
+    ///
+    /// A relative path is inside each save directory. An absolute path must be on the same file
+    /// system as the saves for the final move to be atomic.
+    pub fn staging_dir(mut self, staging_dir: impl Into<PathBuf>) -> Self {
+        self.staging_dir = Some(staging_dir.into());
+        self
+    }
+
+    /// Refuse to sync a save with a newer format version than the destination save.
+    ///
+    /// The Steam and Xbox editions are not always updated at the same time, and a save from the
+    /// updated edition can corrupt the profile in the other. The save game and package format
+    /// versions come from the GVAS header of each save; the engine build is not compared, because
+    /// the editions often run different builds of the same game version. Enabled by default;
+    /// only disable it when both editions are known to be on the same game version.
+    pub fn check_versions(mut self, enable: bool) -> Self {
+        self.check_versions = enable;
+        self
+    }
+
+    /// Read back every synced save and compare it to the source, rolling back on a mismatch.
+    ///
+    /// This catches silent write corruption, at the cost of reading each save again after every
+    /// sync. A mismatch restores the destination to the save it had before the sync, and fails
+    /// with [`SaveError::SyncVerify`](crate::SaveError::SyncVerify). Disabled by default.
+    pub fn verify_syncs(mut self, enable: bool) -> Self {
+        self.verify_syncs = enable;
+        self
+    }
+
+    /// Report what would be synced and backed up, without writing any saves or backups.
+    ///
+    /// The modify time, version, and duplicate checks all run as usual, so the log and the
+    /// [`SteeveEvent`](crate::SteeveEvent)s show exactly what a real sync would do; the events are
+    /// marked with `dry_run`. Syncs finish with [`SyncOutcome::DryRun`](crate::SyncOutcome::DryRun),
+    /// and the pre-sync hook is not called. Missing directories are not created, and the lock that
+    /// keeps two instances from syncing at once is not taken. Disabled by default.
+    pub fn dry_run(mut self, enable: bool) -> Self {
+        self.dry_run = enable;
+        self
+    }
+
+    /// Copy Xbox saves over the Steam saves. Enabled by default.
+    ///
+    /// Disable this to sync from Steam to Xbox only. The Steam saves are then never overwritten
+    /// by a sync, and the Xbox save directory is not watched. Syncing with the shared folder, see
+    /// [`SteeveBuilder::remote_dir`], is not affected.
+    pub fn sync_to_steam(mut self, enable: bool) -> Self {
+        self.sync_to_steam = enable;
+        self
+    }
+
+    /// Copy Steam saves over the Xbox saves. Enabled by default.
+    ///
+    /// Disable this to sync from Xbox to Steam only, e.g. to keep a PC copy of a save that is
+    /// played on the console. The Xbox saves are then never overwritten by a sync, and the Steam
+    /// save directory is not watched.
+    pub fn sync_to_xbox(mut self, enable: bool) -> Self {
+        self.sync_to_xbox = enable;
+        self
+    }
+
+    /// Watch the save directories for changes.
+    ///
+    /// Without watching, saves are only synced by calling [`Steeve::sync_now`], which is useful
+    /// for a one-shot sync from a scheduled task:
+    ///
+    /// ```no_run
+    /// # use steeve_sync::Steeve;
+    /// Steeve::builder().watch(false).build()?.sync_now()?;
+    /// # Ok::<(), steeve_sync::Error>(())
+    /// ```
+    pub fn watch(mut self, watch: bool) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    /// Sync the newest save over the older one when Steeve starts.
+    ///
+    /// Saves that changed while Steeve was not running are otherwise only synced after the next
+    /// file system event. The usual modify time guard applies, so the older save never wins.
+    /// Enabled by default.
+    pub fn initial_sync(mut self, enable: bool) -> Self {
+        self.initial_sync = enable;
+        self
+    }
+
+    /// Set how the save directories are watched. Native file system events are used by default.
+    ///
+    /// Use [`WatchMode::Poll`] when saves are on a network or cloud-synced drive where changes
+    /// are never detected.
+    pub fn watch_mode(mut self, watch_mode: WatchMode) -> Self {
+        self.watch_mode = watch_mode;
+        self
+    }
+
+    /// Also sync every `interval` while watching, whether or not any file system events arrived.
+    ///
+    /// This is a safety net for setups that drop events without an error, such as network
+    /// shares, WSL, or some antivirus software. Each periodic sync works like
+    /// [`Steeve::sync_now`] and never bounces a save back to the side it came from, but nothing is
+    /// synced while paused. Zero disables it, which is the default.
+    pub fn sync_interval(mut self, interval: Duration) -> Self {
+        self.sync_interval = interval;
+        self
+    }
+
+    /// Set how long to wait for writes to a save to settle before syncing it, 500 ms by default.
+    ///
+    /// A shorter debounce syncs sooner, which suits fast local disks. The game may flush a save
+    /// with several writes though, and each one that lands outside the debounce is synced and
+    /// backed up on its own, so expect more redundant backups. Zero is allowed, and is rounded
+    /// up to a few milliseconds. Syncs never bounce back to the side they came from, at any
+    /// debounce.
+    ///
+    /// A longer debounce suits slow disks and network drives, where a save can take longer than
+    /// half a second to flush. Save validation only refuses a partial save when it is too short
+    /// to be a save at all, so waiting longer is the reliable fix.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Set how long a save must stay the same size and age before it is copied, 200 ms by
+    /// default.
+    ///
+    /// The save is checked again after this time, right before it is backed up and copied. A
+    /// save that is still changing is being written by the game, and its sync is retried later
+    /// instead of copying a partial save. Zero skips the check.
+    pub fn settle_time(mut self, settle_time: Duration) -> Self {
+        self.settle_time = settle_time;
+        self
+    }
+
+    /// Set how far apart modify times can be and still count as the same time, zero by default.
+    ///
+    /// Some file systems, like FAT32 and exFAT, store modify times in 2 second steps, and synced
+    /// folders may have clock skew. Within the tolerance, the saves are compared by contents
+    /// instead: identical saves are left alone, otherwise the larger save wins, then the one
+    /// written last. A save is only skipped as older when its modify time is further behind.
+    pub fn mtime_tolerance(mut self, tolerance: Duration) -> Self {
+        self.mtime_tolerance = tolerance;
+        self
+    }
+
+    /// Set what happens when the watcher reports that events may have been lost.
+    ///
+    /// By default, both saves are reconciled with a full sync. The debouncer queues one pending
+    /// event per path without a limit, so there is no queue capacity to tune; events are lost in
+    /// the operating system's buffer beneath it.
+    pub fn overflow_strategy(mut self, overflow: OverflowStrategy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Use this Steam save directory instead of discovering it. It is created if it does not
+    /// exist.
+    ///
+    /// See [`discover_steam_save_dirs`](crate::discover_steam_save_dirs) for candidates.
+    pub fn steam_save_dir(mut self, save_dir: impl Into<PathBuf>) -> Self {
+        self.steam_save_dir = Some(save_dir.into());
+        self
+    }
+
+    /// Use this Xbox save directory instead of discovering it. It is created if it does not
+    /// exist.
+    ///
+    /// See [`discover_xbox_save_dirs`](crate::discover_xbox_save_dirs) for candidates.
+    pub fn xbox_save_dir(mut self, save_dir: impl Into<PathBuf>) -> Self {
+        self.xbox_save_dir = Some(save_dir.into());
+        self
+    }
+
+    /// Also sync the Steam saves with this folder, shared with other machines. It is created if
+    /// it does not exist.
+    ///
+    /// The folder is kept in sync by something else, like Dropbox, OneDrive, or a network share.
+    /// It is checked for new saves every 30 seconds, and after each local change. Whichever side
+    /// changed since the last sync wins. When both changed, e.g. after playing on two machines
+    /// while one was offline, nothing is overwritten and the sync fails with
+    /// [`SaveError::Conflict`](crate::SaveError::Conflict); the shared save is kept as a Steam
+    /// backup, so either one can be restored.
+    ///
+    /// Call this again for each additional folder, e.g. one per machine or a second Steam
+    /// library. Each folder is watched and polled, and the newest save is copied to all of them.
+    pub fn remote_dir(mut self, remote_dir: impl Into<PathBuf>) -> Self {
+        self.remote_dirs.push(remote_dir.into());
+        self
+    }
+
+    /// Set how long a changed save in the shared folder must stay unchanged before it is pulled,
+    /// 10 seconds by default.
+    ///
+    /// Synced folders may deliver a save in several writes, far apart. Zero pulls right away.
+    pub fn remote_settle_time(mut self, settle_time: Duration) -> Self {
+        self.remote_settle_time = settle_time;
+        self
+    }
+
+    /// Run a command after each successful sync.
+    ///
+    /// The command is split on whitespace into the program and its arguments, and is not run
+    /// through a shell. These placeholders are replaced in each argument:
+    ///
+    /// - `{side}`: the side that was synced to, `steam` or `xbox`.
+    /// - `{file}`: the path to the save that was written.
+    /// - `{direction}`: `steam-to-xbox` or `xbox-to-steam`.
+    ///
+    /// The command runs in the background, and its output is logged. A command that fails or
+    /// runs for longer than [`SteeveBuilder::on_sync_timeout`] is logged and does not affect
+    /// syncing.
+    ///
+    /// ```no_run
+    /// # use steeve_sync::Steeve;
+    /// let steeve = Steeve::builder()
+    ///     .on_sync("rclone copy {file} remote:drg/{side}")
+    ///     .build()?;
+    /// # Ok::<(), steeve_sync::Error>(())
+    /// ```
+    pub fn on_sync(mut self, command: impl Into<String>) -> Self {
+        self.on_sync = Some(command.into());
+        self
+    }
+
+    /// Set how long the [`SteeveBuilder::on_sync`] command may run before it is killed, 30
+    /// seconds by default.
+    pub fn on_sync_timeout(mut self, timeout: Duration) -> Self {
+        self.on_sync_timeout = timeout;
+        self
+    }
+
+    /// Set how often to retry discovering the save directories when Steam or the Xbox edition
+    /// cannot be found yet.
+    ///
+    /// Right after boot, Steam and the Xbox package may not be registered yet when Steeve starts.
+    /// The first retry waits for `interval`, and the delay doubles after each retry. The default
+    /// is 4 retries from 2 seconds, about 30 seconds in total. Zero retries fails right away.
+    /// This only applies to directories that are not configured explicitly.
+    pub fn discovery_retry(mut self, retries: usize, interval: Duration) -> Self {
+        self.discovery_retries = retries;
+        self.discovery_interval = interval;
+        self
+    }
+
+    /// Set the qualifier, organization, and application names that locate the data directory,
+    /// where backups and the lock file are kept.
+    ///
+    /// The default is `org`, `KodeWerx`, `SteeveSync`. Other front-ends should use their own
+    /// names, so they do not share backups or the single-instance lock with Steeve Sync.
+    ///
+    /// ```no_run
+    /// # use steeve_sync::Steeve;
+    /// let steeve = Steeve::builder()
+    ///     .project_dirs("com", "Example", "SaveMirror")
+    ///     .build()?;
+    /// # Ok::<(), steeve_sync::Error>(())
+    /// ```
+    pub fn project_dirs(
+        mut self,
+        qualifier: impl Into<String>,
+        organization: impl Into<String>,
+        application: impl Into<String>,
+    ) -> Self {
+        self.project_dirs = (qualifier.into(), organization.into(), application.into());
+        self
+    }
+
+    /// Create the sync service.
+    ///
+    /// # Errors
+    ///
+    /// May fail if there are any I/O errors, or if the save directories cannot be located.
+    pub fn build(self) -> Result<Steeve, Error> {
+        let max_backups = self.steam_max_backups.max(self.xbox_max_backups);
+        if max_backups > self.max_backups_limit {
+            warn!(
+                "Max backups is {max_backups}, over the limit of {}",
+                self.max_backups_limit,
+            );
+            return Err(Error::MaxBackups);
+        }
+        if self.steam_max_backups < 1 || self.xbox_max_backups < 1 {
+            return Err(Error::MaxBackups);
+        }
+        if !self.sync_to_steam && !self.sync_to_xbox {
+            return Err(Error::NoSyncDirection);
+        }
+        if self.trash && self.max_trash < 1 {
+            return Err(Error::MaxTrash);
+        }
+        if self.watch_mode == WatchMode::Poll(Duration::ZERO) {
+            return Err(Error::PollInterval);
+        }
+        if self.backup_layout == BackupLayout::PerSide && !self.distinct_backup_subdirs() {
+            return Err(Error::BackupDirs);
+        }
+
+        Steeve::from_builder(self)
+    }
+
+    /// Check that the per-side backup subdirectories are distinct from each other and the
+    /// trash, ignoring case for Windows.
+    fn distinct_backup_subdirs(&self) -> bool {
+        let steam = self.steam_backup_subdir.as_str();
+        let xbox = self.xbox_backup_subdir.as_str();
+
+        !steam.is_empty()
+            && !xbox.is_empty()
+            && !steam.eq_ignore_ascii_case(xbox)
+            && !steam.eq_ignore_ascii_case(TRASH_DIR)
+            && !xbox.eq_ignore_ascii_case(TRASH_DIR)
+    }
+}
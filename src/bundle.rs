@@ -0,0 +1,187 @@
+//! Portable backup bundles: a zip file of the backups for both sides.
+//!
+//! A bundle holds a manifest and one directory per side, e.g. `Steam/` and `Xbox/`, with the
+//! backup files inside.
+
+use crate::saves::{parse_backup_name, SaveError};
+use crate::Error;
+use log::info;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Name of the manifest file in a bundle.
+const MANIFEST: &str = "steeve-bundle.txt";
+
+/// First line of the manifest, identifying the file as a bundle.
+const MANIFEST_HEADER: &str = "Steeve-Sync backup bundle";
+
+/// The backups for one side of a bundle.
+pub(crate) struct BundleSide {
+    /// Directory name in the bundle, e.g. `Steam`.
+    pub(crate) name: &'static str,
+
+    /// Directory that backups are imported into.
+    pub(crate) backup_dir: PathBuf,
+
+    /// Backups to export.
+    pub(crate) backups: Vec<PathBuf>,
+}
+
+/// Write the backups for each side into a new bundle at `dest`.
+pub(crate) fn export(dest: &Path, sides: &[BundleSide]) -> Result<(), Error> {
+    let mut zip = ZipWriter::new(File::create(dest).map_err(SaveError::from)?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    zip.start_file(MANIFEST, options)?;
+    writeln!(zip, "{MANIFEST_HEADER}").map_err(SaveError::from)?;
+    writeln!(zip, "version: {}", env!("CARGO_PKG_VERSION")).map_err(SaveError::from)?;
+    writeln!(zip, "created: {timestamp}").map_err(SaveError::from)?;
+
+    let mut count = 0;
+    for side in sides {
+        writeln!(zip, "{}: {} backups", side.name, side.backups.len()).map_err(SaveError::from)?;
+
+        for path in &side.backups {
+            let Some(filename) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+
+            zip.start_file(format!("{}/{filename}", side.name), options)?;
+            let mut file = File::open(path).map_err(SaveError::from)?;
+            std::io::copy(&mut file, &mut zip).map_err(SaveError::from)?;
+            count += 1;
+        }
+    }
+
+    zip.finish()?;
+    info!("Exported {count} backups to bundle: {:?}", dest);
+
+    Ok(())
+}
+
+/// Restore the backups in the bundle at `src` into the backup directory of each side.
+///
+/// The whole bundle is validated before anything is written. Existing backups with the same name
+/// are only overwritten with `force`. Returns the number of restored backups.
+pub(crate) fn import(src: &Path, sides: &[BundleSide], force: bool) -> Result<usize, Error> {
+    let mut zip = ZipArchive::new(File::open(src).map_err(SaveError::from)?)?;
+
+    // Validate the manifest
+    let mut manifest = String::new();
+    zip.by_name(MANIFEST)
+        .map_err(|_| Error::BadBundle("missing manifest".to_string()))?
+        .read_to_string(&mut manifest)
+        .map_err(SaveError::from)?;
+    if manifest.lines().next() != Some(MANIFEST_HEADER) {
+        return Err(Error::BadBundle("not a Steeve-Sync bundle".to_string()));
+    }
+
+    // Validate every entry, and find where it goes
+    let mut targets = Vec::new();
+    for index in 0..zip.len() {
+        let entry = zip.by_index(index)?;
+        if entry.is_dir() || entry.name() == MANIFEST {
+            continue;
+        }
+
+        let target = entry
+            .enclosed_name()
+            .and_then(|path| bundle_target(path, sides))
+            .ok_or_else(|| Error::BadBundle(format!("unexpected file: {}", entry.name())))?;
+        if target.exists() && !force {
+            return Err(Error::BundleClobber(target));
+        }
+        targets.push((index, target));
+    }
+
+    for (index, target) in &targets {
+        let mut entry = zip.by_index(*index)?;
+        let mut file = File::create(target).map_err(SaveError::from)?;
+        std::io::copy(&mut entry, &mut file).map_err(SaveError::from)?;
+    }
+    info!("Imported {} backups from bundle: {:?}", targets.len(), src);
+
+    Ok(targets.len())
+}
+
+/// Get the path to restore a bundle entry to, if it is a backup in a side's directory.
+fn bundle_target(path: &Path, sides: &[BundleSide]) -> Option<PathBuf> {
+    let mut components = path.components();
+    let (Some(Component::Normal(dir)), Some(Component::Normal(filename)), None) =
+        (components.next(), components.next(), components.next())
+    else {
+        return None;
+    };
+
+    let side = sides.iter().find(|side| dir.to_str() == Some(side.name))?;
+    parse_backup_name(filename.to_str()?)?;
+
+    Some(side.backup_dir.join(filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const STEAM_BACKUP: &str = "1680000000_steam_76561197960287930_Player.sav";
+
+    fn sides(dir: &Path) -> [BundleSide; 1] {
+        let backup_dir = dir.join("Steam");
+        std::fs::create_dir_all(&backup_dir).unwrap();
+
+        [BundleSide {
+            name: "Steam",
+            backups: vec![backup_dir.join(STEAM_BACKUP)],
+            backup_dir,
+        }]
+    }
+
+    #[test]
+    fn export_then_import() {
+        let from = TempDir::new().unwrap();
+        let to = TempDir::new().unwrap();
+        let bundle = from.path().join("bundle.zip");
+
+        let from_sides = sides(from.path());
+        std::fs::write(&from_sides[0].backups[0], b"backup").unwrap();
+        export(&bundle, &from_sides).unwrap();
+
+        let to_sides = sides(to.path());
+        assert_eq!(import(&bundle, &to_sides, false).unwrap(), 1);
+        assert_eq!(std::fs::read(&to_sides[0].backups[0]).unwrap(), b"backup");
+
+        // Existing backups are not overwritten without force
+        assert!(matches!(
+            import(&bundle, &to_sides, false),
+            Err(Error::BundleClobber(_)),
+        ));
+        assert_eq!(import(&bundle, &to_sides, true).unwrap(), 1);
+    }
+
+    #[test]
+    fn import_rejects_unexpected_files() {
+        let dir = TempDir::new().unwrap();
+        let bundle = dir.path().join("bundle.zip");
+
+        let mut zip = ZipWriter::new(File::create(&bundle).unwrap());
+        zip.start_file(MANIFEST, FileOptions::default()).unwrap();
+        writeln!(zip, "{MANIFEST_HEADER}").unwrap();
+        zip.start_file("Steam/notes.txt", FileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+
+        assert!(matches!(
+            import(&bundle, &sides(dir.path()), false),
+            Err(Error::BadBundle(_)),
+        ));
+    }
+}
@@ -0,0 +1,239 @@
+//! User configuration, stored as TOML in the config directory.
+
+use crate::builder::{DEFAULT_DEBOUNCE, DEFAULT_GAME_EXIT_GRACE, DEFAULT_MAX_BACKUPS_LIMIT};
+use crate::{Error, SteeveBuilder};
+use directories::ProjectDirs;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Name of the config file in the config directory.
+const CONFIG_FILE: &str = "config.toml";
+
+/// Settings that users can change without recompiling.
+///
+/// Fields missing from the file keep their default values.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Maximum number of backups to retain for each save, from 1 to 1000.
+    pub max_backups: usize,
+
+    /// Use this Steam save directory instead of discovering it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steam_save_dir: Option<PathBuf>,
+
+    /// Use this Xbox save directory instead of discovering it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xbox_save_dir: Option<PathBuf>,
+
+    /// Also sync the Steam saves with this folder, shared with other machines.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_dir: Option<PathBuf>,
+
+    /// More folders to sync the Steam saves with, e.g. one per machine or a second Steam library.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remote_dirs: Vec<PathBuf>,
+
+    /// Time to wait for writes to settle before syncing, in milliseconds.
+    pub debounce_ms: u64,
+
+    /// Hold off syncing while either edition of the game is running.
+    pub wait_for_game_exit: bool,
+
+    /// Time to wait after the game exits before syncing, in seconds.
+    pub game_exit_grace_secs: u64,
+
+    /// Also sync at this interval in seconds, in case file system events are lost. Zero disables
+    /// it.
+    pub sync_interval_secs: u64,
+
+    /// Copy Xbox saves over the Steam saves.
+    pub sync_to_steam: bool,
+
+    /// Copy Steam saves over the Xbox saves.
+    pub sync_to_xbox: bool,
+
+    /// Show a desktop notification for each synced save. Errors are always shown.
+    pub notify_success: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_backups: 25,
+            steam_save_dir: None,
+            xbox_save_dir: None,
+            remote_dir: None,
+            remote_dirs: Vec::new(),
+            debounce_ms: DEFAULT_DEBOUNCE.as_millis() as u64,
+            wait_for_game_exit: false,
+            game_exit_grace_secs: DEFAULT_GAME_EXIT_GRACE.as_secs(),
+            sync_interval_secs: 0,
+            sync_to_steam: true,
+            sync_to_xbox: true,
+            notify_success: true,
+        }
+    }
+}
+
+impl Config {
+    /// Get the path of the config file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the home directory cannot be found.
+    pub fn path() -> Result<PathBuf, Error> {
+        let config_dir = ProjectDirs::from("org", "KodeWerx", "SteeveSync")
+            .ok_or(Error::HomeDir)?
+            .config_dir()
+            .to_path_buf();
+
+        Ok(config_dir.join(CONFIG_FILE))
+    }
+
+    /// Load the config file, or create it with the defaults when it does not exist.
+    ///
+    /// The defaults are still returned when the new file cannot be written.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the config file exists but cannot be read or parsed.
+    pub fn load_or_default() -> Result<Self, Error> {
+        Self::load_or_default_from(&Self::path()?)
+    }
+
+    /// Save the config file, creating the config directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the config file cannot be written.
+    pub fn save(&self) -> Result<(), Error> {
+        self.save_to(&Self::path()?)
+    }
+
+    /// Create a [`SteeveBuilder`] with these settings.
+    ///
+    /// A `max_backups` out of range is clamped, so a typo in the file does not stop Steeve from
+    /// starting.
+    pub fn builder(&self) -> SteeveBuilder {
+        let max_backups = self.max_backups.clamp(1, DEFAULT_MAX_BACKUPS_LIMIT);
+        if max_backups != self.max_backups {
+            warn!(
+                "Max backups of {} in the config file is out of range, using {max_backups}",
+                self.max_backups,
+            );
+        }
+
+        let mut builder = SteeveBuilder::default()
+            .max_backups(max_backups)
+            .debounce(Duration::from_millis(self.debounce_ms))
+            .wait_for_game_exit(self.wait_for_game_exit)
+            .game_exit_grace(Duration::from_secs(self.game_exit_grace_secs))
+            .sync_interval(Duration::from_secs(self.sync_interval_secs))
+            .sync_to_steam(self.sync_to_steam)
+            .sync_to_xbox(self.sync_to_xbox);
+        if let Some(save_dir) = &self.steam_save_dir {
+            builder = builder.steam_save_dir(save_dir);
+        }
+        if let Some(save_dir) = &self.xbox_save_dir {
+            builder = builder.xbox_save_dir(save_dir);
+        }
+        for remote_dir in self.remote_dir.iter().chain(&self.remote_dirs) {
+            builder = builder.remote_dir(remote_dir);
+        }
+
+        builder
+    }
+
+    fn load_or_default_from(path: &Path) -> Result<Self, Error> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                let config = Self::default();
+                match config.save_to(path) {
+                    Ok(()) => info!("Created config file {path:?}"),
+                    Err(err) => warn!("Could not create config file: {err}"),
+                }
+
+                return Ok(config);
+            }
+            Err(err) => return Err(Error::ConfigIo(path.to_path_buf(), err)),
+        };
+
+        toml::from_str(&text).map_err(|err| Error::BadConfig(path.to_path_buf(), err.to_string()))
+    }
+
+    fn save_to(&self, path: &Path) -> Result<(), Error> {
+        let text = toml::to_string_pretty(self)
+            .map_err(|err| Error::BadConfig(path.to_path_buf(), err.to_string()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| Error::ConfigIo(dir.to_path_buf(), err))?;
+        }
+
+        std::fs::write(path, text).map_err(|err| Error::ConfigIo(path.to_path_buf(), err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn missing_config_is_created() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("SteeveSync").join(CONFIG_FILE);
+
+        let config = Config::load_or_default_from(&path).unwrap();
+        assert_eq!(config, Config::default());
+        assert!(path.is_file());
+        assert_eq!(Config::load_or_default_from(&path).unwrap(), config);
+    }
+
+    #[test]
+    fn config_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+
+        let config = Config {
+            max_backups: 5,
+            steam_save_dir: Some(dir.path().join("steam")),
+            xbox_save_dir: None,
+            remote_dir: Some(dir.path().join("shared")),
+            remote_dirs: vec![dir.path().join("library")],
+            debounce_ms: 2000,
+            wait_for_game_exit: true,
+            game_exit_grace_secs: 10,
+            sync_interval_secs: 300,
+            sync_to_steam: true,
+            sync_to_xbox: false,
+            notify_success: false,
+        };
+        config.save_to(&path).unwrap();
+        assert_eq!(Config::load_or_default_from(&path).unwrap(), config);
+        assert_eq!(config.builder().remote_dirs.len(), 2);
+
+        // Missing fields keep their defaults
+        std::fs::write(&path, "max_backups = 3\n").unwrap();
+        let config = Config::load_or_default_from(&path).unwrap();
+        assert_eq!(config.max_backups, 3);
+        assert_eq!(config.debounce_ms, Config::default().debounce_ms);
+
+        // Out of range caps are clamped
+        for (max_backups, clamped) in [(0, 1), (100_000, DEFAULT_MAX_BACKUPS_LIMIT)] {
+            let builder = Config {
+                max_backups,
+                ..Config::default()
+            }
+            .builder();
+            assert_eq!(builder.steam_max_backups, clamped);
+            assert_eq!(builder.xbox_max_backups, clamped);
+        }
+
+        std::fs::write(&path, "max_backups = \"lots\"\n").unwrap();
+        let result = Config::load_or_default_from(&path);
+        assert!(matches!(result, Err(Error::BadConfig(..))));
+    }
+}
@@ -0,0 +1,155 @@
+//! Detecting whether the game is running, to hold off syncs until it exits.
+
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Process names of the Steam and Xbox editions of the game.
+const GAME_PROCESSES: [&str; 2] = ["fsd-win64-shipping.exe", "fsd-wingdk-shipping.exe"];
+
+/// Check if either edition of the game is running.
+///
+/// When the process list cannot be read, the game is assumed to not be running.
+pub fn game_running() -> bool {
+    let output = if cfg!(windows) {
+        Command::new("tasklist")
+            .args(["/FO", "CSV", "/NH"])
+            .output()
+    } else {
+        Command::new("ps").args(["-A", "-o", "args="]).output()
+    };
+
+    match output {
+        Ok(output) => {
+            let processes = String::from_utf8_lossy(&output.stdout).to_lowercase();
+            GAME_PROCESSES
+                .iter()
+                .any(|process| processes.contains(process))
+        }
+        Err(err) => {
+            warn!("Unable to check if the game is running: {err}");
+            false
+        }
+    }
+}
+
+/// What was last seen of the game.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum GameState {
+    /// The game has not been seen running.
+    NotRunning,
+
+    /// The game was running.
+    Running,
+
+    /// The game was first seen to have exited at this time.
+    Exited(Instant),
+}
+
+/// Holds off syncs while the game is running, and for a grace period after it exits.
+///
+/// The game may still be flushing its save for a moment after the process is gone, so a sync
+/// right away could copy a half-written save. Syncs that were held are not retried one by one;
+/// instead both saves are synced once, when [`GameWatch::release`] sees that the wait is over.
+#[derive(Debug)]
+pub(crate) struct GameWatch {
+    grace: Duration,
+    is_running: fn() -> bool,
+    state: Mutex<GameState>,
+
+    /// Whether a sync was held since the last release.
+    held: AtomicBool,
+}
+
+impl GameWatch {
+    /// Watch for the game with [`game_running`], waiting `grace` after it exits.
+    pub(crate) fn new(grace: Duration) -> Self {
+        Self::with_check(grace, game_running)
+    }
+
+    /// Watch for the game with a custom check.
+    pub(crate) fn with_check(grace: Duration, is_running: fn() -> bool) -> Self {
+        Self {
+            grace,
+            is_running,
+            state: Mutex::new(GameState::NotRunning),
+            held: AtomicBool::new(false),
+        }
+    }
+
+    /// Check if a sync must wait for the game, and remember that one did.
+    pub(crate) fn holds_sync(&self) -> bool {
+        let hold = self.must_wait();
+        if hold {
+            self.held.store(true, Ordering::SeqCst);
+        }
+
+        hold
+    }
+
+    /// Check if the syncs that were held can run now.
+    ///
+    /// Returns `true` once after syncs were held, when the game has exited and the grace period
+    /// is over. The game is not checked while nothing is held.
+    pub(crate) fn release(&self) -> bool {
+        self.held.load(Ordering::SeqCst)
+            && !self.must_wait()
+            && self.held.swap(false, Ordering::SeqCst)
+    }
+
+    /// Check if syncs must wait, because the game is running or only just exited.
+    ///
+    /// The grace period starts when the game is first seen to have exited, which may be later
+    /// than it really exited, but never earlier.
+    fn must_wait(&self) -> bool {
+        let mut state = self.state.lock();
+        if (self.is_running)() {
+            if *state != GameState::Running {
+                info!("Deep Rock Galactic is running, syncing once it exits");
+                *state = GameState::Running;
+            }
+            return true;
+        }
+
+        if *state == GameState::Running {
+            debug!("Deep Rock Galactic exited, syncing in {:?}", self.grace);
+            *state = GameState::Exited(Instant::now());
+        }
+        matches!(*state, GameState::Exited(at) if at.elapsed() < self.grace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static RUNNING: AtomicBool = AtomicBool::new(false);
+
+    fn running() -> bool {
+        RUNNING.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn held_syncs_wait_for_game_exit() {
+        let game = GameWatch::with_check(Duration::from_millis(200), running);
+        assert!(!game.holds_sync());
+        assert!(!game.release());
+
+        RUNNING.store(true, Ordering::SeqCst);
+        assert!(game.holds_sync());
+        assert!(!game.release());
+
+        // The grace period starts when the exit is seen
+        RUNNING.store(false, Ordering::SeqCst);
+        assert!(!game.release());
+        assert!(game.holds_sync());
+        std::thread::sleep(Duration::from_millis(250));
+        assert!(game.release());
+
+        // Held syncs are only released once
+        assert!(!game.release());
+        assert!(!game.holds_sync());
+    }
+}
@@ -0,0 +1,427 @@
+//! A tolerant reader for GVAS, the Unreal Engine 4 save game format, to summarize a save.
+//!
+//! Only what is needed to show which save is which is read. Values that cannot be parsed are
+//! skipped, and a parse that goes wrong keeps everything that was read before it.
+//!
+//! All integers are little-endian. Strings are an `i32` length followed by that many bytes of
+//! NUL-terminated Latin-1 text, or a negative length for that many UTF-16 code units.
+//!
+//! Header:
+//!
+//! | Field              | Type     | Notes                                      |
+//! |--------------------|----------|--------------------------------------------|
+//! | Magic              | 4 bytes  | `GVAS`                                     |
+//! | Versions           | ...      | See [`SaveVersion`]                        |
+//! | Engine branch      | string   |                                            |
+//! | Custom format      | `u32`    |                                            |
+//! | Custom count       | `u32`    |                                            |
+//! | Custom versions    | ...      | `Custom count` 16 byte GUIDs and `i32`s    |
+//! | Save game class    | string   | e.g. `/Script/FSD.FSDSaveGame`             |
+//! | Properties         | ...      | A property list                            |
+//!
+//! A property list is a sequence of properties, ending with one named `None` that has no type.
+//! Each property is its name, its type name, and the `u64` size of its value, followed by a
+//! header that depends on the type, a flag byte that is followed by a 16 byte GUID when set, and
+//! the value itself:
+//!
+//! | Type               | Header                           | Value                          |
+//! |--------------------|----------------------------------|--------------------------------|
+//! | `BoolProperty`     | The value as a byte              | Nothing, the size is zero      |
+//! | `StructProperty`   | Struct type, then a 16 byte GUID | Properties, or native data     |
+//! | `ArrayProperty`    | Element type                     | Skipped                        |
+//! | `SetProperty`      | Element type                     | Skipped                        |
+//! | `MapProperty`      | Key type, then value type        | Skipped                        |
+//! | `ByteProperty`     | Enum type, or `None`             | A byte, or an enum name        |
+//! | `EnumProperty`     | Enum type                        | Skipped                        |
+//! | Anything else      | Nothing                          | Numbers and strings are read   |
+
+use crate::saves::{SaveVersion, GVAS_MAGIC};
+use std::collections::BTreeMap;
+
+/// Name of the property that ends a property list.
+const END_OF_PROPERTIES: &str = "None";
+
+/// Deepest nesting of structs that is read. Deeper structs are skipped.
+const MAX_DEPTH: usize = 8;
+
+/// Struct types with a native layout instead of a property list.
+const NATIVE_STRUCTS: [&str; 11] = [
+    "Color",
+    "DateTime",
+    "Guid",
+    "IntPoint",
+    "IntVector",
+    "LinearColor",
+    "Quat",
+    "Rotator",
+    "Timespan",
+    "Vector",
+    "Vector2D",
+];
+
+/// A summary of a save file, from [`Steeve::save_info`](crate::Steeve::save_info).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaveInfo {
+    /// The format and engine versions in the header.
+    pub version: SaveVersion,
+
+    /// Class of the save game object, e.g. `/Script/FSD.FSDSaveGame`.
+    pub save_class: String,
+
+    /// The player's credits, when the save has them.
+    pub credits: Option<i64>,
+
+    /// Every number, string, and flag found in the save, by property path.
+    ///
+    /// Properties of structs are nested under the struct, e.g. `Resources.Credits`. Arrays,
+    /// sets, and maps are skipped.
+    pub fields: BTreeMap<String, SaveValue>,
+}
+
+/// A simple value read from a save, see [`SaveInfo::fields`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum SaveValue {
+    /// A `BoolProperty`.
+    Bool(bool),
+
+    /// Any integer property, or a `ByteProperty` without an enum.
+    Int(i64),
+
+    /// A `FloatProperty` or `DoubleProperty`.
+    Float(f64),
+
+    /// A `StrProperty`, `NameProperty`, or the name of an enum value.
+    Str(String),
+}
+
+impl SaveInfo {
+    /// Parse a save file.
+    ///
+    /// Returns `None` if the header is incomplete. Properties that cannot be parsed end the
+    /// parse, keeping the values read before them.
+    pub(crate) fn parse(bytes: &[u8]) -> Option<Self> {
+        let version = SaveVersion::parse(bytes)?;
+
+        let mut reader = Reader::new(bytes);
+        reader.skip(GVAS_MAGIC.len() + 8)?;
+        if version.save_game >= SaveVersion::UE5_SAVE_GAME {
+            reader.skip(4)?;
+        }
+        reader.skip(10)?;
+        reader.string()?;
+        reader.u32()?;
+        let custom_versions = usize::try_from(reader.u32()?).ok()?;
+        reader.skip(custom_versions.checked_mul(20)?)?;
+        let save_class = reader.string()?;
+
+        let mut fields = BTreeMap::new();
+        let _ = read_properties(&mut reader, "", 0, &mut fields);
+
+        // The shallowest `Credits` wins, in case a nested struct has its own
+        let credits = fields
+            .iter()
+            .filter(|(path, _)| path.rsplit('.').next() == Some("Credits"))
+            .min_by_key(|(path, _)| path.matches('.').count())
+            .and_then(|(_, value)| match value {
+                SaveValue::Int(credits) => Some(*credits),
+                _ => None,
+            });
+
+        Some(Self {
+            version,
+            save_class,
+            credits,
+            fields,
+        })
+    }
+}
+
+/// Read a property list up to its end, adding the simple values to `fields` under `prefix`.
+///
+/// Returns `None` when the list cannot be parsed. The values read so far are kept.
+fn read_properties(
+    reader: &mut Reader<'_>,
+    prefix: &str,
+    depth: usize,
+    fields: &mut BTreeMap<String, SaveValue>,
+) -> Option<()> {
+    loop {
+        let name = reader.string()?;
+        if name == END_OF_PROPERTIES {
+            return Some(());
+        }
+        let kind = reader.string()?;
+        let size = usize::try_from(reader.u64()?).ok()?;
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}.{name}")
+        };
+
+        let value = match kind.as_str() {
+            "BoolProperty" => {
+                let value = reader.u8()?;
+                reader.property_guid()?;
+
+                Some(SaveValue::Bool(value != 0))
+            }
+            "StructProperty" => {
+                let struct_type = reader.string()?;
+                reader.skip(16)?;
+                reader.property_guid()?;
+                let data = reader.take(size)?;
+
+                // A struct that cannot be parsed does not stop the parse of its parent
+                if depth < MAX_DEPTH && !NATIVE_STRUCTS.contains(&struct_type.as_str()) {
+                    let _ = read_properties(&mut Reader::new(data), &path, depth + 1, fields);
+                }
+
+                None
+            }
+            "ByteProperty" => {
+                let enum_type = reader.string()?;
+                reader.property_guid()?;
+                let data = reader.take(size)?;
+
+                match (enum_type.as_str(), data) {
+                    ("None", &[byte]) => Some(SaveValue::Int(byte.into())),
+                    _ => Reader::new(data).string().map(SaveValue::Str),
+                }
+            }
+            "ArrayProperty" | "SetProperty" | "EnumProperty" => {
+                reader.string()?;
+                reader.property_guid()?;
+                reader.skip(size)?;
+
+                None
+            }
+            "MapProperty" => {
+                reader.string()?;
+                reader.string()?;
+                reader.property_guid()?;
+                reader.skip(size)?;
+
+                None
+            }
+            _ => {
+                reader.property_guid()?;
+
+                simple_value(&kind, reader.take(size)?)
+            }
+        };
+
+        if let Some(value) = value {
+            fields.entry(path).or_insert(value);
+        }
+    }
+}
+
+/// Read the value of a number or string property, or `None` for any other type.
+fn simple_value(kind: &str, data: &[u8]) -> Option<SaveValue> {
+    let value = match kind {
+        "IntProperty" => SaveValue::Int(i32::from_le_bytes(data.try_into().ok()?).into()),
+        "Int64Property" => SaveValue::Int(i64::from_le_bytes(data.try_into().ok()?)),
+        "UInt32Property" => SaveValue::Int(u32::from_le_bytes(data.try_into().ok()?).into()),
+        "UInt64Property" => {
+            SaveValue::Int(i64::try_from(u64::from_le_bytes(data.try_into().ok()?)).ok()?)
+        }
+        "FloatProperty" => SaveValue::Float(f32::from_le_bytes(data.try_into().ok()?).into()),
+        "DoubleProperty" => SaveValue::Float(f64::from_le_bytes(data.try_into().ok()?)),
+        "StrProperty" | "NameProperty" => SaveValue::Str(Reader::new(data).string()?),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// A little-endian reader over a byte slice, with Unreal Engine strings.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn take(&mut self, size: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset.checked_add(size)?)?;
+        self.offset += size;
+
+        Some(bytes)
+    }
+
+    fn skip(&mut self, size: usize) -> Option<()> {
+        self.take(size).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)?.try_into().ok().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)?.try_into().ok().map(u64::from_le_bytes)
+    }
+
+    /// Skip the optional GUID after a property header.
+    fn property_guid(&mut self) -> Option<()> {
+        if self.u8()? != 0 {
+            self.skip(16)?;
+        }
+
+        Some(())
+    }
+
+    /// Read a length-prefixed string, in Latin-1 or UTF-16, without its NUL terminator.
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as i32;
+        let mut text = if len < 0 {
+            let units = self
+                .take(usize::try_from(len.unsigned_abs()).ok()?.checked_mul(2)?)?
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect::<Vec<_>>();
+
+            String::from_utf16(&units).ok()?
+        } else {
+            let bytes = self.take(usize::try_from(len).ok()?)?;
+
+            bytes.iter().copied().map(char::from).collect()
+        };
+        if text.ends_with('\0') {
+            text.pop();
+        }
+
+        Some(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(bytes: &mut Vec<u8>, s: &str) {
+        bytes.extend_from_slice(&(s.len() as i32 + 1).to_le_bytes());
+        bytes.extend_from_slice(s.as_bytes());
+        bytes.push(0);
+    }
+
+    fn wide_string(bytes: &mut Vec<u8>, s: &str) {
+        let units = s.encode_utf16().chain([0]).collect::<Vec<_>>();
+        bytes.extend_from_slice(&(-(units.len() as i32)).to_le_bytes());
+        for unit in units {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+
+    /// Append a property header, without the type-specific part.
+    fn property(bytes: &mut Vec<u8>, name: &str, kind: &str, size: usize) {
+        string(bytes, name);
+        string(bytes, kind);
+        bytes.extend_from_slice(&(size as u64).to_le_bytes());
+    }
+
+    fn int(bytes: &mut Vec<u8>, name: &str, value: i32) {
+        property(bytes, name, "IntProperty", 4);
+        bytes.push(0);
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn header() -> Vec<u8> {
+        let mut bytes = GVAS_MAGIC.to_vec();
+        for version in [2_u32, 522] {
+            bytes.extend_from_slice(&version.to_le_bytes());
+        }
+        for part in [4_u16, 27, 2] {
+            bytes.extend_from_slice(&part.to_le_bytes());
+        }
+        bytes.extend_from_slice(&18319896_u32.to_le_bytes());
+        string(&mut bytes, "++UE4+Release-4.27");
+        bytes.extend_from_slice(&3_u32.to_le_bytes());
+        bytes.extend_from_slice(&1_u32.to_le_bytes());
+        bytes.extend_from_slice(&[0xab; 16]);
+        bytes.extend_from_slice(&7_i32.to_le_bytes());
+        string(&mut bytes, "/Script/FSD.FSDSaveGame");
+        bytes
+    }
+
+    #[test]
+    fn parse_save_info() {
+        let mut bytes = header();
+        int(&mut bytes, "Credits", 123_456);
+
+        // A struct with its own property list, and a nested `Credits` that is not the player's
+        let mut resources = Vec::new();
+        property(&mut resources, "Morkite", "FloatProperty", 4);
+        resources.push(0);
+        resources.extend_from_slice(&42.5_f32.to_le_bytes());
+        int(&mut resources, "Credits", 7);
+        string(&mut resources, END_OF_PROPERTIES);
+        property(&mut bytes, "Resources", "StructProperty", resources.len());
+        string(&mut bytes, "ResourcesSave");
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.push(0);
+        bytes.extend_from_slice(&resources);
+
+        // A native struct, an array, and an unknown type are skipped
+        property(&mut bytes, "LastPlayed", "StructProperty", 8);
+        string(&mut bytes, "DateTime");
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.push(0);
+        bytes.extend_from_slice(&[0xff; 8]);
+        property(&mut bytes, "Perks", "ArrayProperty", 12);
+        string(&mut bytes, "IntProperty");
+        bytes.push(0);
+        bytes.extend_from_slice(&[0xff; 12]);
+        property(&mut bytes, "Mystery", "SoftObjectProperty", 3);
+        bytes.push(1);
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.extend_from_slice(&[0xff; 3]);
+
+        property(&mut bytes, "HasPromoted", "BoolProperty", 0);
+        bytes.extend_from_slice(&[1, 0]);
+        let mut name = Vec::new();
+        wide_string(&mut name, "Kärl");
+        property(&mut bytes, "Name", "StrProperty", name.len());
+        bytes.push(0);
+        bytes.extend_from_slice(&name);
+
+        // A truncated property keeps everything before it
+        property(&mut bytes, "Broken", "IntProperty", 4);
+        bytes.extend_from_slice(&[0, 1]);
+
+        let info = SaveInfo::parse(&bytes).unwrap();
+        assert_eq!(info.version.engine, (4, 27, 2));
+        assert_eq!(info.save_class, "/Script/FSD.FSDSaveGame");
+        assert_eq!(info.credits, Some(123_456));
+        assert_eq!(
+            info.fields.into_iter().collect::<Vec<_>>(),
+            [
+                ("Credits".to_string(), SaveValue::Int(123_456)),
+                ("HasPromoted".to_string(), SaveValue::Bool(true)),
+                ("Name".to_string(), SaveValue::Str("Kärl".to_string())),
+                ("Resources.Credits".to_string(), SaveValue::Int(7)),
+                ("Resources.Morkite".to_string(), SaveValue::Float(42.5)),
+            ],
+        );
+    }
+
+    #[test]
+    fn incomplete_header_is_rejected() {
+        let bytes = header();
+        assert!(SaveInfo::parse(&bytes[..bytes.len() - 4]).is_none());
+        assert!(SaveInfo::parse(b"GVAS").is_none());
+
+        // A save without properties is still a save
+        let info = SaveInfo::parse(&bytes).unwrap();
+        assert_eq!(info.credits, None);
+        assert!(info.fields.is_empty());
+    }
+}
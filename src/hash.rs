@@ -0,0 +1,371 @@
+//! Content hashing for save and backup comparison.
+
+use crate::store::{SaveStore, StoreEntry};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Size of the buffer used to stream files through the hasher.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// The hash algorithm used to compare saves with their backups.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum HashAlgo {
+    /// BLAKE3: fast, and cryptographically strong.
+    #[default]
+    Blake3,
+
+    /// SHA-256: slower, for those who prefer a long-established standard.
+    Sha256,
+
+    /// XXH3 (128-bit): fastest, but not collision resistant against crafted inputs.
+    XxHash,
+}
+
+impl HashAlgo {
+    /// Create a new hasher for this algorithm.
+    fn hasher(self) -> Box<dyn ContentHasher> {
+        match self {
+            Self::Blake3 => Box::new(blake3::Hasher::new()),
+            Self::Sha256 => Box::<sha2::Sha256>::default(),
+            Self::XxHash => Box::new(xxhash_rust::xxh3::Xxh3::new()),
+        }
+    }
+
+    /// Hash everything that can be read from `reader`.
+    pub(crate) fn hash_reader(self, mut reader: impl Read) -> std::io::Result<Vec<u8>> {
+        let mut hasher = self.hasher();
+        let mut buffer = vec![0; BUFFER_SIZE];
+
+        loop {
+            match reader.read(&mut buffer)? {
+                0 => break,
+                size => hasher.update(&buffer[..size]),
+            }
+        }
+
+        Ok(hasher.finalize())
+    }
+}
+
+/// Name of the manifest file that keeps the hashes of the backups in a directory.
+pub(crate) const HASH_MANIFEST: &str = ".steeve-manifest";
+
+/// First field of the manifest lines that keep the hash of a save after its last sync.
+const SYNCED_LINE: &str = "synced";
+
+/// Hashes of files that rarely change, e.g. backups, so they are not read again for every
+/// comparison.
+///
+/// A hash is reused while the file keeps its size and modify time, and was made with the same
+/// algorithm. The hashes for each directory are kept in a [`HASH_MANIFEST`] file, so they also
+/// survive restarts.
+///
+/// The cache also remembers the hash of each save after it was last synced, for telling which
+/// saves changed since. These are kept in the manifest of the backup directory for the side
+/// that was synced to last.
+#[derive(Debug, Default)]
+pub(crate) struct HashCache {
+    hashes: Mutex<HashMap<PathBuf, CachedHash>>,
+
+    /// Hashes of saves after their last sync, by save path.
+    synced: Mutex<HashMap<PathBuf, SyncedHash>>,
+
+    /// Directories with changes that are not saved in their manifest yet.
+    dirty: Mutex<HashSet<PathBuf>>,
+}
+
+/// A hash in the [`HashCache`], with what it was made from.
+#[derive(Debug, Eq, PartialEq)]
+struct CachedHash {
+    algo: HashAlgo,
+    len: u64,
+    modified: SystemTime,
+    hash: Vec<u8>,
+}
+
+/// The hash of a save after its last sync.
+#[derive(Debug, Eq, PartialEq)]
+struct SyncedHash {
+    /// Directory of the manifest that keeps this hash.
+    dir: PathBuf,
+    algo: HashAlgo,
+    hash: Vec<u8>,
+}
+
+impl HashCache {
+    /// Get the hash of a file, or compute it with `hash` and remember it.
+    pub(crate) fn hash(
+        &self,
+        algo: HashAlgo,
+        entry: &StoreEntry,
+        hash: impl FnOnce() -> std::io::Result<Vec<u8>>,
+    ) -> std::io::Result<Vec<u8>> {
+        if let Some(cached) = self.hashes.lock().get(&entry.path) {
+            if cached.algo == algo && cached.len == entry.len && cached.modified == entry.modified {
+                return Ok(cached.hash.clone());
+            }
+        }
+
+        let hash = hash()?;
+        let cached = CachedHash {
+            algo,
+            len: entry.len,
+            modified: entry.modified,
+            hash: hash.clone(),
+        };
+        self.hashes.lock().insert(entry.path.clone(), cached);
+        self.mark_dirty(&entry.path);
+
+        Ok(hash)
+    }
+
+    /// Forget the hashes of files for which `keep` returns false.
+    pub(crate) fn retain(&self, mut keep: impl FnMut(&Path) -> bool) {
+        self.hashes.lock().retain(|path, _| {
+            let keep = keep(path);
+            if !keep {
+                self.mark_dirty(path);
+            }
+            keep
+        });
+    }
+
+    /// Get the hash of the save at `path` after its last sync, if it was made with `algo`.
+    pub(crate) fn synced(&self, algo: HashAlgo, path: &Path) -> Option<Vec<u8>> {
+        self.synced
+            .lock()
+            .get(path)
+            .filter(|synced| synced.algo == algo)
+            .map(|synced| synced.hash.clone())
+    }
+
+    /// Remember the hash of the save at `path` after a sync, in the manifest in `dir`.
+    ///
+    /// The manifest only needs to be written again when this changes what is remembered.
+    pub(crate) fn set_synced(&self, dir: &Path, algo: HashAlgo, path: &Path, hash: Vec<u8>) {
+        let synced = SyncedHash {
+            dir: dir.to_path_buf(),
+            algo,
+            hash,
+        };
+        let mut all_synced = self.synced.lock();
+        if all_synced.get(path) == Some(&synced) {
+            return;
+        }
+        let old = all_synced.insert(path.to_path_buf(), synced);
+        drop(all_synced);
+
+        let mut dirty = self.dirty.lock();
+        if let Some(old) = old {
+            dirty.insert(old.dir);
+        }
+        dirty.insert(dir.to_path_buf());
+    }
+
+    /// Forget the hash of the save at `path` after its last sync, e.g. when it was restored.
+    pub(crate) fn forget_synced(&self, path: &Path) {
+        if let Some(old) = self.synced.lock().remove(path) {
+            self.dirty.lock().insert(old.dir);
+        }
+    }
+
+    /// Load the manifest in `dir`, keeping only the hashes of files that are still unchanged.
+    ///
+    /// Files that were added since are hashed when they are first needed. A missing or
+    /// unreadable manifest is not an error; the hashes are computed again.
+    pub(crate) fn load(&self, store: &dyn SaveStore, dir: &Path) {
+        let Ok(bytes) = store.read(&dir.join(HASH_MANIFEST)) else {
+            return;
+        };
+        let files = store.list(dir).unwrap_or_default();
+
+        let mut hashes = self.hashes.lock();
+        let mut dropped = false;
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            if let Some((path, algo, hash)) = parse_synced_line(line) {
+                let dir = dir.to_path_buf();
+                let synced = SyncedHash { dir, algo, hash };
+                self.synced.lock().insert(path, synced);
+                continue;
+            }
+            let Some((filename, cached)) = parse_manifest_line(line) else {
+                dropped = true;
+                continue;
+            };
+
+            let path = dir.join(filename);
+            let unchanged = files.iter().any(|file| {
+                file.path == path && file.len == cached.len && file.modified == cached.modified
+            });
+            if unchanged {
+                hashes.insert(path, cached);
+            } else {
+                dropped = true;
+            }
+        }
+        drop(hashes);
+
+        if dropped {
+            self.dirty.lock().insert(dir.to_path_buf());
+        }
+    }
+
+    /// Write the manifest in `dir`, if any of its hashes changed since it was last written.
+    pub(crate) fn save(&self, store: &dyn SaveStore, dir: &Path) -> std::io::Result<()> {
+        if !self.dirty.lock().remove(dir) {
+            return Ok(());
+        }
+
+        let mut manifest = String::new();
+        for (path, cached) in self.hashes.lock().iter() {
+            if path.parent() != Some(dir) {
+                continue;
+            }
+            if let Some(line) = manifest_line(path, cached) {
+                manifest.push_str(&line);
+                manifest.push('\n');
+            }
+        }
+        for (path, synced) in self.synced.lock().iter() {
+            if synced.dir == dir {
+                let hash = to_hex(&synced.hash);
+                let line = format!(
+                    "{SYNCED_LINE}\t{:?}\t{hash}\t{}\n",
+                    synced.algo,
+                    path.display()
+                );
+                manifest.push_str(&line);
+            }
+        }
+
+        store.write(&dir.join(HASH_MANIFEST), manifest.as_bytes())
+    }
+
+    /// Remember that the manifest for the directory containing `path` needs to be written.
+    fn mark_dirty(&self, path: &Path) {
+        if let Some(dir) = path.parent() {
+            self.dirty.lock().insert(dir.to_path_buf());
+        }
+    }
+}
+
+/// Format a manifest line: file name, algorithm, size, modify time in nanoseconds, and hash.
+fn manifest_line(path: &Path, cached: &CachedHash) -> Option<String> {
+    let filename = path.file_name()?.to_str()?;
+    let modified = cached
+        .modified
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    let hash = to_hex(&cached.hash);
+
+    Some(format!(
+        "{filename}\t{:?}\t{}\t{modified}\t{hash}",
+        cached.algo, cached.len,
+    ))
+}
+
+/// Parse a line written by [`manifest_line`].
+fn parse_manifest_line(line: &str) -> Option<(&str, CachedHash)> {
+    let mut fields = line.split('\t');
+    let filename = fields.next()?;
+    let algo = parse_algo(fields.next()?)?;
+    let len = fields.next()?.parse().ok()?;
+    let nanos: u64 = fields.next()?.parse().ok()?;
+    let hex = fields.next()?;
+    if fields.next().is_some() {
+        return None;
+    }
+    let hash = parse_hex(hex)?;
+
+    Some((
+        filename,
+        CachedHash {
+            algo,
+            len,
+            modified: SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos),
+            hash,
+        },
+    ))
+}
+
+/// Parse a line written by [`HashCache::save`] for a synced save: the path, algorithm, and hash.
+///
+/// The path comes last, since it is the only field that may contain tabs.
+fn parse_synced_line(line: &str) -> Option<(PathBuf, HashAlgo, Vec<u8>)> {
+    let mut fields = line.splitn(4, '\t');
+    if fields.next()? != SYNCED_LINE {
+        return None;
+    }
+    let algo = parse_algo(fields.next()?)?;
+    let hash = parse_hex(fields.next()?)?;
+    let path = PathBuf::from(fields.next()?);
+
+    Some((path, algo, hash))
+}
+
+/// Parse the name of a hash algorithm, as written with its `Debug` format.
+fn parse_algo(name: &str) -> Option<HashAlgo> {
+    match name {
+        "Blake3" => Some(HashAlgo::Blake3),
+        "Sha256" => Some(HashAlgo::Sha256),
+        "XxHash" => Some(HashAlgo::XxHash),
+        _ => None,
+    }
+}
+
+/// Format bytes as lowercase hex.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Parse bytes formatted by [`to_hex`].
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// The common interface of all supported hashers.
+trait ContentHasher {
+    fn update(&mut self, bytes: &[u8]);
+    fn finalize(self: Box<Self>) -> Vec<u8>;
+}
+
+impl ContentHasher for blake3::Hasher {
+    fn update(&mut self, bytes: &[u8]) {
+        blake3::Hasher::update(self, bytes);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        blake3::Hasher::finalize(&self).as_bytes().to_vec()
+    }
+}
+
+impl ContentHasher for sha2::Sha256 {
+    fn update(&mut self, bytes: &[u8]) {
+        sha2::Digest::update(self, bytes);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        sha2::Digest::finalize(*self).to_vec()
+    }
+}
+
+impl ContentHasher for xxhash_rust::xxh3::Xxh3 {
+    fn update(&mut self, bytes: &[u8]) {
+        xxhash_rust::xxh3::Xxh3::update(self, bytes);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.digest128().to_le_bytes().to_vec()
+    }
+}
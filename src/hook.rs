@@ -0,0 +1,163 @@
+//! User commands that run after a successful sync.
+
+use crate::{Direction, Side};
+use log::{info, warn};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How often a running command is checked for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A command template that runs after each successful sync.
+///
+/// The template is split on whitespace into the program and its arguments, without a shell.
+/// The placeholders `{side}`, `{file}`, and `{direction}` are replaced in every argument.
+#[derive(Clone, Debug)]
+pub(crate) struct SyncCommand {
+    template: Vec<String>,
+    timeout: Duration,
+}
+
+impl SyncCommand {
+    /// Create a sync command, or `None` when the template is blank.
+    pub(crate) fn new(template: &str, timeout: Duration) -> Option<Self> {
+        let template = template
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        (!template.is_empty()).then_some(Self { template, timeout })
+    }
+
+    /// Run the command in the background for a save that was copied to `side` at `file`.
+    pub(crate) fn spawn(&self, side: Side, file: &Path, direction: Direction) {
+        let args = self.args(side, file, direction);
+        let timeout = self.timeout;
+
+        std::thread::spawn(move || run(&args, timeout));
+    }
+
+    /// Get the program and its arguments, with the placeholders replaced.
+    fn args(&self, side: Side, file: &Path, direction: Direction) -> Vec<String> {
+        let side = match side {
+            Side::Steam => "steam",
+            Side::Xbox => "xbox",
+        };
+        let direction = match direction {
+            Direction::SteamToXbox => "steam-to-xbox",
+            Direction::XboxToSteam => "xbox-to-steam",
+        };
+        let file = file.to_string_lossy();
+
+        self.template
+            .iter()
+            .map(|arg| {
+                arg.replace("{side}", side)
+                    .replace("{file}", &file)
+                    .replace("{direction}", direction)
+            })
+            .collect()
+    }
+}
+
+/// Run a command and log its output, killing it if it runs for longer than `timeout`.
+///
+/// Failures are logged, and never interrupt syncing. Returns the exit status when the command
+/// finished in time.
+fn run(args: &[String], timeout: Duration) -> Option<ExitStatus> {
+    let (program, args) = args.split_first()?;
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            warn!("Unable to run sync command {program:?}: {err}");
+            return None;
+        }
+    };
+
+    // Read the output while waiting, so a chatty command cannot fill the pipes and stall
+    let stdout = child.stdout.take().map(read_to_end);
+    let stderr = child.stderr.take().map(read_to_end);
+    let status = wait_timeout(&mut child, timeout);
+
+    for (name, output) in [("stdout", stdout), ("stderr", stderr)] {
+        let output = output
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        let output = output.trim();
+        if !output.is_empty() {
+            info!("Sync command {program:?} {name}: {output}");
+        }
+    }
+
+    match status {
+        Some(status) if status.success() => (),
+        Some(status) => warn!("Sync command {program:?} failed: {status}"),
+        None => warn!("Sync command {program:?} timed out after {timeout:?}"),
+    }
+
+    status
+}
+
+/// Read everything from `reader` on another thread.
+fn read_to_end(mut reader: impl Read + Send + 'static) -> JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = reader.read_to_end(&mut bytes);
+
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// Wait for the child to exit, or kill it after `timeout`.
+fn wait_timeout(child: &mut Child, timeout: Duration) -> Option<ExitStatus> {
+    let start = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Some(status),
+            Ok(None) if start.elapsed() < timeout => std::thread::sleep(POLL_INTERVAL),
+            _ => break,
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_replaced() {
+        let command = SyncCommand::new("cp {file} /backup/{side}-{direction}", Duration::ZERO);
+        let args = command
+            .unwrap()
+            .args(Side::Xbox, Path::new("save.sav"), Direction::SteamToXbox);
+
+        assert_eq!(args, ["cp", "save.sav", "/backup/xbox-steam-to-xbox"]);
+        assert!(SyncCommand::new("  ", Duration::ZERO).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_are_time_limited() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let timeout = Duration::from_millis(200);
+
+        assert!(run(&args(&["true"]), timeout).is_some_and(|status| status.success()));
+        assert!(run(&args(&["false"]), timeout).is_some_and(|status| !status.success()));
+        assert_eq!(run(&args(&["sleep", "5"]), timeout), None);
+        assert_eq!(run(&args(&["steeve-no-such-command"]), timeout), None);
+    }
+}
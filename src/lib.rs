@@ -9,28 +9,100 @@
 //! files updated most recently.
 #![deny(clippy::all)]
 
-use crate::saves::{SaveError, SteamSave, SteeveSave, XboxSave};
+pub use crate::builder::SteeveBuilder;
+pub use crate::config::Config;
+pub use crate::game::game_running;
+use crate::game::GameWatch;
+pub use crate::gvas::{SaveInfo, SaveValue};
+pub use crate::hash::HashAlgo;
+use crate::hook::SyncCommand;
+use crate::remote::RemoteSave;
+pub use crate::saves::{discover_steam_save_dirs, discover_xbox_save_dirs};
+use crate::saves::{resolve_links, SaveOptions, SteamSave, SteeveSave, Trash, XboxSave};
+pub use crate::saves::{BackupEntry, BackupStats, LocateResult, SaveError, SaveVersion};
+pub use crate::self_test::{DirReport, SelfTestReport, SelfTestStage, StageStatus};
+pub use crate::wgs::IndexReport;
 use directories::ProjectDirs;
-use log::{debug, warn};
-use notify_debouncer_mini::new_debouncer;
-use notify_debouncer_mini::notify::{Error as NotifyError, RecommendedWatcher, RecursiveMode};
-use notify_debouncer_mini::{DebounceEventResult, DebouncedEvent, Debouncer};
+use log::{debug, info, warn};
+use notify_debouncer_mini::notify::{self, Error as NotifyError, RecursiveMode, Watcher};
+use notify_debouncer_mini::notify::{PollWatcher, RecommendedWatcher};
+use notify_debouncer_mini::{new_debouncer, new_debouncer_opt};
+use notify_debouncer_mini::{DebounceEventHandler, DebounceEventResult, DebouncedEvent, Debouncer};
+use parking_lot::{Mutex, RwLock};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::time::Duration;
+use std::fs::{File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant, SystemTime};
 use thiserror::Error;
 
+mod builder;
+mod bundle;
+mod config;
+mod game;
+mod gvas;
+mod hash;
+mod hook;
 pub mod logger;
+mod remote;
 mod saves;
+mod self_test;
+mod store;
+#[cfg(test)]
+mod testing;
+mod wgs;
 
 /// All the ways in which [`Steeve`] can fail.
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Max backups must be > 0")]
+    #[error("Max backups must be > 0, and no more than the max backups limit")]
     MaxBackups,
 
+    #[error("Max trash must be > 0")]
+    MaxTrash,
+
+    #[error("Poll interval must be > 0")]
+    PollInterval,
+
+    #[error("Syncing to Steam and syncing to Xbox cannot both be disabled")]
+    NoSyncDirection,
+
+    #[error("Syncing to {0:?} is disabled")]
+    SyncDisabled(Side),
+
+    #[error("Steam and Xbox backup directories must be different from each other and the trash")]
+    BackupDirs,
+
+    #[error("Backup directory {0:?} is inside save directory {1:?}")]
+    BackupInsideSaveDir(PathBuf, PathBuf),
+
     #[error("Could not find home directory")]
     HomeDir,
 
+    #[error("Config file error {0:?}: {1}")]
+    ConfigIo(PathBuf, std::io::Error),
+
+    #[error("Invalid config file {0:?}: {1}")]
+    BadConfig(PathBuf, String),
+
+    #[error("{0:?} save directory does not exist and cannot be created: {1:?}")]
+    SaveDir(Side, PathBuf),
+
+    #[error("Steeve is already running")]
+    AlreadyRunning,
+
+    #[error("Invalid backup bundle: {0}")]
+    BadBundle(String),
+
+    #[error("Backup already exists, refusing to overwrite it: {0:?}")]
+    BundleClobber(PathBuf),
+
+    #[error("Backup bundle error: {0}")]
+    Bundle(#[from] zip::result::ZipError),
+
     #[error("Save error")]
     Save(#[from] SaveError),
 
@@ -38,140 +110,3379 @@ pub enum Error {
     Watch(#[from] NotifyError),
 }
 
+/// The game editions that Steeve synchronizes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Side {
+    /// The Steam edition.
+    Steam,
+
+    /// The Xbox (Microsoft Store) edition.
+    Xbox,
+}
+
+impl Side {
+    /// Get the side that this side's saves are synced to.
+    pub(crate) fn other(self) -> Self {
+        match self {
+            Self::Steam => Self::Xbox,
+            Self::Xbox => Self::Steam,
+        }
+    }
+}
+
+/// Whether saves are backed up before they are overwritten.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BackupPolicy {
+    /// Back up the destination save before every sync.
+    #[default]
+    Enabled,
+
+    /// Never back up saves; sync only.
+    ///
+    /// **Warning:** Without backups there is no way to roll back a bad sync. Only use this when
+    /// saves are already backed up by other means.
+    Disabled,
+}
+
+/// How backups are arranged in the backup directory.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BackupLayout {
+    /// Each side has its own subdirectory, named by [`SteeveBuilder::steam_backup_subdir`] and
+    /// [`SteeveBuilder::xbox_backup_subdir`].
+    #[default]
+    PerSide,
+
+    /// Both sides share the backup directory. Backups are told apart by the side in their file
+    /// names.
+    Flat,
+}
+
+/// Which save slots take part in syncing.
+///
+/// A slot is named by its save file name without the extension, e.g. `76561197960287930_Player`
+/// for Steam, or the 32 digit hex file name for Xbox. Names are compared ignoring ASCII case.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum SlotFilter {
+    /// Sync all slots.
+    #[default]
+    All,
+
+    /// Only sync the named slots. An empty list syncs all slots.
+    Allow(Vec<String>),
+
+    /// Sync all slots except the named ones.
+    Deny(Vec<String>),
+}
+
+impl SlotFilter {
+    /// Check if the named slot takes part in syncing.
+    pub fn includes(&self, slot: &str) -> bool {
+        let listed = |names: &[String]| names.iter().any(|name| name.eq_ignore_ascii_case(slot));
+
+        match self {
+            Self::All => true,
+            Self::Allow(names) => names.is_empty() || listed(names),
+            Self::Deny(names) => !listed(names),
+        }
+    }
+}
+
+/// How the time a backup was made is written at the start of its file name.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BackupTimestamp {
+    /// Seconds since the Unix epoch, e.g. `1704207845`.
+    #[default]
+    Unix,
+
+    /// Sortable, human-readable ISO 8601 UTC time, e.g. `2024-01-02T15-04-05Z`.
+    ///
+    /// Colons are replaced with dashes because they are not allowed in Windows file names.
+    Iso8601,
+}
+
+/// The direction in which a save is synced.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    /// The Steam save is copied over the Xbox save.
+    SteamToXbox,
+
+    /// The Xbox save is copied over the Steam save.
+    XboxToSteam,
+}
+
+/// A sync that is about to overwrite a save.
+#[derive(Clone, Debug)]
+pub struct SyncEvent {
+    /// The direction of the sync.
+    pub direction: Direction,
+
+    /// The save file being copied.
+    pub from: PathBuf,
+
+    /// The save file about to be overwritten.
+    pub to: PathBuf,
+}
+
+/// Something that Steeve did, see [`Steeve::on_event`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SteeveEvent {
+    /// A save was backed up before it was overwritten or restored over.
+    BackupCreated {
+        /// The side of the save that was backed up.
+        side: Side,
+
+        /// The new backup.
+        path: PathBuf,
+
+        /// Whether this is a dry run, see [`SteeveBuilder::dry_run`]; the backup was not written.
+        dry_run: bool,
+    },
+
+    /// A save was not backed up, because an identical backup already exists.
+    DedupSkipped {
+        /// The side of the save.
+        side: Side,
+
+        /// The save that is already backed up.
+        path: PathBuf,
+    },
+
+    /// A save was copied over the other side's save.
+    SaveCopied {
+        /// The direction of the sync.
+        direction: Direction,
+
+        /// The save file that was copied.
+        from: PathBuf,
+
+        /// The save file that was overwritten.
+        to: PathBuf,
+
+        /// Whether this is a dry run, see [`SteeveBuilder::dry_run`]; the save was not copied.
+        dry_run: bool,
+    },
+
+    /// A file system event could not be synced.
+    SyncError(SaveError),
+
+    /// A side's watcher reported errors, and events may have been lost.
+    ///
+    /// The watch is set up again, and a missing save directory is waited for.
+    WatchError {
+        /// The side being watched.
+        side: Side,
+
+        /// What the watcher reported.
+        message: String,
+    },
+}
+
+/// Delivers [`SteeveEvent`]s to the handler registered with [`Steeve::on_event`].
+#[derive(Default)]
+pub(crate) struct EventSink {
+    handler: Mutex<Option<EventHandler>>,
+}
+
+/// A handler for [`SteeveEvent`]s.
+type EventHandler = Arc<dyn Fn(SteeveEvent) + Send + Sync>;
+
+impl EventSink {
+    /// Send an event to the handler, if there is one.
+    ///
+    /// The handler is called without holding its lock, so it may replace itself, and events
+    /// from other threads do not wait for it.
+    pub(crate) fn emit(&self, event: SteeveEvent) {
+        let handler = self.handler.lock().clone();
+        if let Some(handler) = handler {
+            handler(event);
+        }
+    }
+}
+
+impl Debug for EventSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let handler = self.handler.lock().as_ref().map(|_| "EventHandler");
+
+        f.debug_struct("EventSink")
+            .field("handler", &handler)
+            .finish()
+    }
+}
+
+/// The decision made by a pre-sync hook, see [`Steeve::set_pre_sync`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncDecision {
+    /// Go ahead with the copy.
+    Proceed,
+
+    /// Abort the copy. The destination may already have been backed up.
+    Skip,
+
+    /// Abort the copy for now and try again later.
+    Defer,
+}
+
+/// The result of backing up one side with [`Steeve::backup_both`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BackupOutcome {
+    /// A new backup was created at this path.
+    Created(PathBuf),
+
+    /// The save is identical to an existing backup, so no new backup was needed.
+    Duplicate,
+
+    /// There is no save to back up.
+    NoSave,
+}
+
+/// The results of [`Steeve::backup_both`] for each side.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackupPair {
+    pub steam: BackupOutcome,
+    pub xbox: BackupOutcome,
+}
+
+/// The result of a sync that went ahead without errors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncOutcome {
+    /// The save was copied.
+    Copied {
+        /// Number of bytes copied.
+        bytes: u64,
+
+        /// How long the copy took.
+        duration: Duration,
+
+        /// Whether the destination was read back and matched the source, see
+        /// [`SteeveBuilder::verify_syncs`].
+        verified: bool,
+    },
+
+    /// The copy was skipped by the pre-sync hook.
+    Skipped,
+
+    /// The source is newer, but has the same contents as the destination, so nothing was
+    /// copied or backed up.
+    Identical,
+
+    /// The save would have been copied, but nothing was written in dry run mode, see
+    /// [`SteeveBuilder::dry_run`].
+    DryRun {
+        /// Number of bytes that would have been copied.
+        bytes: u64,
+    },
+}
+
+/// How the current Steam and Xbox saves compare, see [`Steeve::compare`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompareResult {
+    /// Both saves have the same contents.
+    Identical,
+
+    /// The Steam save is newer than the Xbox save, by this much.
+    SteamNewer(Duration),
+
+    /// The Xbox save is newer than the Steam save, by this much.
+    XboxNewer(Duration),
+
+    /// The saves differ, but have the same modify time, so neither would be synced.
+    SameAge,
+
+    /// At least one save is missing. The fields are `true` for each side without a save.
+    Missing { steam: bool, xbox: bool },
+}
+
+/// What to do when the file system watcher reports that events may have been lost.
+///
+/// Bursts of changes can overflow the operating system's event buffer, and watchers on network
+/// drives can fail temporarily. Either way, a change to a save may go unnoticed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OverflowStrategy {
+    /// Sync both saves as with [`Steeve::sync_now`], so a lost event never means a missed sync.
+    ///
+    /// This scans both save directories, which is cheap unless errors are reported constantly.
+    #[default]
+    Reconcile,
+
+    /// Only log a warning. Changes that were missed are synced with the next event for the save.
+    Ignore,
+}
+
+/// What the sync service is doing, see [`Steeve::status`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+    /// Watching both saves for changes.
+    Watching,
+
+    /// Watching, but neither edition has a save yet. Syncing starts once either game is played.
+    WaitingForFirstSave,
+
+    /// Watching, but ignoring changes until [`Steeve::resume`].
+    Paused,
+
+    /// Not watching for changes, because the service was stopped or built without watching.
+    Stopped,
+}
+
+/// What a save directory watcher is doing, see [`SideStatus::watcher`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WatchState {
+    /// Watching the save directory for changes.
+    Watching,
+
+    /// Watching the nearest existing parent directory, until the save directory is created.
+    WaitingForDir,
+
+    /// Not watching, or events may have been lost, because of this error.
+    Error(String),
+
+    /// Not watching, because the service was stopped or built without watching, or because
+    /// this side's changes are not synced, see [`SteeveBuilder::sync_to_steam`].
+    Stopped,
+}
+
+/// The state of one side's watcher, and why it last changed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WatcherStatus {
+    /// What the watcher is doing.
+    pub state: WatchState,
+
+    /// When the state last changed.
+    pub since: SystemTime,
+
+    /// Why the state last changed.
+    pub reason: String,
+}
+
+/// The sync state of both sides, see [`Steeve::sync_status`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyncStatus {
+    /// The Steam side.
+    pub steam: SideStatus,
+
+    /// The Xbox side.
+    pub xbox: SideStatus,
+}
+
+/// The sync state of one side.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SideStatus {
+    /// When a sync last copied a save to this side.
+    pub last_sync: Option<SystemTime>,
+
+    /// The last error syncing to this side, cleared by the next successful sync.
+    pub last_error: Option<String>,
+
+    /// Path to the save file, if one was found.
+    pub save_path: Option<PathBuf>,
+
+    /// Number of backups kept for this side.
+    pub backups: usize,
+
+    /// The state of this side's save directory watcher, and why it last changed.
+    pub watcher: WatcherStatus,
+}
+
+/// A hook that can veto a sync before the destination is overwritten.
+type PreSyncHook = Arc<dyn Fn(&SyncEvent) -> SyncDecision + Send + Sync>;
+
+/// Syncs in a row that find no save on a side before its save directory is rediscovered.
+const RESCAN_AFTER_MISSES: usize = 3;
+
+/// How often the shared folder is checked for saves from other machines.
+///
+/// Synced and network folders do not reliably report changes, so they are polled instead of
+/// watched.
+const REMOTE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long to wait before retrying a sync deferred by the pre-sync hook.
+const DEFER_DELAY: Duration = Duration::from_secs(5);
+
+/// How many times in a row a sync is deferred before it is dropped.
+///
+/// This gives up after five minutes. The next change to the save is synced as usual.
+const MAX_DEFERS: usize = 60;
+
+/// How often the game is checked while syncs are waiting for it to exit.
+const GAME_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long events for a save written by a sync are ignored, while it keeps the same contents.
+///
+/// This covers the debounce and deferred retries. A later event for the same contents is still
+/// harmless, because identical saves are never copied.
+const OWN_WRITE_WINDOW: Duration = Duration::from_secs(30);
+
+/// A save written by a sync.
+#[derive(Debug)]
+struct OwnWrite {
+    /// Hash of the contents that were written.
+    hash: Vec<u8>,
+
+    /// When events for the save are no longer ignored.
+    expires: Instant,
+}
+
 /// The primary sync service.
 pub struct Steeve {
-    steam_save: SteamSave,
-    xbox_save: XboxSave,
-    steam_watcher: Debouncer<RecommendedWatcher>,
-    xbox_watcher: Debouncer<RecommendedWatcher>,
+    shared: Arc<Shared>,
+    lock: Option<File>,
+    trash: Option<Trash>,
+    data_dir: PathBuf,
+    steam_save_dir: Option<PathBuf>,
+    xbox_save_dir: Option<PathBuf>,
+    watch_config: WatchConfig,
+    steam_watcher: WatcherSlot,
+    xbox_watcher: WatcherSlot,
+
+    /// A watcher for each shared folder that could be watched, see [`Steeve::watch_remotes`].
+    remote_watchers: Vec<SaveWatcher>,
+}
+
+/// How the watchers are created, kept for starting them again.
+#[derive(Clone, Copy, Debug)]
+struct WatchConfig {
+    mode: WatchMode,
+    debounce: Duration,
+    overflow: OverflowStrategy,
+}
+
+/// How the save directories are watched for changes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WatchMode {
+    /// Use the native file system events for the platform.
+    #[default]
+    Native,
+
+    /// Scan the save directories for changes at this interval.
+    ///
+    /// Native events are unreliable on some network file systems (SMB, NFS) and cloud-synced
+    /// virtual drives. Polling works everywhere, at the cost of some CPU and I/O.
+    Poll(Duration),
+}
+
+/// A debounced file system watcher of either kind.
+enum SaveWatcher {
+    Native(Debouncer<RecommendedWatcher>),
+    Poll(Debouncer<PollWatcher>),
+}
+
+impl SaveWatcher {
+    fn watcher(&mut self) -> &mut dyn Watcher {
+        match self {
+            Self::Native(debouncer) => debouncer.watcher(),
+            Self::Poll(debouncer) => debouncer.watcher(),
+        }
+    }
+}
+
+impl Debug for SaveWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Native(_) => f.write_str("Debouncer<RecommendedWatcher>"),
+            Self::Poll(_) => f.write_str("Debouncer<PollWatcher>"),
+        }
+    }
+}
+
+/// A side's watcher, shared with its own event handler so the watch can follow the save
+/// directory when it is created or removed.
+type WatcherSlot = Arc<Mutex<Option<SideWatcher>>>;
+
+/// A running watcher, and the directory that it watches.
+#[derive(Debug)]
+struct SideWatcher {
+    watcher: SaveWatcher,
+
+    /// The save directory, or its nearest existing parent while the save directory is missing.
+    watched: PathBuf,
+}
+
+/// Get the directory to watch for `save_dir`, and how to watch it.
+///
+/// This is the save directory itself when it exists, otherwise its nearest existing parent. The
+/// parent is watched without recursion, only to notice when the next directory is created.
+fn watch_target(save_dir: &Path) -> Option<(&Path, RecursiveMode)> {
+    if save_dir.is_dir() {
+        return Some((save_dir, RecursiveMode::Recursive));
+    }
+
+    save_dir
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.is_dir())
+        .map(|dir| (dir, RecursiveMode::NonRecursive))
+}
+
+/// Shortest debounce timeout given to the watchers.
+const MIN_DEBOUNCE: Duration = Duration::from_millis(10);
+
+/// Name of the lock file in the data directory, held while a [`Steeve`] is alive.
+const LOCK_FILE: &str = "steeve.lock";
+
+/// State shared between [`Steeve`] and the file system event handlers.
+struct Shared {
+    /// Holds off syncs while the game runs, see [`SteeveBuilder::wait_for_game_exit`].
+    game: Option<GameWatch>,
+
+    steam_save: RwLock<SteamSave>,
+    xbox_save: RwLock<XboxSave>,
+    pre_sync: RwLock<Option<PreSyncHook>>,
+
+    /// Source paths whose last sync was skipped because the destination was newer.
+    ///
+    /// Used to log each skip once, instead of on every file system event.
+    mtime_skips: Mutex<HashSet<PathBuf>>,
+
+    /// Saves recently written by a sync, with the hash of what was written.
+    ///
+    /// Events for these are our own writes, and are ignored while the save still has the same
+    /// contents, so a sync never bounces back to the side it came from. This does not depend on
+    /// modify times, which may be coarse or touched by the game's storage layer.
+    own_writes: Mutex<HashMap<PathBuf, OwnWrite>>,
+
+    /// When a sync last copied a save to each side.
+    last_syncs: Mutex<HashMap<Side, SystemTime>>,
+
+    /// The last error syncing to each side, since the last successful sync.
+    last_errors: Mutex<HashMap<Side, String>>,
+
+    /// Command to run after each successful sync.
+    on_sync: Option<SyncCommand>,
+
+    /// The state of each side's watcher.
+    watchers: Mutex<HashMap<Side, WatcherStatus>>,
+
+    /// Where sync events are sent, shared with the save options.
+    events: Arc<EventSink>,
+
+    /// Ignore file system events, see [`Steeve::pause`].
+    paused: AtomicBool,
+
+    /// Sides whose save directory is rediscovered when their save keeps going missing.
+    rescans: Mutex<HashMap<Side, Rescan>>,
+
+    /// Folders shared with other machines or libraries, that the Steam saves are synced with.
+    remotes: Vec<RemoteSave>,
+
+    /// Sides that syncs never copy a save to, see [`SteeveBuilder::sync_to_steam`].
+    read_only: HashSet<Side>,
+
+    /// Syncs that are retried later, because the pre-sync hook deferred them or the save was
+    /// still being written.
+    deferred: Mutex<DeferQueue>,
+}
+
+/// Syncs waiting to be retried, see [`Steeve::defer`].
+#[derive(Debug, Default)]
+struct DeferQueue {
+    /// The events to handle again, with how many times in a row each was deferred.
+    events: Vec<(Side, DebouncedEvent, usize)>,
+
+    /// Whether the thread that retries the events is running.
+    timer: bool,
+}
+
+/// Counts syncs that found no save on a discovered side.
+#[derive(Debug)]
+struct Rescan {
+    /// The side's watcher, moved when the save directory is rediscovered.
+    slot: Weak<Mutex<Option<SideWatcher>>>,
+
+    /// Syncs in a row that found no save.
+    misses: usize,
 }
 
 impl Steeve {
     /// Create a sync service.
     ///
+    /// See [`Steeve::builder`] for more configuration options.
+    ///
     /// # Errors
     ///
     /// May fail if there are any I/O errors, or if the save directories cannot be located.
+    /// Returns [`Error::AlreadyRunning`] if another instance has not been stopped.
     pub fn new(max_backups: usize) -> Result<Self, Error> {
-        if max_backups < 1 {
-            return Err(Error::MaxBackups);
-        }
+        Self::builder().max_backups(max_backups).build()
+    }
 
-        // Get the path for backups
-        let mut backup_dir = ProjectDirs::from("org", "KodeWerx", "SteeveSync")
+    /// Create a sync service for saves in these directories, instead of discovering them.
+    ///
+    /// This supports games installed to custom locations, and saves on network shares or in
+    /// renamed folders. Directories that do not exist yet are created. The same can be
+    /// configured with [`SteeveBuilder::steam_save_dir`] and [`SteeveBuilder::xbox_save_dir`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SaveDir`] if either directory cannot be created, and may fail like
+    /// [`Steeve::new`] otherwise.
+    pub fn with_paths(
+        max_backups: usize,
+        steam_dir: impl Into<PathBuf>,
+        xbox_dir: impl Into<PathBuf>,
+    ) -> Result<Self, Error> {
+        Self::builder()
+            .max_backups(max_backups)
+            .steam_save_dir(steam_dir)
+            .xbox_save_dir(xbox_dir)
+            .build()
+    }
+
+    /// Create a builder for configuring the sync service.
+    pub fn builder() -> SteeveBuilder {
+        SteeveBuilder::default()
+    }
+
+    /// Create a sync service from a validated builder.
+    fn from_builder(builder: SteeveBuilder) -> Result<Self, Error> {
+        let (qualifier, organization, application) = &builder.project_dirs;
+        let data_dir = ProjectDirs::from(qualifier, organization, application)
             .ok_or(Error::HomeDir)?
             .data_dir()
             .to_path_buf();
-        backup_dir.push("Backups");
-
-        let steam_save = SteamSave::new(max_backups, backup_dir.clone())?;
-        let xbox_save = XboxSave::new(max_backups, backup_dir)?;
-        let steam_watcher = {
-            let xbox_save = xbox_save.clone();
-            new_debouncer(
-                Duration::from_millis(500),
-                None,
-                move |res: DebounceEventResult| {
-                    if let Ok(events) = res {
-                        for event in events {
-                            Self::handle_steam_event(&xbox_save, event);
-                        }
-                    }
-                },
-            )?
-        };
-        let xbox_watcher = {
-            let steam_save = steam_save.clone();
-            new_debouncer(
-                Duration::from_millis(500),
-                None,
-                move |res: DebounceEventResult| {
-                    if let Ok(events) = res {
-                        for event in events {
-                            Self::handle_xbox_event(&steam_save, event);
-                        }
-                    }
-                },
-            )?
+        // A dry run writes nothing, so it leaves the lock to an instance that really syncs
+        let lock = if builder.dry_run {
+            None
+        } else {
+            Some(Self::lock(&data_dir)?)
+        };
+
+        // Get the path for backups
+        let backup_dir = data_dir.join("Backups");
+
+        let trash = if builder.trash {
+            Some(Trash::new(
+                builder.max_trash,
+                backup_dir.clone(),
+                builder.dry_run,
+            )?)
+        } else {
+            None
+        };
+
+        let steam_options = SaveOptions {
+            trash: trash.clone(),
+            ..SaveOptions::from_builder(&builder)
         };
+        let events = steam_options.events.clone();
+        let xbox_options = SaveOptions {
+            max_backups: builder.xbox_max_backups,
+            ..steam_options.clone()
+        };
+
+        let (steam_backup_dir, xbox_backup_dir) = match builder.backup_layout {
+            BackupLayout::PerSide => (
+                backup_dir.join(&builder.steam_backup_subdir),
+                backup_dir.join(&builder.xbox_backup_subdir),
+            ),
+            BackupLayout::Flat => (backup_dir.clone(), backup_dir.clone()),
+        };
+
+        if !builder.dry_run {
+            Self::prepare_save_dir(Side::Steam, builder.steam_save_dir.as_deref())?;
+            Self::prepare_save_dir(Side::Xbox, builder.xbox_save_dir.as_deref())?;
+        }
+        let (retries, interval) = (builder.discovery_retries, builder.discovery_interval);
+        let steam_save_dir = Self::retry_discovery(retries, interval, || {
+            Self::locate_steam_save_dir(&builder.steam_save_dir)
+        })?;
+        let xbox_save_dir = Self::retry_discovery(retries, interval, || {
+            Self::locate_xbox_save_dir(&builder.xbox_save_dir)
+        })?;
+
+        let shared = Arc::new(Shared {
+            game: builder
+                .wait_for_game_exit
+                .then(|| GameWatch::new(builder.game_exit_grace)),
+            steam_save: RwLock::new(SteamSave::new(
+                steam_options,
+                steam_save_dir,
+                steam_backup_dir,
+            )?),
+            xbox_save: RwLock::new(XboxSave::new(xbox_options, xbox_save_dir, xbox_backup_dir)?),
+            pre_sync: RwLock::new(None),
+            mtime_skips: Mutex::new(HashSet::new()),
+            own_writes: Mutex::new(HashMap::new()),
+            last_syncs: Mutex::new(HashMap::new()),
+            last_errors: Mutex::new(HashMap::new()),
+            on_sync: builder
+                .on_sync
+                .as_deref()
+                .and_then(|template| SyncCommand::new(template, builder.on_sync_timeout)),
+            watchers: Mutex::new(HashMap::new()),
+            events: events.clone(),
+            paused: AtomicBool::new(false),
+            rescans: Mutex::new(HashMap::new()),
+            remotes: builder
+                .remote_dirs
+                .iter()
+                .map(|dir| {
+                    RemoteSave::new(dir.clone(), builder.remote_settle_time, builder.dry_run)
+                })
+                .collect::<Result<_, _>>()?,
+            read_only: [
+                (Side::Steam, builder.sync_to_steam),
+                (Side::Xbox, builder.sync_to_xbox),
+            ]
+            .into_iter()
+            .filter_map(|(side, enabled)| (!enabled).then_some(side))
+            .collect(),
+            deferred: Mutex::new(DeferQueue::default()),
+        });
+        Self::check_backup_dirs(&shared, &backup_dir)?;
+
+        if !Self::has_any_save(&shared) {
+            info!("No saves found on either side yet; Steeve will sync once you play");
+        }
 
         let mut steeve = Self {
-            steam_save,
-            xbox_save,
-            steam_watcher,
-            xbox_watcher,
+            shared,
+            lock,
+            trash,
+            data_dir,
+            steam_save_dir: builder.steam_save_dir,
+            xbox_save_dir: builder.xbox_save_dir,
+            watch_config: WatchConfig {
+                mode: builder.watch_mode,
+                debounce: builder.debounce,
+                overflow: builder.overflow,
+            },
+            steam_watcher: WatcherSlot::default(),
+            xbox_watcher: WatcherSlot::default(),
+            remote_watchers: Vec::new(),
         };
 
-        // TODO: Fix directory-not-found errors by waiting for them to be created.
+        let slots = [
+            (Side::Steam, &steeve.steam_save_dir, &steeve.steam_watcher),
+            (Side::Xbox, &steeve.xbox_save_dir, &steeve.xbox_watcher),
+        ];
+        for (side, configured, slot) in slots {
+            if configured.is_none() {
+                let slot = Arc::downgrade(slot);
+                let rescan = Rescan { slot, misses: 0 };
+                steeve.shared.rescans.lock().insert(side, rescan);
+            }
+        }
 
-        // Start watching for changes
-        let path = steeve.steam_save.save_dir();
-        steeve
-            .steam_watcher
-            .watcher()
-            .watch(path, RecursiveMode::Recursive)?;
+        if builder.watch {
+            steeve.ensure_watching()?;
+            for remote in &steeve.shared.remotes {
+                info!(
+                    "Syncing Steam saves with shared folder {:?}",
+                    remote.remote_dir()
+                );
+            }
+            if !steeve.shared.remotes.is_empty() {
+                Self::poll_remote(&steeve.shared);
+            }
+            if !builder.sync_interval.is_zero() {
+                info!(
+                    "Syncing every {:?}, regardless of file system events",
+                    builder.sync_interval,
+                );
+                Self::poll_saves(&steeve.shared, builder.sync_interval);
+            }
+        }
 
-        let path = steeve.xbox_save.save_dir();
-        steeve
-            .xbox_watcher
-            .watcher()
-            .watch(path, RecursiveMode::Recursive)?;
+        if steeve.shared.game.is_some() {
+            Self::watch_game(&steeve.shared);
+        }
 
-        // TODO: Attempt initial sync
+        if builder.initial_sync {
+            steeve.initial_sync();
+        }
 
         Ok(steeve)
     }
 
-    /// Stop watching for events.
-    pub fn stop(&mut self) -> Result<(), Error> {
-        self.steam_watcher
-            .watcher()
-            .unwatch(self.steam_save.save_dir())?;
-        self.xbox_watcher
-            .watcher()
-            .unwatch(self.xbox_save.save_dir())?;
+    /// Take the lock that keeps two instances from syncing the same saves.
+    ///
+    /// The lock is released when the file is closed, including when the process exits.
+    fn lock(data_dir: &Path) -> Result<File, Error> {
+        std::fs::create_dir_all(data_dir)
+            .map_err(|_| SaveError::DirCreate(data_dir.to_path_buf()))?;
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(data_dir.join(LOCK_FILE))
+            .map_err(SaveError::from)?;
 
-        Ok(())
+        match file.try_lock() {
+            Ok(()) => Ok(file),
+            Err(TryLockError::WouldBlock) => Err(Error::AlreadyRunning),
+            Err(TryLockError::Error(err)) => Err(SaveError::from(err).into()),
+        }
     }
 
-    /// Event handler for Steam save directory.
-    fn handle_steam_event(xbox_save: &XboxSave, event: DebouncedEvent) {
-        if SteamSave::save_file(&event.path).is_none() {
-            return;
+    /// Check that no backup directory is inside a save directory.
+    ///
+    /// Backups written inside a watched save directory would trigger more file system events,
+    /// feeding back into the watchers.
+    fn check_backup_dirs(shared: &Shared, backup_dir: &Path) -> Result<(), Error> {
+        let steam_save = shared.steam_save.read();
+        let xbox_save = shared.xbox_save.read();
+        let backup_dirs = [backup_dir, steam_save.backup_dir(), xbox_save.backup_dir()];
+        let save_dirs = [steam_save.save_dir(), xbox_save.save_dir()];
+
+        for save_dir in save_dirs {
+            let resolved_save_dir = resolve_links(save_dir);
+            for backup_dir in backup_dirs {
+                if resolve_links(backup_dir).starts_with(&resolved_save_dir) {
+                    return Err(Error::BackupInsideSaveDir(
+                        backup_dir.to_path_buf(),
+                        save_dir.to_path_buf(),
+                    ));
+                }
+            }
         }
 
-        debug!("Got event for Steam path: {:?}", event.path);
+        Ok(())
+    }
 
-        match xbox_save.copy_save(&event.path) {
-            Err(SaveError::NoSave | SaveError::ModifyTime) => (),
-            Err(err) => warn!("Xbox save error: {:?}", err),
-            _ => (),
+    /// Create an explicitly configured save directory if it does not exist.
+    fn prepare_save_dir(side: Side, save_dir: Option<&Path>) -> Result<(), Error> {
+        match save_dir {
+            Some(save_dir) if std::fs::create_dir_all(save_dir).is_err() => {
+                Err(Error::SaveDir(side, save_dir.to_path_buf()))
+            }
+            _ => Ok(()),
         }
     }
 
-    /// Event handler for Xbox save directory.
-    fn handle_xbox_event(steam_save: &SteamSave, event: DebouncedEvent) {
-        if XboxSave::save_file(&event.path).is_none() {
-            return;
+    /// Retry a save directory discovery that fails because Steam or the Xbox edition cannot be
+    /// found, doubling the delay from `interval` after each retry.
+    fn retry_discovery<T>(
+        retries: usize,
+        interval: Duration,
+        mut discover: impl FnMut() -> Result<T, SaveError>,
+    ) -> Result<T, SaveError> {
+        let mut delay = interval;
+        for _ in 0..retries {
+            match discover() {
+                Err(err @ (SaveError::HomeDir | SaveError::SteamDir | SaveError::SteamApp(_))) => {
+                    warn!("{err}, retrying in {delay:?}");
+                    std::thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                }
+                result => return result,
+            }
         }
 
-        debug!("Got event for Xbox path: {:?}", event.path);
+        discover()
+    }
 
-        match steam_save.copy_save(&event.path) {
-            Err(SaveError::NoSave | SaveError::ModifyTime) => (),
-            Err(err) => warn!("Steam save error: {:?}", err),
-            _ => (),
+    /// Get the explicitly configured Steam save directory, or discover it.
+    fn locate_steam_save_dir(save_dir: &Option<PathBuf>) -> Result<PathBuf, SaveError> {
+        match save_dir {
+            Some(save_dir) => Ok(save_dir.clone()),
+            None => SteamSave::locate_save_dir(),
         }
     }
-}
 
-impl Debug for Steeve {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Steeve")
-            .field("steam_save", &self.steam_save)
-            .field("xbox_save", &self.xbox_save)
-            .field("steam_watcher", &"Debouncer<RecommendedWatcher>")
-            .field("xbox_watcher", &"Debouncer<RecommendedWatcher>")
-            .finish()
+    /// Get the explicitly configured Xbox save directory, or discover it.
+    fn locate_xbox_save_dir(save_dir: &Option<PathBuf>) -> Result<PathBuf, SaveError> {
+        match save_dir {
+            Some(save_dir) => Ok(save_dir.clone()),
+            None => XboxSave::locate_save_dir(),
+        }
+    }
+
+    /// Create a debounced file system watcher that passes events to the handler for `side`, and
+    /// start watching `path`.
+    fn start_watcher(
+        shared: &Arc<Shared>,
+        config: WatchConfig,
+        side: Side,
+        slot: Weak<Mutex<Option<SideWatcher>>>,
+        path: &Path,
+        mode: RecursiveMode,
+    ) -> Result<SaveWatcher, Error> {
+        let mut watcher = Self::create_watcher(shared, config, side, slot)?;
+        watcher.watcher().watch(path, mode)?;
+
+        Ok(watcher)
+    }
+
+    /// Create a debounced file system watcher that passes events to the handler for `side`.
+    ///
+    /// The watcher is stored in `slot`, so it can move its own watch when the save directory
+    /// is created or removed.
+    fn create_watcher(
+        shared: &Arc<Shared>,
+        config: WatchConfig,
+        side: Side,
+        slot: Weak<Mutex<Option<SideWatcher>>>,
+    ) -> Result<SaveWatcher, Error> {
+        let WatchConfig {
+            mode: watch_mode,
+            debounce,
+            overflow,
+        } = config;
+        let shared = shared.clone();
+
+        // The debouncer batches events on a timer that ticks faster than the timeout, so a zero
+        // timeout would spin. Rounding up keeps the latency negligible.
+        let timeout = debounce.max(MIN_DEBOUNCE);
+        let event_handler = move |res: DebounceEventResult| match res {
+            Ok(events) => {
+                if let Some(slot) = slot.upgrade() {
+                    Self::follow_save_dir(&shared, &slot, side);
+                }
+                shared.recover_watch_state(side);
+                for event in events {
+                    Self::handle_event(&shared, side, event);
+                }
+            }
+            Err(errors) => {
+                warn!("File system watcher errors, events may have been lost: {errors:?}");
+                let message = format!("{errors:?}");
+                shared.set_watch_state(
+                    side,
+                    WatchState::Error(message.clone()),
+                    "the watcher reported errors, events may have been lost",
+                );
+                shared
+                    .events
+                    .emit(SteeveEvent::WatchError { side, message });
+                if let Some(slot) = slot.upgrade() {
+                    if let Err(err) = Self::watch_again(&shared, &slot, side) {
+                        warn!(
+                            "Unable to watch the {side:?} save directory again: {:?}",
+                            err
+                        );
+                    }
+                }
+                if overflow == OverflowStrategy::Reconcile && !shared.is_paused() {
+                    info!("Reconciling saves after lost events");
+                    match shared.sync_both() {
+                        Err(err) if !err.is_benign() => warn!("Reconcile error: {:?}", err),
+                        _ => (),
+                    }
+                }
+            }
+        };
+
+        Self::new_watcher(watch_mode, timeout, event_handler)
+    }
+
+    /// Create a debounced file system watcher of the kind that `mode` asks for.
+    fn new_watcher<F: DebounceEventHandler>(
+        mode: WatchMode,
+        timeout: Duration,
+        event_handler: F,
+    ) -> Result<SaveWatcher, Error> {
+        let watcher = match mode {
+            WatchMode::Native => SaveWatcher::Native(new_debouncer(timeout, None, event_handler)?),
+            WatchMode::Poll(interval) => {
+                let config = notify::Config::default().with_poll_interval(interval);
+                SaveWatcher::Poll(new_debouncer_opt(timeout, None, event_handler, config)?)
+            }
+        };
+
+        Ok(watcher)
+    }
+
+    /// Watch each shared folder, so changes from other machines are synced without waiting for
+    /// the next poll.
+    ///
+    /// Synced and network folders do not always report changes, or cannot be watched at all, so
+    /// they are still polled. A folder that cannot be watched is only logged.
+    fn watch_remotes(&mut self) {
+        if !self.remote_watchers.is_empty() {
+            return;
+        }
+
+        let timeout = self.watch_config.debounce.max(MIN_DEBOUNCE);
+        for remote in &self.shared.remotes {
+            let dir = remote.remote_dir();
+            let shared = Arc::downgrade(&self.shared);
+            let event_handler = move |res: DebounceEventResult| {
+                let Some(shared) = shared.upgrade() else {
+                    return;
+                };
+                match res {
+                    Ok(events) => {
+                        let changed = events
+                            .iter()
+                            .any(|event| SteamSave::save_file(&event.path).is_some());
+                        if changed && !shared.is_paused() {
+                            shared.sync_remote_logged();
+                        }
+                    }
+                    Err(errors) => debug!("Shared folder watcher errors: {errors:?}"),
+                }
+            };
+            let watcher = Self::new_watcher(self.watch_config.mode, timeout, event_handler)
+                .and_then(|mut watcher| {
+                    watcher.watcher().watch(dir, RecursiveMode::NonRecursive)?;
+                    Ok(watcher)
+                });
+            match watcher {
+                Ok(watcher) => self.remote_watchers.push(watcher),
+                Err(err) => info!(
+                    "Unable to watch shared folder {dir:?}, polling it: {:?}",
+                    err
+                ),
+            }
+        }
+    }
+
+    /// Stop watching for events.
+    ///
+    /// This also releases the lock, so another instance can be started. Use
+    /// [`Steeve::ensure_watching`] to start watching again.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        self.lock.take();
+
+        // Dropping the watchers removes their watches
+        self.steam_watcher.lock().take();
+        self.xbox_watcher.lock().take();
+        self.remote_watchers.clear();
+        for side in [Side::Steam, Side::Xbox] {
+            self.shared
+                .set_watch_state(side, WatchState::Stopped, "the service was stopped");
+        }
+
+        Ok(())
+    }
+
+    /// Pause syncing, e.g. during a play session, without stopping the watchers.
+    ///
+    /// File system events are ignored until [`Steeve::resume`]. [`Steeve::sync_now`] still
+    /// syncs while paused.
+    pub fn pause(&mut self) {
+        info!("Syncing is paused");
+        self.shared.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resume syncing after [`Steeve::pause`].
+    ///
+    /// Changes made while paused are not synced by the watchers, so `sync_now` syncs them right
+    /// away, as with [`Steeve::sync_now`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first save error from the sync.
+    pub fn resume(&mut self, sync_now: bool) -> Result<(), Error> {
+        info!("Syncing is resumed");
+        self.shared.paused.store(false, Ordering::SeqCst);
+        if sync_now {
+            self.sync_now()?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if syncing is paused with [`Steeve::pause`].
+    pub fn is_paused(&self) -> bool {
+        self.shared.is_paused()
+    }
+
+    /// Start watching both save directories, unless they are already watched.
+    ///
+    /// This is idempotent: watchers that are running are left alone, so nothing is ever watched
+    /// twice. The lock is taken again if it was released by [`Steeve::stop`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AlreadyRunning`] if another instance took the lock in the meantime, or an
+    /// error if a save directory cannot be watched. A save directory that does not exist yet is
+    /// not an error; its nearest existing parent is watched until it is created.
+    pub fn ensure_watching(&mut self) -> Result<(), Error> {
+        if self.lock.is_none() {
+            self.lock = Some(Self::lock(&self.data_dir)?);
+        }
+
+        self.ensure_watcher(Side::Steam)?;
+        self.ensure_watcher(Side::Xbox)?;
+        self.watch_remotes();
+
+        Ok(())
+    }
+
+    /// Start watching one side's save directory, unless it is already watched.
+    ///
+    /// When the save directory does not exist, its nearest existing parent is watched instead.
+    fn ensure_watcher(&mut self, side: Side) -> Result<(), Error> {
+        let shared = &self.shared;
+        let slot = match side {
+            Side::Steam => &self.steam_watcher,
+            Side::Xbox => &self.xbox_watcher,
+        };
+        if slot.lock().is_some() {
+            return Ok(());
+        }
+        let dest = side.other();
+        if !shared.syncs_to(dest) {
+            let reason = format!("syncing to {dest:?} is disabled");
+            shared.set_watch_state(side, WatchState::Stopped, reason);
+
+            return Ok(());
+        }
+
+        let save_dir = shared.watch_dir(side);
+        let Some((path, mode)) = watch_target(&save_dir) else {
+            let err = NotifyError::path_not_found().add_path(save_dir.clone());
+            let reason = format!("no parent of {save_dir:?} exists");
+            shared.set_watch_state(side, WatchState::Error(err.to_string()), reason);
+
+            return Err(err.into());
+        };
+        let watcher = Self::start_watcher(
+            shared,
+            self.watch_config,
+            side,
+            Arc::downgrade(slot),
+            path,
+            mode,
+        );
+        let watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                let reason = format!("unable to watch {path:?}");
+                shared.set_watch_state(side, WatchState::Error(err.to_string()), reason);
+
+                return Err(err);
+            }
+        };
+        *slot.lock() = Some(SideWatcher {
+            watcher,
+            watched: path.to_path_buf(),
+        });
+
+        if mode == RecursiveMode::Recursive {
+            shared.set_watch_state(side, WatchState::Watching, format!("started on {path:?}"));
+        } else {
+            info!("The {side:?} save directory {save_dir:?} does not exist yet, waiting for it");
+            let reason = format!("{save_dir:?} does not exist, watching {path:?}");
+            shared.set_watch_state(side, WatchState::WaitingForDir, reason);
+
+            // The directory may have been created while the watcher was starting
+            Self::follow_save_dir(shared, slot, side);
+        }
+
+        Ok(())
+    }
+
+    /// Move a side's watch when its save directory was created or removed.
+    ///
+    /// Saves are reconciled once the save directory is watched, because the game may have
+    /// written a save before the watch moved.
+    fn follow_save_dir(shared: &Shared, slot: &Mutex<Option<SideWatcher>>, side: Side) {
+        let save_dir = shared.watch_dir(side);
+        match Self::retarget(shared, slot, side, &save_dir) {
+            Ok(true) => {
+                info!("The {side:?} save directory was created, syncing saves");
+                match shared.sync_both() {
+                    Err(err) if !err.is_benign() => warn!("Reconcile error: {:?}", err),
+                    _ => (),
+                }
+            }
+            Ok(false) => (),
+            Err(err) => warn!("Unable to follow the {side:?} save directory: {:?}", err),
+        }
+    }
+
+    /// Replace a side's watch after its watcher reported errors.
+    ///
+    /// The old watch may be broken even when its path still exists, so it is always removed. A
+    /// failure leaves the side without a watch, in the error state.
+    fn watch_again(
+        shared: &Shared,
+        slot: &Mutex<Option<SideWatcher>>,
+        side: Side,
+    ) -> Result<bool, Error> {
+        if let Some(side_watcher) = slot.lock().as_mut() {
+            let old = std::mem::take(&mut side_watcher.watched);
+            let _ = side_watcher.watcher.watcher().unwatch(&old);
+        }
+
+        Self::retarget(shared, slot, side, &shared.watch_dir(side))
+    }
+
+    /// Point a side's watcher at `save_dir`, or at its nearest existing parent.
+    ///
+    /// Returns `true` when the watcher moved onto the save directory. A failure leaves the old
+    /// watch in place.
+    fn retarget(
+        shared: &Shared,
+        slot: &Mutex<Option<SideWatcher>>,
+        side: Side,
+        save_dir: &Path,
+    ) -> Result<bool, Error> {
+        let Some((path, mode)) = watch_target(save_dir) else {
+            return Err(NotifyError::path_not_found()
+                .add_path(save_dir.to_path_buf())
+                .into());
+        };
+
+        let mut slot = slot.lock();
+        let Some(side_watcher) = slot.as_mut() else {
+            return Ok(false);
+        };
+        if side_watcher.watched == path {
+            return Ok(false);
+        }
+
+        // Watch the new path first, so a failure leaves the old watch in place
+        if let Err(err) = side_watcher.watcher.watcher().watch(path, mode) {
+            let state = WatchState::Error(err.to_string());
+            shared.set_watch_state(side, state, format!("unable to watch {path:?}"));
+            return Err(err.into());
+        }
+
+        // The old directory may no longer exist, which is fine
+        let old = std::mem::replace(&mut side_watcher.watched, path.to_path_buf());
+        let _ = side_watcher.watcher.watcher().unwatch(&old);
+        debug!("{side:?} watch moved: {old:?} -> {path:?}");
+
+        if mode == RecursiveMode::Recursive {
+            let reason = format!("watching {path:?}");
+            shared.set_watch_state(side, WatchState::Watching, reason);
+
+            Ok(true)
+        } else {
+            let reason = format!("{save_dir:?} does not exist, watching {path:?}");
+            shared.set_watch_state(side, WatchState::WaitingForDir, reason);
+
+            Ok(false)
+        }
+    }
+
+    /// Get the state of one side's watcher, and why it last changed.
+    ///
+    /// This is also part of [`Steeve::sync_status`].
+    pub fn watcher_status(&self, side: Side) -> WatcherStatus {
+        self.shared.watch_state(side)
+    }
+
+    /// Get what the sync service is doing.
+    ///
+    /// See [`Steeve::sync_status`] for the details of each side, including the state of its
+    /// watcher and why it last changed.
+    pub fn status(&self) -> Status {
+        let watching = self.steam_watcher.lock().is_some() || self.xbox_watcher.lock().is_some();
+        if !watching || self.lock.is_none() {
+            Status::Stopped
+        } else if self.is_paused() {
+            Status::Paused
+        } else if !Self::has_any_save(&self.shared) {
+            Status::WaitingForFirstSave
+        } else {
+            Status::Watching
+        }
+    }
+
+    /// Sync whichever save changed while Steeve was not running.
+    ///
+    /// Errors are logged rather than returned, so a bad save does not keep Steeve from starting.
+    fn initial_sync(&self) {
+        let steam = self.shared.steam_save.read().locate_save_path().is_some();
+        let xbox = self.shared.xbox_save.read().locate_save_path().is_some();
+        match (steam, xbox) {
+            (true, true) => match self.shared.sync_both() {
+                Err(err) if !err.is_benign() => warn!("Initial sync error: {:?}", err),
+                _ => (),
+            },
+            // A new save needs a Steam ID or an Xbox container, which only the game can create
+            (true, false) => info!("No Xbox save yet; play the Xbox edition once to start syncing"),
+            (false, true) => {
+                info!("No Steam save yet; play the Steam edition once to start syncing")
+            }
+            (false, false) => (),
+        }
+    }
+
+    /// Check if either side has a save.
+    fn has_any_save(shared: &Shared) -> bool {
+        shared.steam_save.read().locate_save_path().is_some()
+            || shared.xbox_save.read().locate_save_path().is_some()
+    }
+
+    /// Sync the newest save over the older one right away, without waiting for a file system
+    /// event.
+    ///
+    /// Each direction goes through the same modify time guard as the watchers, so at most one
+    /// save is overwritten. Nothing is copied when either save is missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns the save error, except for expected skips. When several saves fail to sync, the
+    /// others are still synced, and the errors are returned together in [`SaveError::Several`].
+    /// While the game runs, see [`SteeveBuilder::wait_for_game_exit`], this fails with
+    /// [`SaveError::GameRunning`], and the saves are synced once it exits.
+    pub fn sync_now(&self) -> Result<(), Error> {
+        Ok(self.shared.sync_both()?)
+    }
+
+    /// Copy the saves in one direction right away, even when the destination is newer.
+    ///
+    /// This is for when one side is known to be right, e.g. after editing a save by hand. The
+    /// save is still validated, and the destination is always backed up first, even with
+    /// [`BackupPolicy::Disabled`]. Saves that changed on both sides are overwritten too.
+    ///
+    /// # Errors
+    ///
+    /// Fails if syncing to the destination is disabled, see [`SteeveBuilder::sync_to_steam`], if
+    /// there is no save to copy, or with the first save error. It also fails with
+    /// [`SaveError::GameRunning`] while the game runs, see [`SteeveBuilder::wait_for_game_exit`].
+    pub fn force_sync(&self, direction: Direction) -> Result<(), Error> {
+        let dest = match direction {
+            Direction::SteamToXbox => Side::Xbox,
+            Direction::XboxToSteam => Side::Steam,
+        };
+        if !self.shared.syncs_to(dest) {
+            return Err(Error::SyncDisabled(dest));
+        }
+        info!("Forcing a sync to {dest:?}");
+        self.shared.force_sync(direction)?;
+        self.shared.keep_local_saves();
+
+        Ok(())
+    }
+
+    /// Compare the current Steam and Xbox saves without syncing anything.
+    ///
+    /// This is the read-only counterpart to [`Steeve::sync_now`], e.g. for showing whether the
+    /// saves are in sync. Saves are compared by hash, then by modify time.
+    ///
+    /// # Errors
+    ///
+    /// Fails if either save cannot be read.
+    pub fn compare(&self) -> Result<CompareResult, Error> {
+        Ok(self.shared.compare()?)
+    }
+
+    /// Get when a sync last copied a save to `side`, or `None` if it has not happened since the
+    /// sync service was created.
+    pub fn last_sync_at(&self, side: Side) -> Option<SystemTime> {
+        self.shared.last_syncs.lock().get(&side).copied()
+    }
+
+    /// Get the sync state of both sides, e.g. for showing in a status panel.
+    ///
+    /// The backup count is zero when the backup directory cannot be read.
+    pub fn sync_status(&self) -> SyncStatus {
+        SyncStatus {
+            steam: self.shared.side_status(&*self.shared.steam_save.read()),
+            xbox: self.shared.side_status(&*self.shared.xbox_save.read()),
+        }
+    }
+
+    /// Check if a sync copied a save to either side after `when`.
+    pub fn synced_since(&self, when: SystemTime) -> bool {
+        self.shared
+            .last_syncs
+            .lock()
+            .values()
+            .any(|&synced| synced > when)
+    }
+
+    /// Set a hook that is called before a save is overwritten.
+    ///
+    /// The hook runs after the destination has been backed up, and its [`SyncDecision`] decides
+    /// whether the copy goes ahead, is skipped, or is retried later. A deferred sync is retried
+    /// every few seconds, for up to five minutes.
+    ///
+    /// The hook may replace itself, but it holds up other syncs and the rediscovery of the save
+    /// directories while it runs, so it should return quickly. Return [`SyncDecision::Defer`]
+    /// instead of waiting.
+    pub fn set_pre_sync<F>(&self, hook: F)
+    where
+        F: Fn(&SyncEvent) -> SyncDecision + Send + Sync + 'static,
+    {
+        *self.shared.pre_sync.write() = Some(Arc::new(hook));
+    }
+
+    /// Set a handler that is called with a [`SteeveEvent`] whenever Steeve backs up or syncs a
+    /// save, or fails to sync one.
+    ///
+    /// This replaces any previous handler. The handler is called on the watcher threads, maybe
+    /// on several at once, and holds up the sync that sent the event, so it should return
+    /// quickly. See [`Steeve::event_channel`] for receiving events on another thread instead.
+    pub fn on_event<F>(&self, handler: F)
+    where
+        F: Fn(SteeveEvent) + Send + Sync + 'static,
+    {
+        *self.shared.events.handler.lock() = Some(Arc::new(handler));
+    }
+
+    /// Receive [`SteeveEvent`]s through a channel, e.g. to show them in a user interface.
+    ///
+    /// This replaces any handler set with [`Steeve::on_event`].
+    pub fn event_channel(&self) -> Receiver<SteeveEvent> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.on_event(move |event| {
+            let _ = sender.send(event);
+        });
+
+        receiver
+    }
+
+    /// Report which side, if any, recognizes the path as a save file.
+    ///
+    /// The path must be an existing file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use steeve_sync::{Side, Steeve};
+    /// let path = std::env::temp_dir().join("76561197960287930_Player.sav");
+    /// std::fs::write(&path, b"GVAS")?;
+    /// assert_eq!(Steeve::classify(&path), Some(Side::Steam));
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn classify(path: &Path) -> Option<Side> {
+        if SteamSave::save_file(path).is_some() {
+            Some(Side::Steam)
+        } else if XboxSave::save_file(path).is_some() {
+            Some(Side::Xbox)
+        } else {
+            None
+        }
+    }
+
+    /// Permanently remove all pruned backups from the trash.
+    ///
+    /// Does nothing when the trash is not enabled.
+    pub fn empty_trash(&self) -> Result<(), Error> {
+        if let Some(trash) = &self.trash {
+            trash.empty()?;
+        }
+
+        Ok(())
+    }
+
+    /// Backup the current Steam and Xbox saves right away, regardless of modify times.
+    ///
+    /// This is a snapshot of both sides, e.g. before a risky game update. Saves that are already
+    /// backed up are not duplicated.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first backup error. The Steam save is backed up first.
+    pub fn backup_both(&self) -> Result<BackupPair, Error> {
+        let steam = self.shared.steam_save.read().backup_now()?;
+        let xbox = self.shared.xbox_save.read().backup_now()?;
+
+        Ok(BackupPair { steam, xbox })
+    }
+
+    /// Restore the newest backup for one side over its current save.
+    ///
+    /// The current save is backed up first, unless it is already backed up. The restored save is
+    /// synced to the other side like any other change to the save. Returns the restored backup,
+    /// or `None` when the side has no backups.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no current save to restore over, if the backup is not a valid save, or
+    /// if there are any I/O errors.
+    pub fn restore_latest(&self, side: Side) -> Result<Option<BackupEntry>, Error> {
+        let backup = match side {
+            Side::Steam => self.shared.steam_save.read().restore_latest()?,
+            Side::Xbox => self.shared.xbox_save.read().restore_latest()?,
+        };
+        if backup.is_some() {
+            self.shared.keep_local_saves();
+        }
+
+        Ok(backup)
+    }
+
+    /// List the backups for one side, from oldest to newest.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the backup directory cannot be read.
+    pub fn list_backups(&self, side: Side) -> Result<Vec<BackupEntry>, Error> {
+        let backups = match side {
+            Side::Steam => self.shared.steam_save.read().list_backups()?,
+            Side::Xbox => self.shared.xbox_save.read().list_backups()?,
+        };
+
+        Ok(backups)
+    }
+
+    /// Read a summary of the current save for one side, such as the player's credits.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no save, if it does not start with a complete GVAS header, or if there
+    /// are any I/O errors.
+    pub fn save_info(&self, side: Side) -> Result<SaveInfo, Error> {
+        let info = match side {
+            Side::Steam => self.shared.steam_save.read().read_info()?,
+            Side::Xbox => self.shared.xbox_save.read().read_info()?,
+        };
+
+        Ok(info)
+    }
+
+    /// Delete a backup from [`Steeve::list_backups`] for one side.
+    ///
+    /// The backup is moved to the trash instead when it is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the backup is not one of this side's backups, or if it cannot be removed.
+    pub fn delete_backup(&self, side: Side, backup: &BackupEntry) -> Result<(), Error> {
+        match side {
+            Side::Steam => self.shared.steam_save.read().delete_backup(backup)?,
+            Side::Xbox => self.shared.xbox_save.read().delete_backup(backup)?,
+        }
+
+        Ok(())
+    }
+
+    /// Show the backup directory for one side in the file manager.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file manager cannot be started.
+    pub fn open_backup_dir(&self, side: Side) -> Result<(), Error> {
+        match side {
+            Side::Steam => self.shared.steam_save.read().open_backup_dir()?,
+            Side::Xbox => self.shared.xbox_save.read().open_backup_dir()?,
+        }
+
+        Ok(())
+    }
+
+    /// Restore a backup from [`Steeve::list_backups`] over the current save for one side.
+    ///
+    /// The current save is backed up first, unless it is already backed up. The restored save is
+    /// synced to the other side like any other change to the save.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the backup is not one of this side's backups, if there is no current save to
+    /// restore over, if the backup is not a valid save, or if there are any I/O errors.
+    pub fn restore_backup(&self, side: Side, backup: &BackupEntry) -> Result<(), Error> {
+        match side {
+            Side::Steam => self.shared.steam_save.read().restore(backup)?,
+            Side::Xbox => self.shared.xbox_save.read().restore(backup)?,
+        }
+        self.shared.keep_local_saves();
+
+        Ok(())
+    }
+
+    /// Restore a Steam backup from [`Steeve::list_backups`] over the current Steam save.
+    ///
+    /// See [`Steeve::restore_backup`].
+    pub fn restore_steam(&self, backup: &BackupEntry) -> Result<(), Error> {
+        self.restore_backup(Side::Steam, backup)
+    }
+
+    /// Restore an Xbox backup from [`Steeve::list_backups`] over the current Xbox save.
+    ///
+    /// See [`Steeve::restore_backup`].
+    pub fn restore_xbox(&self, backup: &BackupEntry) -> Result<(), Error> {
+        self.restore_backup(Side::Xbox, backup)
+    }
+
+    /// Write all backups for both sides into a zip file at `dest`, e.g. for moving to another PC.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the backups cannot be read, or the bundle cannot be written.
+    pub fn export_bundle(&self, dest: &Path) -> Result<(), Error> {
+        bundle::export(dest, &self.bundle_sides())
+    }
+
+    /// Restore the backups from a bundle created by [`Steeve::export_bundle`].
+    ///
+    /// Backups are restored into the configured backup directories, not over the saves. Existing
+    /// backups with the same names are only overwritten when `force` is set. Returns the number
+    /// of restored backups.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the bundle is invalid, or would overwrite a backup without `force`. Nothing is
+    /// restored in either case.
+    pub fn import_bundle(&self, src: &Path, force: bool) -> Result<usize, Error> {
+        bundle::import(src, &self.bundle_sides(), force)
+    }
+
+    /// Get the backups and backup directory for each side of a bundle.
+    fn bundle_sides(&self) -> [bundle::BundleSide; 2] {
+        let steam_save = self.shared.steam_save.read();
+        let xbox_save = self.shared.xbox_save.read();
+
+        [
+            bundle::BundleSide {
+                name: "Steam",
+                backup_dir: steam_save.backup_dir().to_path_buf(),
+                backups: steam_save.backup_paths(),
+            },
+            bundle::BundleSide {
+                name: "Xbox",
+                backup_dir: xbox_save.backup_dir().to_path_buf(),
+                backups: xbox_save.backup_paths(),
+            },
+        ]
+    }
+
+    /// Look for the current Steam save, or find out why there is none.
+    pub fn locate_steam_save(&self) -> LocateResult {
+        self.shared.steam_save.read().locate()
+    }
+
+    /// Look for the current Xbox save, or find out why there is none.
+    pub fn locate_xbox_save(&self) -> LocateResult {
+        self.shared.xbox_save.read().locate()
+    }
+
+    /// Get a summary of the Steam save backups.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the backup directory cannot be read.
+    pub fn steam_backup_stats(&self) -> Result<BackupStats, Error> {
+        Ok(self.shared.steam_save.read().backup_stats()?)
+    }
+
+    /// Get a summary of the Xbox save backups.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the backup directory cannot be read.
+    pub fn xbox_backup_stats(&self) -> Result<BackupStats, Error> {
+        Ok(self.shared.xbox_save.read().backup_stats()?)
+    }
+
+    /// Check the Xbox `containers.index` against the save containers and blobs on disk.
+    ///
+    /// The report lists containers in the index that are missing on disk, blobs that are
+    /// missing from their containers, and containers and blobs that are not listed at all.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the index cannot be read or parsed.
+    pub fn check_xbox_index(&self) -> Result<IndexReport, Error> {
+        let xbox_save = self.shared.xbox_save.read();
+
+        Ok(wgs::check(xbox_save.save_dir())?)
+    }
+
+    /// Remove containers that are missing on disk from the Xbox `containers.index`.
+    ///
+    /// The index is copied into the Xbox backup directory first. Missing blobs and orphans are
+    /// only reported. Close the game and the Xbox app before repairing.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the index cannot be read, parsed, backed up, or written.
+    pub fn repair_xbox_index(&self) -> Result<IndexReport, Error> {
+        let xbox_save = self.shared.xbox_save.read();
+
+        Ok(wgs::repair(xbox_save.save_dir(), xbox_save.backup_dir())?)
+    }
+
+    /// Exercise the sync pipeline on fake saves in a temporary directory.
+    ///
+    /// Runs a sync, a de-duped backup, a restore, and a prune, verifying each stage. The report
+    /// also lists the real save directories found on this machine and whether they are writable.
+    /// Real saves and backups are never touched.
+    pub fn self_test() -> SelfTestReport {
+        self_test::run()
+    }
+
+    /// Re-run save directory discovery and move the watchers to any directories that changed.
+    ///
+    /// Each side is rediscovered independently; a side that cannot be found keeps its current
+    /// directory and watcher, while the other side is still updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first discovery or watch error, after both sides have been attempted.
+    pub fn rediscover(&mut self) -> Result<(), Error> {
+        let steam = Self::rediscover_side(
+            &self.shared,
+            &self.steam_watcher,
+            Side::Steam,
+            &self.steam_save_dir,
+        );
+        let xbox = Self::rediscover_side(
+            &self.shared,
+            &self.xbox_watcher,
+            Side::Xbox,
+            &self.xbox_save_dir,
+        );
+
+        steam.and(xbox)
+    }
+
+    /// Re-run save directory discovery for one side, and move its watcher if the directory
+    /// changed.
+    fn rediscover_side(
+        shared: &Shared,
+        slot: &Mutex<Option<SideWatcher>>,
+        side: Side,
+        configured: &Option<PathBuf>,
+    ) -> Result<(), Error> {
+        let result = match side {
+            Side::Steam => Self::locate_steam_save_dir(configured)
+                .map_err(Error::from)
+                .and_then(|save_dir| {
+                    let mut steam_save = shared.steam_save.write();
+                    Self::rewatch(
+                        shared,
+                        slot,
+                        side,
+                        &steam_save.watch_dir(),
+                        &resolve_links(&save_dir),
+                    )?;
+                    steam_save.set_save_dir(save_dir);
+
+                    Ok(())
+                }),
+            Side::Xbox => Self::locate_xbox_save_dir(configured)
+                .map_err(Error::from)
+                .and_then(|save_dir| {
+                    let mut xbox_save = shared.xbox_save.write();
+                    Self::rewatch(
+                        shared,
+                        slot,
+                        side,
+                        &xbox_save.watch_dir(),
+                        &resolve_links(&save_dir),
+                    )?;
+                    xbox_save.set_save_dir(save_dir);
+
+                    Ok(())
+                }),
+        };
+        if let Err(err) = &result {
+            warn!("{side:?} rediscovery error: {:?}", err);
+        }
+
+        result
+    }
+
+    /// Count a sync that found no save on `side`, and rediscover the side after too many.
+    ///
+    /// The game may have been moved to another Steam library while Steeve is running, which
+    /// leaves the watcher on the old, empty save directory. Only discovered directories are
+    /// rediscovered, never configured ones.
+    fn note_missing_save(shared: &Shared, side: Side) {
+        if let Some(slot) = Self::count_missing_save(shared, side).and_then(|slot| slot.upgrade()) {
+            info!("No {side:?} save found {RESCAN_AFTER_MISSES} times, looking for it again");
+            let _ = Self::rediscover_side(shared, &slot, side, &None);
+        }
+    }
+
+    /// Count a sync that found no save on `side`.
+    ///
+    /// Returns the side's watcher when it is time to rediscover the side, and starts counting
+    /// over.
+    fn count_missing_save(shared: &Shared, side: Side) -> Option<Weak<Mutex<Option<SideWatcher>>>> {
+        let mut rescans = shared.rescans.lock();
+        let rescan = rescans.get_mut(&side)?;
+        rescan.misses += 1;
+        if rescan.misses < RESCAN_AFTER_MISSES {
+            return None;
+        }
+        rescan.misses = 0;
+
+        Some(rescan.slot.clone())
+    }
+
+    /// Move a watcher from the `old` save directory to the `new` one, if they differ.
+    ///
+    /// A new directory that does not exist yet is waited for, like at startup.
+    fn rewatch(
+        shared: &Shared,
+        slot: &Mutex<Option<SideWatcher>>,
+        side: Side,
+        old: &Path,
+        new: &Path,
+    ) -> Result<(), Error> {
+        if old == new {
+            return Ok(());
+        }
+
+        info!("Steeve found a new {side:?} save directory: {old:?} -> {new:?}");
+
+        Self::retarget(shared, slot, side, new).map(|_| ())
+    }
+
+    /// Sync both saves once the game has exited, if any syncs were held, until Steeve is dropped.
+    fn watch_game(shared: &Arc<Shared>) {
+        let shared = Arc::downgrade(shared);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(GAME_POLL_INTERVAL);
+            let Some(shared) = shared.upgrade() else {
+                break;
+            };
+            Self::sync_released(&shared);
+        });
+    }
+
+    /// Sync both saves for [`Steeve::watch_game`], if syncs were held and the game has exited.
+    ///
+    /// Held syncs are not retried one by one, so however long the game runs, nothing is dropped.
+    fn sync_released(shared: &Shared) {
+        if shared.is_paused() || !shared.game.as_ref().is_some_and(GameWatch::release) {
+            return;
+        }
+
+        info!("Deep Rock Galactic exited, syncing");
+        match shared.sync_both() {
+            Err(err) if !err.is_benign() => {
+                warn!("Sync error after the game exited: {:?}", err);
+                shared.events.emit(SteeveEvent::SyncError(err));
+            }
+            _ => (),
+        }
+    }
+
+    /// Event handler for one side's save directory.
+    ///
+    /// A change to a save on `side` is synced over its pair on the other side.
+    fn handle_event(shared: &Arc<Shared>, side: Side, event: DebouncedEvent) {
+        Self::retry_event(shared, side, event, 0);
+    }
+
+    /// Handle a file system event for `side` that was already deferred `defers` times in a row.
+    fn retry_event(shared: &Arc<Shared>, side: Side, event: DebouncedEvent, defers: usize) {
+        if shared.is_paused() {
+            debug!("Syncing is paused, ignoring event for {:?}", event.path);
+            return;
+        }
+
+        let path = match side {
+            Side::Steam => Self::synced_event_path(&*shared.steam_save.read(), &event.path),
+            Side::Xbox => Self::synced_event_path(&*shared.xbox_save.read(), &event.path),
+        };
+        let Some(path) = path else {
+            return;
+        };
+
+        debug!("Got event for {side:?} path: {:?}", path);
+        if shared.is_own_write(side, &path) {
+            debug!("Ignoring our own write to {side:?} save");
+            return;
+        }
+
+        let dest = side.other();
+        match shared.sync_from(side, &path) {
+            Err(SaveError::SaveDirMissing(dir)) => {
+                info!(
+                    "The {dest:?} save directory {dir:?} does not exist, has it been played yet?"
+                );
+                Self::note_missing_save(shared, dest);
+            }
+            Err(SaveError::NoSave) => Self::note_missing_save(shared, dest),
+            Err(SaveError::Deferred | SaveError::StillWriting(_)) => {
+                Self::defer(shared, side, event, defers);
+            }
+            Err(err) if !err.is_benign() => {
+                warn!("{dest:?} save error: {:?}", err);
+                shared.record_error(dest, &err);
+                shared.events.emit(SteeveEvent::SyncError(err));
+            }
+            Ok(_) => shared.found_save(dest),
+            _ => (),
+        }
+
+        // Either way, the Steam save may now be newer than the shared folder
+        shared.sync_remote_logged();
+    }
+
+    /// Get the save file that an event refers to, if it takes part in syncing.
+    fn synced_event_path<S: SteeveSave>(save: &S, path: &Path) -> Option<PathBuf> {
+        Self::event_save_path(save, path).filter(|path| save.is_synced_save(path))
+    }
+
+    /// Get the save file that an event refers to, if any.
+    ///
+    /// The game may write to a temporary file and rename it over the save. The debouncer reports
+    /// a rename as an event for each path, so the temporary file is ignored and the rename
+    /// destination is synced once.
+    ///
+    /// Events may also report a changed directory instead of the save file, e.g. when FSEvents
+    /// on macOS coalesces them, or after a bulk change. When the directory is the save directory,
+    /// one of its parents, or inside it, the save directory is scanned for the save.
+    fn event_save_path<S: SteeveSave>(save: &S, path: &Path) -> Option<PathBuf> {
+        if path.is_dir() {
+            let related = |dir: &Path| dir.starts_with(path) || path.starts_with(dir);
+            if !related(save.save_dir()) && !related(&save.watch_dir()) {
+                return None;
+            }
+
+            return save.locate_save_path().map(|(path, _)| path);
+        }
+
+        S::save_file(path).map(|_| path.to_path_buf())
+    }
+
+    /// Sync with the shared folder every [`REMOTE_POLL_INTERVAL`], until Steeve is dropped.
+    fn poll_remote(shared: &Arc<Shared>) {
+        let shared = Arc::downgrade(shared);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(REMOTE_POLL_INTERVAL);
+            let Some(shared) = shared.upgrade() else {
+                break;
+            };
+            if !shared.is_paused() {
+                shared.sync_remote_logged();
+            }
+        });
+    }
+
+    /// Sync both sides every `interval`, until Steeve is dropped.
+    ///
+    /// Nothing is synced while paused or stopped. Saves written by these syncs are recorded like
+    /// any other, so the watchers ignore them.
+    fn poll_saves(shared: &Arc<Shared>, interval: Duration) {
+        let shared = Arc::downgrade(shared);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+            let Some(shared) = shared.upgrade() else {
+                break;
+            };
+            Self::poll_once(&shared);
+        });
+    }
+
+    /// Sync both sides once for [`Steeve::poll_saves`], unless paused or stopped.
+    fn poll_once(shared: &Shared) {
+        if shared.is_paused() || shared.is_stopped() {
+            return;
+        }
+        match shared.sync_both() {
+            Err(err) if !err.is_benign() => {
+                warn!("Periodic sync error: {:?}", err);
+                shared.events.emit(SteeveEvent::SyncError(err));
+            }
+            _ => (),
+        }
+    }
+
+    /// Run the event handler for `side` again after a delay, unless the event was already
+    /// deferred too many times.
+    ///
+    /// An event that is already waiting for a retry is not queued again.
+    fn defer(shared: &Arc<Shared>, side: Side, event: DebouncedEvent, defers: usize) {
+        if defers >= MAX_DEFERS {
+            warn!("Giving up on the sync deferred for path: {:?}", event.path);
+            return;
+        }
+        debug!("Sync deferred for path: {:?}", event.path);
+
+        let mut queue = shared.deferred.lock();
+        let queued = queue
+            .events
+            .iter()
+            .any(|(queued, other, _)| *queued == side && other.path == event.path);
+        if !queued {
+            queue.events.push((side, event, defers + 1));
+        }
+        if !queue.timer {
+            queue.timer = true;
+            Self::retry_deferred(shared);
+        }
+    }
+
+    /// Retry the deferred events after a delay, on a single thread that stops when there are
+    /// none left.
+    fn retry_deferred(shared: &Arc<Shared>) {
+        let shared = Arc::downgrade(shared);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(DEFER_DELAY);
+            let Some(shared) = shared.upgrade() else {
+                break;
+            };
+            let events = {
+                let mut queue = shared.deferred.lock();
+                if queue.events.is_empty() {
+                    queue.timer = false;
+                    break;
+                }
+                std::mem::take(&mut queue.events)
+            };
+            for (side, event, defers) in events {
+                Self::retry_event(&shared, side, event, defers);
+            }
+        });
+    }
+}
+
+impl Shared {
+    /// Sync the newest save over the older one, in whichever direction is needed.
+    ///
+    /// Every save slot is synced with its pair on the other side. A slot that fails to sync does
+    /// not keep the others from syncing; when several fail, all of their errors are returned in
+    /// [`SaveError::Several`]. Each error is also recorded for the side that was not synced.
+    ///
+    /// While the game holds syncs, nothing is synced and [`SaveError::GameRunning`] is returned.
+    fn sync_both(&self) -> Result<(), SaveError> {
+        if self.holds_for_game() {
+            return Err(SaveError::GameRunning);
+        }
+
+        let mut errors = Vec::new();
+        let mut keep = |side: Option<Side>, result: Result<_, SaveError>| match result {
+            Err(err) if !err.is_benign() => {
+                if let Some(side) = side {
+                    self.record_error(side, &err);
+                }
+                errors.push(err);
+            }
+            _ => (),
+        };
+
+        if self.syncs_to(Side::Xbox) {
+            let steam_paths = self.steam_save.read().locate_save_paths();
+            for (path, _) in steam_paths {
+                keep(Some(Side::Xbox), self.sync_to_xbox(&path, false).map(drop));
+            }
+        }
+
+        if self.syncs_to(Side::Steam) {
+            let xbox_paths = self.xbox_save.read().locate_save_paths();
+            for (path, _) in xbox_paths {
+                keep(
+                    Some(Side::Steam),
+                    self.sync_to_steam(&path, false).map(drop),
+                );
+            }
+        }
+
+        // A save still arriving in a shared folder is picked up by a later poll
+        for err in self.sync_remote() {
+            if !matches!(err, SaveError::StillWriting(_)) {
+                keep(None, Err(err));
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(SaveError::Several(errors)),
+        }
+    }
+
+    /// Sync the Steam saves with every shared folder, and pass pulled saves on to Xbox.
+    ///
+    /// The Steam saves are the hub: a save pulled from one shared folder replaces the Steam save,
+    /// so it is pushed to the shared folders that were synced before it, in the same pass. The
+    /// newest save thus reaches every location, and a save that changed in two of them is a
+    /// conflict. A folder that fails does not keep the others from syncing.
+    ///
+    /// Returns the errors, except for expected skips.
+    fn sync_remote(&self) -> Vec<SaveError> {
+        let mut pulled = Vec::new();
+        let mut results = Vec::with_capacity(self.remotes.len());
+        let mut last_pull = 0;
+        for (i, remote) in self.remotes.iter().enumerate() {
+            let result = remote.sync(&self.steam_save.read());
+            if let Ok(paths) = &result {
+                if !paths.is_empty() {
+                    last_pull = i;
+                }
+                pulled.extend(paths.iter().cloned());
+            }
+            results.push(result);
+        }
+        for (result, remote) in results.iter_mut().zip(&self.remotes).take(last_pull) {
+            *result = remote.sync(&self.steam_save.read());
+            if let Ok(paths) = result {
+                pulled.extend(paths.iter().cloned());
+            }
+        }
+
+        let mut errors = results
+            .into_iter()
+            .filter_map(Result::err)
+            .filter(|err| !err.is_benign())
+            .collect::<Vec<_>>();
+
+        pulled.sort();
+        pulled.dedup();
+        for path in pulled {
+            self.last_syncs
+                .lock()
+                .insert(Side::Steam, SystemTime::now());
+            if !self.syncs_to(Side::Xbox) {
+                continue;
+            }
+            match self.sync_to_xbox(&path, false) {
+                Err(err) if !err.is_benign() => errors.push(err),
+                _ => (),
+            }
+        }
+
+        errors
+    }
+
+    /// Let the local saves win over changes in the shared folder on the next sync.
+    ///
+    /// A restored or forced save is the one the user chose, so it also resolves conflicts with
+    /// the shared folder.
+    fn keep_local_saves(&self) {
+        for remote in &self.remotes {
+            remote.keep_local(&self.steam_save.read());
+        }
+    }
+
+    /// Copy every save in `direction`, even over newer saves.
+    fn force_sync(&self, direction: Direction) -> Result<(), SaveError> {
+        if self.holds_for_game() {
+            return Err(SaveError::GameRunning);
+        }
+
+        let from_paths = match direction {
+            Direction::SteamToXbox => self.steam_save.read().locate_save_paths(),
+            Direction::XboxToSteam => self.xbox_save.read().locate_save_paths(),
+        };
+        if from_paths.is_empty() {
+            return Err(SaveError::NoSave);
+        }
+
+        for (path, _) in from_paths {
+            match direction {
+                Direction::SteamToXbox => self.sync_to_xbox(&path, true)?,
+                Direction::XboxToSteam => self.sync_to_steam(&path, true)?,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Sync with the shared folders, logging and reporting errors instead of returning them.
+    fn sync_remote_logged(&self) {
+        if self.holds_for_game() {
+            return;
+        }
+
+        for err in self.sync_remote() {
+            match err {
+                SaveError::StillWriting(path) => {
+                    debug!("Waiting for {path:?} to settle in the shared folder");
+                }
+                err => {
+                    warn!("Shared folder error: {:?}", err);
+                    self.events.emit(SteeveEvent::SyncError(err));
+                }
+            }
+        }
+    }
+
+    /// Compare the current Steam and Xbox saves.
+    fn compare(&self) -> Result<CompareResult, SaveError> {
+        let steam_save = self.steam_save.read();
+        let steam_path = steam_save.locate_save_path();
+        let xbox_path = self.xbox_save.read().locate_save_path();
+        let (Some((steam_path, _)), Some((xbox_path, _))) = (&steam_path, &xbox_path) else {
+            return Ok(CompareResult::Missing {
+                steam: steam_path.is_none(),
+                xbox: xbox_path.is_none(),
+            });
+        };
+
+        if steam_save.hash(steam_path)? == steam_save.hash(xbox_path)? {
+            return Ok(CompareResult::Identical);
+        }
+
+        let steam_time = steam_path.metadata()?.modified()?;
+        let xbox_time = xbox_path.metadata()?.modified()?;
+        Ok(
+            match (
+                steam_time.duration_since(xbox_time),
+                xbox_time.duration_since(steam_time),
+            ) {
+                (Ok(by), _) if !by.is_zero() => CompareResult::SteamNewer(by),
+                (_, Ok(by)) if !by.is_zero() => CompareResult::XboxNewer(by),
+                _ => CompareResult::SameAge,
+            },
+        )
+    }
+
+    /// Check if syncs may copy saves to `side`.
+    fn syncs_to(&self, side: Side) -> bool {
+        !self.read_only.contains(&side)
+    }
+
+    /// Copy a save from `side` over its pair on the other side.
+    fn sync_from(&self, side: Side, from: &Path) -> Result<SyncOutcome, SaveError> {
+        if self.holds_for_game() {
+            return Err(SaveError::GameRunning);
+        }
+
+        match side {
+            Side::Steam => self.sync_to_xbox(from, false),
+            Side::Xbox => self.sync_to_steam(from, false),
+        }
+    }
+
+    /// Copy a Steam save over the Xbox save, see [`Shared::force_sync`] for `force`.
+    fn sync_to_xbox(&self, from: &Path, force: bool) -> Result<SyncOutcome, SaveError> {
+        let mut dest = None;
+        let pre_sync = |to: &Path| {
+            dest = Some(to.to_path_buf());
+            self.pre_sync(Direction::SteamToXbox, from, to)
+        };
+        // The lock is released before record_sync takes it again, or a queued writer deadlocks
+        let result = {
+            let xbox_save = self.xbox_save.read();
+            if force {
+                xbox_save.force_copy_save(from, pre_sync)
+            } else {
+                xbox_save.copy_save(from, pre_sync)
+            }
+        };
+        self.log_mtime_skip(from, "Xbox", &result);
+        if let (Ok(SyncOutcome::Copied { .. }), Some(dest)) = (&result, dest) {
+            self.record_sync(Side::Xbox, Direction::SteamToXbox, from, dest);
+        }
+
+        result
+    }
+
+    /// Copy an Xbox save over the Steam save, see [`Shared::force_sync`] for `force`.
+    fn sync_to_steam(&self, from: &Path, force: bool) -> Result<SyncOutcome, SaveError> {
+        let mut dest = None;
+        let pre_sync = |to: &Path| {
+            dest = Some(to.to_path_buf());
+            self.pre_sync(Direction::XboxToSteam, from, to)
+        };
+        // The lock is released before record_sync takes it again, or a queued writer deadlocks
+        let result = {
+            let steam_save = self.steam_save.read();
+            if force {
+                steam_save.force_copy_save(from, pre_sync)
+            } else {
+                steam_save.copy_save(from, pre_sync)
+            }
+        };
+        self.log_mtime_skip(from, "Steam", &result);
+        if let (Ok(SyncOutcome::Copied { .. }), Some(dest)) = (&result, dest) {
+            self.record_sync(Side::Steam, Direction::XboxToSteam, from, dest);
+        }
+
+        result
+    }
+
+    /// Explain why nothing happened when a sync is skipped because the destination is newer.
+    ///
+    /// Only the first skip for each path is logged. A successful copy resets it.
+    fn log_mtime_skip(&self, from: &Path, to_name: &str, result: &Result<SyncOutcome, SaveError>) {
+        let mut mtime_skips = self.mtime_skips.lock();
+        match result {
+            Err(SaveError::ModifyTime) if mtime_skips.insert(from.to_path_buf()) => {
+                info!("Not syncing {from:?}, the {to_name} save is newer or the same age");
+            }
+            Ok(SyncOutcome::Copied { .. }) => {
+                mtime_skips.remove(from);
+            }
+            _ => (),
+        }
+    }
+
+    /// Reset the count of syncs that found no save on `side`.
+    fn found_save(&self, side: Side) {
+        if let Some(rescan) = self.rescans.lock().get_mut(&side) {
+            rescan.misses = 0;
+        }
+    }
+
+    /// Check if file system events are ignored.
+    fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Get the directory to watch for one side's saves.
+    fn watch_dir(&self, side: Side) -> PathBuf {
+        match side {
+            Side::Steam => self.steam_save.read().watch_dir(),
+            Side::Xbox => self.xbox_save.read().watch_dir(),
+        }
+    }
+
+    /// Get the state of one side's watcher.
+    fn watch_state(&self, side: Side) -> WatcherStatus {
+        self.watchers
+            .lock()
+            .get(&side)
+            .cloned()
+            .unwrap_or_else(|| WatcherStatus {
+                state: WatchState::Stopped,
+                since: SystemTime::now(),
+                reason: "watching has not started".to_string(),
+            })
+    }
+
+    /// Check if neither side is watched, e.g. after [`Steeve::stop`].
+    fn is_stopped(&self) -> bool {
+        [Side::Steam, Side::Xbox]
+            .into_iter()
+            .all(|side| self.watch_state(side).state == WatchState::Stopped)
+    }
+
+    /// Change the state of one side's watcher.
+    fn set_watch_state(&self, side: Side, state: WatchState, reason: impl Into<String>) {
+        let reason = reason.into();
+        debug!("{side:?} watcher is {state:?}: {reason}");
+
+        let status = WatcherStatus {
+            state,
+            since: SystemTime::now(),
+            reason,
+        };
+        self.watchers.lock().insert(side, status);
+    }
+
+    /// Mark a watcher as watching again when it delivers events after an error.
+    fn recover_watch_state(&self, side: Side) {
+        let failed = self
+            .watchers
+            .lock()
+            .get(&side)
+            .is_some_and(|status| matches!(status.state, WatchState::Error(_)));
+        if failed {
+            let reason = "events arrived again after an error";
+            self.set_watch_state(side, WatchState::Watching, reason);
+        }
+    }
+
+    /// Remember a successful copy from `from` to `dest` on `side`, and report it.
+    ///
+    /// File system events for the destination are ignored until it changes again, or for at most
+    /// [`OWN_WRITE_WINDOW`].
+    fn record_sync(&self, side: Side, direction: Direction, from: &Path, dest: PathBuf) {
+        self.last_syncs.lock().insert(side, SystemTime::now());
+        self.last_errors.lock().remove(&side);
+        if let Some(on_sync) = &self.on_sync {
+            on_sync.spawn(side, &dest, direction);
+        }
+        self.events.emit(SteeveEvent::SaveCopied {
+            direction,
+            from: from.to_path_buf(),
+            to: dest.clone(),
+            dry_run: false,
+        });
+
+        if let Ok(hash) = self.hash_save(side, &dest) {
+            let expires = Instant::now() + OWN_WRITE_WINDOW;
+            self.own_writes
+                .lock()
+                .insert(dest, OwnWrite { hash, expires });
+        }
+    }
+
+    /// Remember a failed sync to `side`, until the next successful one.
+    fn record_error(&self, side: Side, err: &SaveError) {
+        self.last_errors.lock().insert(side, err.to_string());
+    }
+
+    /// Get the sync state of one side.
+    fn side_status<S: SteeveSave>(&self, save: &S) -> SideStatus {
+        let side = save.side();
+
+        SideStatus {
+            last_sync: self.last_syncs.lock().get(&side).copied(),
+            last_error: self.last_errors.lock().get(&side).cloned(),
+            save_path: save.locate_save_path().map(|(path, _)| path),
+            backups: save.backup_stats().map_or(0, |stats| stats.count),
+            watcher: self.watch_state(side),
+        }
+    }
+
+    /// Check if the save at `path` on `side` still has the contents left by a recent sync.
+    ///
+    /// A single write can be reported by several events, especially with a short debounce, so
+    /// the save is remembered until it is modified by someone else, or the window expires.
+    fn is_own_write(&self, side: Side, path: &Path) -> bool {
+        let mut own_writes = self.own_writes.lock();
+        let Some(written) = own_writes.get(path) else {
+            return false;
+        };
+
+        let unchanged = written.expires > Instant::now()
+            && self
+                .hash_save(side, path)
+                .is_ok_and(|hash| hash == written.hash);
+        if !unchanged {
+            own_writes.remove(path);
+        }
+
+        unchanged
+    }
+
+    /// Hash a save on `side`.
+    fn hash_save(&self, side: Side, path: &Path) -> std::io::Result<Vec<u8>> {
+        match side {
+            Side::Steam => self.steam_save.read().hash(path),
+            Side::Xbox => self.xbox_save.read().hash(path),
+        }
+    }
+
+    /// Check if syncs must wait for the game to exit, see [`SteeveBuilder::wait_for_game_exit`].
+    ///
+    /// This is checked before anything is validated or backed up. Held syncs are not deferred;
+    /// both saves are synced once the game exits instead, see [`Steeve::watch_game`].
+    fn holds_for_game(&self) -> bool {
+        let hold = self.game.as_ref().is_some_and(GameWatch::holds_sync);
+        if hold {
+            debug!("Holding the sync until the game exits");
+        }
+
+        hold
+    }
+
+    /// Ask the pre-sync hook whether to go ahead with a sync.
+    ///
+    /// The hook is called without holding its lock, so it may replace itself.
+    fn pre_sync(&self, direction: Direction, from: &Path, to: &Path) -> SyncDecision {
+        let hook = self.pre_sync.read().clone();
+        match hook {
+            Some(hook) => hook(&SyncEvent {
+                direction,
+                from: from.to_path_buf(),
+                to: to.to_path_buf(),
+            }),
+            None => SyncDecision::Proceed,
+        }
+    }
+}
+
+impl Debug for Shared {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Shared")
+            .field("game", &self.game)
+            .field("steam_save", &self.steam_save)
+            .field("xbox_save", &self.xbox_save)
+            .field(
+                "pre_sync",
+                &self.pre_sync.read().as_ref().map(|_| "PreSyncHook"),
+            )
+            .field("mtime_skips", &self.mtime_skips)
+            .field("own_writes", &self.own_writes)
+            .field("last_syncs", &self.last_syncs)
+            .field("last_errors", &self.last_errors)
+            .field("on_sync", &self.on_sync)
+            .field("watchers", &self.watchers)
+            .field("events", &self.events)
+            .field("paused", &self.paused)
+            .field("rescans", &self.rescans)
+            .field("remotes", &self.remotes)
+            .field("read_only", &self.read_only)
+            .field("deferred", &self.deferred)
+            .finish()
+    }
+}
+
+impl Debug for Steeve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Steeve")
+            .field("shared", &self.shared)
+            .field("lock", &self.lock)
+            .field("trash", &self.trash)
+            .field("data_dir", &self.data_dir)
+            .field("steam_save_dir", &self.steam_save_dir)
+            .field("xbox_save_dir", &self.xbox_save_dir)
+            .field("watch_config", &self.watch_config)
+            .field("steam_watcher", &self.steam_watcher)
+            .field("xbox_watcher", &self.xbox_watcher)
+            .field("remote_watchers", &self.remote_watchers)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{read_body, write_save, STEAM_NAME, XBOX_NAME};
+    use notify_debouncer_mini::DebouncedEventKind;
+    use std::fs::File;
+    use std::sync::atomic::{AtomicBool, AtomicUsize};
+    use tempfile::TempDir;
+
+    /// Create shared state with save and backup directories for both sides in `dir`.
+    fn shared(dir: &TempDir) -> Arc<Shared> {
+        paired_shared(dir, &[])
+    }
+
+    /// Create the shared state for saves in `dir`, with Steam and Xbox slots paired.
+    fn paired_shared(dir: &TempDir, pairs: &[(&str, &str)]) -> Arc<Shared> {
+        let options = SaveOptions {
+            slot_pairs: pairs
+                .iter()
+                .map(|&(steam, xbox)| (steam.to_string(), xbox.to_string()))
+                .collect(),
+            ..SaveOptions::for_test()
+        };
+        let events = options.events.clone();
+        let path = |name: &str| {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            path
+        };
+
+        Arc::new(Shared {
+            game: None,
+            steam_save: RwLock::new(
+                SteamSave::new(options.clone(), path("steam"), path("backup/steam")).unwrap(),
+            ),
+            xbox_save: RwLock::new(
+                XboxSave::new(options, path("xbox"), path("backup/xbox")).unwrap(),
+            ),
+            pre_sync: RwLock::new(None),
+            mtime_skips: Mutex::new(HashSet::new()),
+            own_writes: Mutex::new(HashMap::new()),
+            last_syncs: Mutex::new(HashMap::new()),
+            last_errors: Mutex::new(HashMap::new()),
+            on_sync: None,
+            watchers: Mutex::new(HashMap::new()),
+            events,
+            paused: AtomicBool::new(false),
+            rescans: Mutex::new(HashMap::new()),
+            remotes: Vec::new(),
+            read_only: HashSet::new(),
+            deferred: Mutex::new(DeferQueue::default()),
+        })
+    }
+
+    #[test]
+    fn temp_then_rename_syncs_once() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+
+        let syncs = Arc::new(AtomicUsize::new(0));
+        let counter = syncs.clone();
+        *shared.pre_sync.write() = Some(Arc::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            SyncDecision::Proceed
+        }));
+
+        let steam_dir = dir.path().join("steam");
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&xbox_path, b"old", Duration::from_secs(60 * 60));
+
+        // The game writes a temporary file, then renames it over the save
+        let temp_path = steam_dir.join(format!("{STEAM_NAME}.tmp"));
+        let save_path = steam_dir.join(STEAM_NAME);
+        write_save(&temp_path, b"new", Duration::ZERO);
+        std::fs::rename(&temp_path, &save_path).unwrap();
+
+        // The debouncer reports both sides of the rename
+        for path in [temp_path, save_path.clone()] {
+            let event = DebouncedEvent {
+                path,
+                kind: DebouncedEventKind::Any,
+            };
+            Steeve::handle_event(&shared, Side::Steam, event);
+        }
+
+        assert_eq!(syncs.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            std::fs::read(&xbox_path).unwrap(),
+            std::fs::read(&save_path).unwrap(),
+        );
+    }
+
+    #[test]
+    fn directory_event_syncs_save() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+
+        let steam_dir = dir.path().join("steam");
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&xbox_path, b"old", Duration::from_secs(60 * 60));
+        write_save(&steam_dir.join(STEAM_NAME), b"new", Duration::ZERO);
+
+        // Unrelated directories are still ignored
+        let event = DebouncedEvent {
+            path: dir.path().join("backup"),
+            kind: DebouncedEventKind::Any,
+        };
+        Steeve::handle_event(&shared, Side::Steam, event);
+        assert_ne!(
+            std::fs::read(&xbox_path).unwrap(),
+            std::fs::read(steam_dir.join(STEAM_NAME)).unwrap()
+        );
+
+        // The event names the save directory, not the save file
+        let event = DebouncedEvent {
+            path: steam_dir.clone(),
+            kind: DebouncedEventKind::Any,
+        };
+        Steeve::handle_event(&shared, Side::Steam, event);
+        assert_eq!(
+            std::fs::read(&xbox_path).unwrap(),
+            std::fs::read(steam_dir.join(STEAM_NAME)).unwrap(),
+        );
+    }
+
+    #[test]
+    fn backup_inside_save_dir_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+
+        let backup_dir = dir.path().join("backup");
+        assert!(Steeve::check_backup_dirs(&shared, &backup_dir).is_ok());
+
+        let backup_dir = dir.path().join("steam").join("Backups");
+        std::fs::create_dir(&backup_dir).unwrap();
+        assert!(matches!(
+            Steeve::check_backup_dirs(&shared, &backup_dir),
+            Err(Error::BackupInsideSaveDir(..)),
+        ));
+    }
+
+    #[test]
+    fn second_instance_is_locked_out() {
+        let dir = TempDir::new().unwrap();
+
+        let lock = Steeve::lock(dir.path()).unwrap();
+        assert!(matches!(
+            Steeve::lock(dir.path()),
+            Err(Error::AlreadyRunning),
+        ));
+
+        drop(lock);
+        assert!(Steeve::lock(dir.path()).is_ok());
+    }
+
+    #[test]
+    fn explicit_save_dirs_are_created() {
+        let dir = TempDir::new().unwrap();
+
+        let save_dir = dir.path().join("saves").join("steam");
+        assert!(Steeve::prepare_save_dir(Side::Steam, Some(&save_dir)).is_ok());
+        assert!(save_dir.is_dir());
+
+        // A file is in the way
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"").unwrap();
+        assert!(matches!(
+            Steeve::prepare_save_dir(Side::Xbox, Some(&file.join("xbox"))),
+            Err(Error::SaveDir(Side::Xbox, _)),
+        ));
+    }
+
+    #[test]
+    fn discovery_is_retried() {
+        let mut attempts = 0;
+        let result = Steeve::retry_discovery(3, Duration::ZERO, || {
+            attempts += 1;
+            match attempts {
+                1 => Err(SaveError::SteamDir),
+                2 => Err(SaveError::SteamApp(Vec::new())),
+                _ => Ok(attempts),
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        // Only discovery errors are retried, and only so often
+        let mut attempts = 0;
+        let result = Steeve::retry_discovery(3, Duration::ZERO, || -> Result<(), _> {
+            attempts += 1;
+            Err(SaveError::NoSave)
+        });
+        assert!(matches!(result, Err(SaveError::NoSave)));
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0;
+        let result = Steeve::retry_discovery(3, Duration::ZERO, || -> Result<(), _> {
+            attempts += 1;
+            Err(SaveError::HomeDir)
+        });
+        assert!(matches!(result, Err(SaveError::HomeDir)));
+        assert_eq!(attempts, 4);
+    }
+
+    /// Create a Steeve for the saves in `dir`, without watching or a lock.
+    fn steeve(dir: &TempDir) -> Steeve {
+        Steeve {
+            shared: shared(dir),
+            lock: None,
+            trash: None,
+            data_dir: dir.path().to_path_buf(),
+            steam_save_dir: None,
+            xbox_save_dir: None,
+            watch_config: WatchConfig {
+                mode: WatchMode::Native,
+                debounce: Duration::from_millis(50),
+                overflow: OverflowStrategy::Reconcile,
+            },
+            steam_watcher: WatcherSlot::default(),
+            xbox_watcher: WatcherSlot::default(),
+            remote_watchers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ensure_watching_is_idempotent() {
+        let dir = TempDir::new().unwrap();
+        let mut steeve = steeve(&dir);
+        assert_eq!(steeve.status(), Status::Stopped);
+
+        steeve.ensure_watching().unwrap();
+        steeve.ensure_watching().unwrap();
+        assert!(steeve.steam_watcher.lock().is_some() && steeve.xbox_watcher.lock().is_some());
+        assert!(matches!(
+            Steeve::lock(dir.path()),
+            Err(Error::AlreadyRunning),
+        ));
+
+        // Watching starts again after a stop
+        steeve.stop().unwrap();
+        assert_eq!(steeve.status(), Status::Stopped);
+        assert_eq!(steeve.watcher_status(Side::Xbox).state, WatchState::Stopped);
+        steeve.ensure_watching().unwrap();
+        assert_ne!(steeve.status(), Status::Stopped);
+        assert_eq!(
+            steeve.watcher_status(Side::Xbox).state,
+            WatchState::Watching
+        );
+
+        // A missing save directory is waited for
+        steeve.stop().unwrap();
+        std::fs::remove_dir_all(dir.path().join("xbox")).unwrap();
+        steeve.ensure_watching().unwrap();
+        assert_eq!(
+            steeve.watcher_status(Side::Steam).state,
+            WatchState::Watching
+        );
+        let status = steeve.watcher_status(Side::Xbox);
+        assert_eq!(status.state, WatchState::WaitingForDir);
+        assert!(status.reason.contains("does not exist"));
+    }
+
+    #[test]
+    fn max_backups_is_limited() {
+        let result = Steeve::builder().max_backups(0).build();
+        assert!(matches!(result, Err(Error::MaxBackups)));
+        let result = Steeve::builder().xbox_max_backups(100_000).build();
+        assert!(matches!(result, Err(Error::MaxBackups)));
+        let result = Steeve::builder()
+            .max_backups(2000)
+            .max_backups_limit(1500)
+            .build();
+        assert!(matches!(result, Err(Error::MaxBackups)));
+    }
+
+    #[test]
+    fn broken_watch_is_replaced() {
+        let dir = TempDir::new().unwrap();
+        let mut steeve = steeve(&dir);
+        let xbox_dir = dir.path().join("xbox");
+        steeve.ensure_watching().unwrap();
+
+        let state = WatchState::Error("lost events".to_string());
+        steeve.shared.set_watch_state(Side::Xbox, state, "test");
+        let moved = Steeve::watch_again(&steeve.shared, &steeve.xbox_watcher, Side::Xbox);
+        assert!(moved.unwrap());
+        assert_eq!(
+            steeve.watcher_status(Side::Xbox).state,
+            WatchState::Watching
+        );
+        let watched = steeve.xbox_watcher.lock().as_ref().unwrap().watched.clone();
+        assert_eq!(watched, xbox_dir);
+
+        // A save directory that was removed is waited for
+        std::fs::remove_dir_all(&xbox_dir).unwrap();
+        let moved = Steeve::watch_again(&steeve.shared, &steeve.xbox_watcher, Side::Xbox);
+        assert!(!moved.unwrap());
+        assert_eq!(
+            steeve.watcher_status(Side::Xbox).state,
+            WatchState::WaitingForDir
+        );
+    }
+
+    #[test]
+    fn watch_follows_save_dir() {
+        let dir = TempDir::new().unwrap();
+        let mut steeve = steeve(&dir);
+        let xbox_dir = dir.path().join("xbox");
+        std::fs::remove_dir_all(&xbox_dir).unwrap();
+        steeve.ensure_watching().unwrap();
+
+        let wait_for = |state: WatchState| {
+            for _ in 0..100 {
+                if steeve.watcher_status(Side::Xbox).state == state {
+                    return true;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            false
+        };
+        assert_eq!(
+            steeve.watcher_status(Side::Xbox).state,
+            WatchState::WaitingForDir
+        );
+
+        // The watch moves onto the save directory once it is created
+        std::fs::create_dir(&xbox_dir).unwrap();
+        assert!(wait_for(WatchState::Watching));
+
+        // And back to the parent when it is removed again
+        std::fs::remove_dir(&xbox_dir).unwrap();
+        assert!(wait_for(WatchState::WaitingForDir));
+        std::fs::create_dir(&xbox_dir).unwrap();
+        assert!(wait_for(WatchState::Watching));
+
+        // Saves in the recreated directory are synced
+        write_save(&xbox_dir.join(XBOX_NAME), b"old", Duration::from_secs(60));
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        write_save(&steam_path, b"new", Duration::ZERO);
+        for _ in 0..100 {
+            if std::fs::read(xbox_dir.join(XBOX_NAME))
+                .unwrap()
+                .ends_with(b"new")
+            {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        panic!("The Steam save was not synced to the recreated Xbox save directory");
+    }
+
+    #[test]
+    fn zero_debounce_does_not_loop() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+
+        let to_xbox = Arc::new(AtomicUsize::new(0));
+        let to_steam = Arc::new(AtomicUsize::new(0));
+        let (xbox_counter, steam_counter) = (to_xbox.clone(), to_steam.clone());
+        *shared.pre_sync.write() = Some(Arc::new(move |event| {
+            match event.direction {
+                Direction::SteamToXbox => xbox_counter.fetch_add(1, Ordering::SeqCst),
+                Direction::XboxToSteam => steam_counter.fetch_add(1, Ordering::SeqCst),
+            };
+            SyncDecision::Proceed
+        }));
+
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&xbox_path, b"old", Duration::from_secs(60 * 60));
+
+        let mut watchers = Vec::new();
+        for (side, name) in [(Side::Steam, "steam"), (Side::Xbox, "xbox")] {
+            let config = WatchConfig {
+                mode: WatchMode::Native,
+                debounce: Duration::ZERO,
+                overflow: OverflowStrategy::Reconcile,
+            };
+            let path = dir.path().join(name);
+            let mode = RecursiveMode::Recursive;
+            let watcher = Steeve::start_watcher(&shared, config, side, Weak::new(), &path, mode);
+            watchers.push(watcher.unwrap());
+        }
+
+        let save_path = dir.path().join("steam").join(STEAM_NAME);
+        write_save(&save_path, b"new", Duration::ZERO);
+
+        // Wait for the sync, then give any echo time to come back
+        for _ in 0..100 {
+            if to_xbox.load(Ordering::SeqCst) > 0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        std::thread::sleep(Duration::from_millis(500));
+
+        assert!(to_xbox.load(Ordering::SeqCst) > 0);
+        assert_eq!(to_steam.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            std::fs::read(&xbox_path).unwrap(),
+            std::fs::read(&save_path).unwrap(),
+        );
+    }
+
+    #[test]
+    fn deferred_syncs_are_queued_once() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+
+        // The hook defers the sync and replaces itself, without deadlocking
+        let hook_shared = Arc::downgrade(&shared);
+        *shared.pre_sync.write() = Some(Arc::new(move |_| {
+            if let Some(shared) = hook_shared.upgrade() {
+                *shared.pre_sync.write() = Some(Arc::new(|_| SyncDecision::Defer));
+            }
+            SyncDecision::Defer
+        }));
+
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&xbox_path, b"old", Duration::from_secs(60 * 60));
+        write_save(&steam_path, b"new", Duration::ZERO);
+
+        let event = || DebouncedEvent {
+            path: steam_path.clone(),
+            kind: DebouncedEventKind::Any,
+        };
+        Steeve::handle_event(&shared, Side::Steam, event());
+        Steeve::handle_event(&shared, Side::Steam, event());
+        {
+            let queue = shared.deferred.lock();
+            assert!(queue.timer);
+            assert_eq!(queue.events.len(), 1);
+            assert_eq!(queue.events[0].2, 1);
+        }
+
+        // An event that was deferred too often is dropped
+        shared.deferred.lock().events.clear();
+        Steeve::retry_event(&shared, Side::Steam, event(), MAX_DEFERS);
+        assert!(shared.deferred.lock().events.is_empty());
+    }
+
+    static GAME_RUNNING: AtomicBool = AtomicBool::new(false);
+
+    #[test]
+    fn held_syncs_outlast_the_defer_limit() {
+        let dir = TempDir::new().unwrap();
+        let mut shared = shared(&dir);
+        Arc::get_mut(&mut shared).unwrap().game =
+            Some(GameWatch::with_check(Duration::ZERO, || {
+                GAME_RUNNING.load(Ordering::SeqCst)
+            }));
+
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&xbox_path, b"old", Duration::from_secs(60 * 60));
+        write_save(&steam_path, b"new", Duration::ZERO);
+
+        // Nothing is copied or backed up while the game runs, and the sync is not deferred
+        GAME_RUNNING.store(true, Ordering::SeqCst);
+        let event = DebouncedEvent {
+            path: steam_path.clone(),
+            kind: DebouncedEventKind::Any,
+        };
+        Steeve::handle_event(&shared, Side::Steam, event);
+        assert!(matches!(shared.sync_both(), Err(SaveError::GameRunning)));
+        assert!(shared.deferred.lock().events.is_empty());
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"old"));
+        assert!(shared.xbox_save.read().list_backups().unwrap().is_empty());
+
+        // A session longer than deferred syncs are retried for still syncs on exit
+        for _ in 0..=MAX_DEFERS {
+            Steeve::sync_released(&shared);
+        }
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"old"));
+
+        GAME_RUNNING.store(false, Ordering::SeqCst);
+        Steeve::sync_released(&shared);
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"new"));
+    }
+
+    #[test]
+    fn own_writes_are_not_echoed() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+
+        let copies = Arc::new(AtomicUsize::new(0));
+        let counter = copies.clone();
+        *shared.pre_sync.write() = Some(Arc::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            SyncDecision::Proceed
+        }));
+
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&xbox_path, b"old", Duration::from_secs(60 * 60));
+        write_save(&steam_path, b"new", Duration::ZERO);
+
+        let event = |path: &Path| DebouncedEvent {
+            path: path.to_path_buf(),
+            kind: DebouncedEventKind::Any,
+        };
+        Steeve::handle_event(&shared, Side::Steam, event(&steam_path));
+
+        // The copy is touched later, as if by a coarse clock or the game's storage layer
+        File::options()
+            .write(true)
+            .open(&xbox_path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(2))
+            .unwrap();
+        Steeve::handle_event(&shared, Side::Xbox, event(&xbox_path));
+        Steeve::handle_event(&shared, Side::Steam, event(&steam_path));
+
+        assert_eq!(copies.load(Ordering::SeqCst), 1);
+        assert!(shared.is_own_write(Side::Xbox, &xbox_path));
+
+        // The write is forgotten once the window expires
+        shared
+            .own_writes
+            .lock()
+            .get_mut(&xbox_path)
+            .unwrap()
+            .expires = Instant::now();
+        assert!(!shared.is_own_write(Side::Xbox, &xbox_path));
+    }
+
+    #[test]
+    fn paused_events_sync_on_resume() {
+        let dir = TempDir::new().unwrap();
+        let mut steeve = steeve(&dir);
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&xbox_path, b"old", Duration::from_secs(60 * 60));
+        write_save(&steam_path, b"new", Duration::ZERO);
+
+        steeve.pause();
+        assert!(steeve.is_paused());
+        let event = DebouncedEvent {
+            path: steam_path.clone(),
+            kind: DebouncedEventKind::Any,
+        };
+        Steeve::handle_event(&steeve.shared, Side::Steam, event);
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"old"));
+
+        steeve.resume(true).unwrap();
+        assert!(!steeve.is_paused());
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"new"));
+    }
+
+    #[test]
+    fn missing_saves_are_counted() {
+        let dir = TempDir::new().unwrap();
+        let shared = paired_shared(&dir, &[]);
+        let slot = Arc::new(Mutex::new(None));
+        let rescan = Rescan {
+            slot: Arc::downgrade(&slot),
+            misses: 0,
+        };
+        shared.rescans.lock().insert(Side::Steam, rescan);
+        let misses = |side| shared.rescans.lock().get(&side).map(|rescan| rescan.misses);
+
+        assert!(Steeve::count_missing_save(&shared, Side::Steam).is_none());
+        assert!(Steeve::count_missing_save(&shared, Side::Steam).is_none());
+        assert_eq!(misses(Side::Steam), Some(2));
+        shared.found_save(Side::Steam);
+        assert_eq!(misses(Side::Steam), Some(0));
+
+        // Configured sides are never rediscovered
+        assert!(Steeve::count_missing_save(&shared, Side::Xbox).is_none());
+        assert_eq!(misses(Side::Xbox), None);
+
+        // The side is rediscovered after too many misses, without really looking for Steam here,
+        // and the count starts over
+        let rediscover = (0..RESCAN_AFTER_MISSES)
+            .filter_map(|_| Steeve::count_missing_save(&shared, Side::Steam))
+            .collect::<Vec<_>>();
+        assert_eq!(rediscover.len(), 1);
+        assert!(rediscover[0].ptr_eq(&Arc::downgrade(&slot)));
+        assert_eq!(misses(Side::Steam), Some(0));
+    }
+
+    #[test]
+    fn compare_reports_newer_side() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+        assert_eq!(
+            shared.compare().unwrap(),
+            CompareResult::Missing {
+                steam: true,
+                xbox: true,
+            },
+        );
+
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&steam_path, b"save", Duration::ZERO);
+        assert_eq!(
+            shared.compare().unwrap(),
+            CompareResult::Missing {
+                steam: false,
+                xbox: true,
+            },
+        );
+
+        write_save(&xbox_path, b"save", Duration::from_secs(60));
+        assert_eq!(shared.compare().unwrap(), CompareResult::Identical);
+
+        write_save(&xbox_path, b"old", Duration::from_secs(60));
+        assert!(matches!(
+            shared.compare().unwrap(),
+            CompareResult::SteamNewer(by) if by >= Duration::from_secs(59),
+        ));
+
+        // Nothing was copied
+        assert_ne!(
+            std::fs::read(&xbox_path).unwrap(),
+            std::fs::read(&steam_path).unwrap(),
+        );
+    }
+
+    #[test]
+    fn sync_records_last_sync_per_side() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+        let before = SystemTime::now();
+
+        write_save(
+            &dir.path().join("steam").join(STEAM_NAME),
+            b"new",
+            Duration::ZERO,
+        );
+        write_save(
+            &dir.path().join("xbox").join(XBOX_NAME),
+            b"old",
+            Duration::from_secs(60),
+        );
+        shared.sync_both().unwrap();
+
+        let last_syncs = shared.last_syncs.lock();
+        assert!(last_syncs.get(&Side::Xbox).is_some_and(|&at| at >= before));
+        assert_eq!(last_syncs.get(&Side::Steam), None);
+    }
+
+    #[test]
+    fn sync_status_reports_each_side() {
+        let dir = TempDir::new().unwrap();
+        let steeve = steeve(&dir);
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&steam_path, b"new", Duration::ZERO);
+        write_save(&xbox_path, b"old", Duration::from_secs(60));
+
+        let err = SaveError::SaveDirMissing(dir.path().join("xbox"));
+        steeve.shared.record_error(Side::Xbox, &err);
+        assert_eq!(steeve.sync_status().xbox.last_error, Some(err.to_string()));
+
+        steeve.sync_now().unwrap();
+
+        let status = steeve.sync_status();
+        assert!(status.xbox.last_sync.is_some());
+        assert_eq!(status.xbox.last_error, None);
+        assert_eq!(status.xbox.save_path, Some(xbox_path));
+        assert_eq!(status.xbox.backups, 1);
+        assert_eq!(status.steam.last_sync, None);
+        assert_eq!(status.steam.save_path, Some(steam_path));
+        assert_eq!(status.steam.backups, 0);
+        assert_eq!(status.steam.watcher.state, WatchState::Stopped);
+        assert_eq!(
+            status.xbox.watcher.reason,
+            steeve.watcher_status(Side::Xbox).reason
+        );
+    }
+
+    #[test]
+    fn periodic_sync_without_events() {
+        let dir = TempDir::new().unwrap();
+        let mut steeve = steeve(&dir);
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&steam_path, b"new", Duration::ZERO);
+        write_save(&xbox_path, b"old", Duration::from_secs(60));
+
+        // Stopped and paused services are left alone
+        Steeve::poll_once(&steeve.shared);
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"old"));
+
+        steeve.pause();
+        steeve.ensure_watching().unwrap();
+        Steeve::poll_once(&steeve.shared);
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"old"));
+
+        // The watchers never see the save change, but the periodic sync copies it anyway
+        steeve.shared.paused.store(false, Ordering::SeqCst);
+        Steeve::poll_once(&steeve.shared);
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"new"));
+        assert!(!steeve.list_backups(Side::Xbox).unwrap().is_empty());
+
+        // Errors are recorded for the side that was not synced
+        steeve.pause();
+        std::fs::write(&steam_path, b"not a save").unwrap();
+        steeve.shared.paused.store(false, Ordering::SeqCst);
+        Steeve::poll_once(&steeve.shared);
+        let last_errors = steeve.shared.last_errors.lock();
+        assert!(last_errors.get(&Side::Xbox).is_some());
+        assert!(last_errors.get(&Side::Steam).is_none());
+    }
+
+    #[test]
+    fn initial_sync_copies_newer_save() {
+        let dir = TempDir::new().unwrap();
+        let steeve = steeve(&dir);
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+
+        // Without an Xbox save there is nothing to copy over
+        write_save(&steam_path, b"new", Duration::ZERO);
+        steeve.initial_sync();
+        assert!(!xbox_path.exists());
+
+        write_save(&xbox_path, b"old", Duration::from_secs(60));
+        steeve.initial_sync();
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"new"));
+
+        // The older save never wins
+        write_save(&steam_path, b"older", Duration::from_secs(120));
+        steeve.initial_sync();
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"new"));
+    }
+
+    #[test]
+    fn force_sync_overwrites_newer_save() {
+        let dir = TempDir::new().unwrap();
+        let steeve = steeve(&dir);
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        assert!(matches!(
+            steeve.force_sync(Direction::SteamToXbox),
+            Err(Error::Save(SaveError::NoSave)),
+        ));
+
+        write_save(&steam_path, b"edited", Duration::from_secs(60));
+        write_save(&xbox_path, b"newer", Duration::ZERO);
+        steeve.sync_now().unwrap();
+        assert!(std::fs::read(&steam_path).unwrap().ends_with(b"newer"));
+
+        write_save(&steam_path, b"edited", Duration::from_secs(60));
+        write_save(&xbox_path, b"newer", Duration::ZERO);
+        steeve.force_sync(Direction::SteamToXbox).unwrap();
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"edited"));
+
+        // The overwritten save was backed up
+        let backups = steeve.list_backups(Side::Xbox).unwrap();
+        let backup = std::fs::read(&backups.last().unwrap().path).unwrap();
+        assert!(backup.ends_with(b"newer"));
+
+        // Broken saves are never forced over good ones
+        std::fs::write(&steam_path, b"broken").unwrap();
+        assert!(steeve.force_sync(Direction::SteamToXbox).is_err());
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"edited"));
+    }
+
+    #[test]
+    fn one_way_sync_leaves_other_side_alone() {
+        let dir = TempDir::new().unwrap();
+        let mut steeve = steeve(&dir);
+        let shared = Arc::get_mut(&mut steeve.shared).unwrap();
+        shared.read_only.insert(Side::Steam);
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+
+        // The newer Xbox save is not copied to Steam
+        write_save(&steam_path, b"old", Duration::from_secs(60));
+        write_save(&xbox_path, b"new", Duration::ZERO);
+        steeve.initial_sync();
+        assert!(std::fs::read(&steam_path).unwrap().ends_with(b"old"));
+
+        // But Steam saves are still copied to Xbox
+        write_save(&steam_path, b"newer", Duration::ZERO);
+        steeve.sync_now().unwrap();
+        assert!(std::fs::read(&xbox_path).unwrap().ends_with(b"newer"));
+
+        // Only the Steam save directory is watched
+        steeve.ensure_watching().unwrap();
+        assert!(steeve.steam_watcher.lock().is_some());
+        assert!(steeve.xbox_watcher.lock().is_none());
+        let status = steeve.watcher_status(Side::Xbox);
+        assert_eq!(status.state, WatchState::Stopped);
+        assert!(status.reason.contains("disabled"));
+    }
+
+    #[test]
+    fn saves_reach_every_shared_folder() {
+        let dir = TempDir::new().unwrap();
+        let mut steeve = steeve(&dir);
+        let shared = Arc::get_mut(&mut steeve.shared).unwrap();
+        for name in ["desktop", "library"] {
+            let remote = RemoteSave::new(dir.path().join(name), Duration::ZERO, false).unwrap();
+            shared.remotes.push(remote);
+        }
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        let desktop_path = dir.path().join("desktop").join(STEAM_NAME);
+        let library_path = dir.path().join("library").join(STEAM_NAME);
+        let paths = [&steam_path, &xbox_path, &desktop_path, &library_path];
+        write_save(&steam_path, b"old", Duration::from_secs(120));
+        write_save(&xbox_path, b"old", Duration::from_secs(120));
+
+        // The newest save reaches every location, including folders synced before it was found
+        write_save(&library_path, b"library", Duration::from_secs(60));
+        steeve.sync_now().unwrap();
+        for path in paths {
+            assert_eq!(read_body(path), b"library", "{path:?}");
+        }
+
+        write_save(&desktop_path, b"desktop", Duration::ZERO);
+        steeve.sync_now().unwrap();
+        for path in paths {
+            assert_eq!(read_body(path), b"desktop", "{path:?}");
+        }
+
+        // Each shared folder is watched
+        steeve.ensure_watching().unwrap();
+        assert_eq!(steeve.remote_watchers.len(), 2);
+        steeve.stop().unwrap();
+        assert!(steeve.remote_watchers.is_empty());
+    }
+
+    #[test]
+    fn slots_are_synced_in_pairs() {
+        let dir = TempDir::new().unwrap();
+        let (steam_a, steam_b) = ("1_Player", "2_Player");
+        let (xbox_a, xbox_b) = (
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+            "BBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB",
+        );
+        let steam_path = |slot: &str| dir.path().join("steam").join(format!("{slot}.sav"));
+        let xbox_path = |slot: &str| dir.path().join("xbox").join(slot);
+        let read = |path: PathBuf| std::fs::read(path).unwrap();
+        let shared = shared(&dir);
+
+        // The newer Steam save goes to one pair, and the newer Xbox save to the other
+        write_save(&steam_path(steam_a), b"steam a", Duration::ZERO);
+        write_save(&xbox_path(xbox_a), b"xbox a", Duration::from_secs(60));
+        write_save(&steam_path(steam_b), b"steam b", Duration::from_secs(60));
+        write_save(&xbox_path(xbox_b), b"xbox b", Duration::ZERO);
+
+        // Without pairs, the saves could belong together either way
+        let Err(SaveError::Several(errors)) = shared.sync_both() else {
+            panic!("Every slot should fail to sync");
+        };
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(
+            &errors[0],
+            SaveError::AmbiguousSlot { slot, candidates } if slot == steam_a && candidates.len() == 2
+        ));
+        assert!(errors
+            .iter()
+            .all(|err| matches!(err, SaveError::AmbiguousSlot { .. })));
+        assert!(read(xbox_path(xbox_a)).ends_with(b"xbox a"));
+
+        let shared = paired_shared(&dir, &[(steam_a, xbox_a), (steam_b, xbox_b)]);
+        shared.sync_both().unwrap();
+        assert!(read(xbox_path(xbox_a)).ends_with(b"steam a"));
+        assert!(read(steam_path(steam_b)).ends_with(b"xbox b"));
+        assert!(read(steam_path(steam_a)).ends_with(b"steam a"));
+        assert!(read(xbox_path(xbox_b)).ends_with(b"xbox b"));
+    }
+
+    #[test]
+    fn sync_emits_events() {
+        let dir = TempDir::new().unwrap();
+        let shared = shared(&dir);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        *shared.events.handler.lock() = Some(Arc::new(move |event| {
+            let _ = sender.send(event);
+        }));
+
+        let steam_path = dir.path().join("steam").join(STEAM_NAME);
+        let xbox_path = dir.path().join("xbox").join(XBOX_NAME);
+        write_save(&steam_path, b"new", Duration::ZERO);
+        write_save(&xbox_path, b"old", Duration::from_secs(60));
+        shared.sync_both().unwrap();
+
+        let events = receiver.try_iter().collect::<Vec<_>>();
+        assert!(matches!(
+            events.as_slice(),
+            [
+                SteeveEvent::BackupCreated { side: Side::Xbox, .. },
+                SteeveEvent::SaveCopied { direction: Direction::SteamToXbox, from, to, dry_run: false },
+            ] if from == &steam_path && to == &xbox_path
+        ));
     }
 }
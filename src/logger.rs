@@ -1,85 +1,178 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
 use parking_lot::Mutex;
-use std::{collections::VecDeque, io::Write, ops::Deref, sync::Arc};
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::ops::Deref;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
 
-/// The logger holds log lines for [`MemLogger`].
+/// The logger holds log records for [`MemLogger`].
 ///
 /// It derefs to the inner mutex.
 ///
 /// # Examples
 ///
 /// ```
-/// # use steeve_sync::logger::Logger;
+/// # use steeve_sync::logger::{LogRecord, Logger};
+/// # use log::Level;
+/// # use time::OffsetDateTime;
 /// let logger = Logger::default();
 /// let mut guard = logger.lock();
-/// guard.push_back("Hello, world!".to_string());
+/// guard.push_back(LogRecord {
+///     level: Level::Info,
+///     timestamp: OffsetDateTime::now_utc(),
+///     message: "Hello, world!".to_string(),
+/// });
 /// assert_eq!(guard.len(), 1);
 /// ```
 #[derive(Clone, Debug, Default)]
-pub struct Logger(Arc<Mutex<VecDeque<String>>>);
+pub struct Logger {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+    subscribers: Arc<Mutex<Vec<Sender<LogRecord>>>>,
+}
+
+/// A single logged message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogRecord {
+    /// Severity of the message.
+    pub level: Level,
 
-/// An in-memory logger that removes old log lines with a configurable cap.
+    /// When the message was logged.
+    pub timestamp: OffsetDateTime,
+
+    /// The message itself.
+    pub message: String,
+}
+
+/// An in-memory logger that removes old log records with a configurable cap.
+///
+/// It implements [`Log`] directly, so records keep their level instead of being formatted into
+/// text first.
 #[derive(Debug)]
 pub struct MemLogger {
-    max_lines: usize,
-    buffer: Vec<u8>,
     logger: Logger,
+    max_records: usize,
+    level: LevelFilter,
+    offset: UtcOffset,
 }
 
 impl Deref for Logger {
-    type Target = Mutex<VecDeque<String>>;
+    type Target = Mutex<VecDeque<LogRecord>>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.records
+    }
+}
+
+impl Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = time::macros::format_description!(
+            "[year]-[month]-[day] [hour repr:24]:[minute]:[second].[subsecond digits:3]"
+        );
+        let timestamp = self.timestamp.format(format).map_err(|_| fmt::Error)?;
+
+        write!(f, "{timestamp} [{}] {}", self.level, self.message)
+    }
+}
+
+impl Logger {
+    /// Get every new log record as it is logged.
+    ///
+    /// Records logged before subscribing are not sent; they are still available through the
+    /// inner mutex. Dropping the receiver unsubscribes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use steeve_sync::logger::{Logger, MemLogger};
+    /// # use log::{Level, LevelFilter, Log, Record};
+    /// let logger = Logger::default();
+    /// let mem_logger = MemLogger::new(10, LevelFilter::Info, logger.clone());
+    /// let records = logger.subscribe();
+    ///
+    /// for (level, message) in [(Level::Info, "Synced"), (Level::Warn, "Save error")] {
+    ///     mem_logger.log(&Record::builder().level(level).args(format_args!("{message}")).build());
+    /// }
+    ///
+    /// // A GUI would drain this on each frame, and could show only warnings and errors
+    /// let warnings = records
+    ///     .try_iter()
+    ///     .filter(|record| record.level <= Level::Warn)
+    ///     .map(|record| record.message)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(warnings, ["Save error"]);
+    /// ```
+    pub fn subscribe(&self) -> Receiver<LogRecord> {
+        let (sender, receiver) = channel();
+        self.subscribers.lock().push(sender);
+
+        receiver
+    }
+
+    /// Add a record, removing the oldest ones over `max_records`, and send it to subscribers.
+    fn push(&self, record: LogRecord, max_records: usize) {
+        // Forget subscribers that dropped their receiver
+        self.subscribers
+            .lock()
+            .retain(|sender| sender.send(record.clone()).is_ok());
+
+        let mut records = self.records.lock();
+        while !records.is_empty() && records.len() >= max_records {
+            records.pop_front();
+        }
+        if max_records > 0 {
+            records.push_back(record);
+        }
     }
 }
 
 impl MemLogger {
-    /// Create a new in-memory logger with the given `max_lines` cap.
-    pub fn new(max_lines: usize, logger: Logger) -> Self {
+    /// Create a new in-memory logger with the given `max_records` cap, keeping records up to
+    /// `level`.
+    ///
+    /// Timestamps are in UTC, see [`MemLogger::with_time_offset`].
+    pub fn new(max_records: usize, level: LevelFilter, logger: Logger) -> Self {
         Self {
-            max_lines,
-            buffer: Vec::new(),
             logger,
+            max_records,
+            level,
+            offset: UtcOffset::UTC,
         }
     }
-}
 
-impl Write for MemLogger {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.buffer.write_all(buf)?;
+    /// Record timestamps in the given time zone offset.
+    ///
+    /// The local offset can only be found reliably before any threads are started, so it is
+    /// passed in.
+    pub fn with_time_offset(mut self, offset: UtcOffset) -> Self {
+        self.offset = offset;
+        self
+    }
 
-        // Flush when a new-line is written
-        if buf.iter().any(|b| char::from(*b) == '\n') {
-            self.flush()?;
-        }
+    /// Get the most verbose level that is kept.
+    pub fn level(&self) -> LevelFilter {
+        self.level
+    }
+}
 
-        Ok(buf.len())
+impl Log for MemLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.level
     }
 
-    fn flush(&mut self) -> std::io::Result<()> {
-        // Convert bytes into a string with lossy UTF-8 encoding
-        let buffer = String::from_utf8_lossy(&self.buffer);
-
-        // This scope is for releasing the lock ASAP
-        {
-            let mut guard = self.logger.lock();
-
-            // Write all lines
-            for line in buffer.lines() {
-                if line.is_empty() {
-                    continue;
-                }
-
-                if guard.len() >= self.max_lines {
-                    guard.pop_front();
-                }
-                guard.push_back(line.to_string());
-            }
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
         }
 
-        // Consume the buffer
-        self.buffer.clear();
-
-        Ok(())
+        let record = LogRecord {
+            level: record.level(),
+            timestamp: OffsetDateTime::now_utc().to_offset(self.offset),
+            message: record.args().to_string(),
+        };
+        self.logger.push(record, self.max_records);
     }
+
+    fn flush(&self) {}
 }
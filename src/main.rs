@@ -2,20 +2,32 @@
 #![deny(clippy::all)]
 
 use image::error::ImageError;
-use log::{error, info, SetLoggerError};
+use log::{error, info, warn, SetLoggerError};
+use notify_rust::Notification;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use steeve_sync::{
+    game_running,
     logger::{Logger, MemLogger},
-    Error as SteeveError, Steeve,
+    Config, Direction, Error as SteeveError, Side, Steeve, SteeveEvent,
 };
 use tao::{
+    dpi::{PhysicalPosition, PhysicalSize},
     error::OsError,
-    event_loop::EventLoop,
-    menu::{ContextMenu, MenuId, MenuItemAttributes},
+    event_loop::{EventLoop, EventLoopProxy},
+    menu::{ContextMenu, CustomMenuItem, MenuId, MenuItemAttributes},
     system_tray::{BadIcon, Icon as TrayIcon, SystemTray, SystemTrayBuilder},
     window::{Icon, Theme, Window, WindowBuilder},
 };
 use thiserror::Error;
 use time::error::IndeterminateOffset;
+use time::OffsetDateTime;
 
 /// All the ways in which Steeve-Sync can fail.
 #[derive(Debug, Error)]
@@ -37,31 +49,307 @@ enum AppError {
 
     #[error("Bad Icon: {0}")]
     Icon(#[from] BadIcon),
+
+    #[error("Deep Rock Galactic is running, quit the game before restoring a backup")]
+    GameRunning,
+
+    #[error("Restore error: {0}")]
+    Restore(String),
+
+    #[error("Unable to handle Ctrl-C: {0}")]
+    CtrlC(std::io::Error),
+
+    #[error("--dry-run cannot be used with {0}")]
+    DryRun(&'static str),
+}
+
+/// Title of the tray menu item that pauses syncing.
+const PAUSE_TITLE: &str = "Pause syncing";
+
+/// How often the status in the options window is refreshed while it is shown.
+const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Events posted to the event loop from other threads.
+#[derive(Debug)]
+enum UserEvent {
+    /// A tray action that ran on a worker thread finished, with the tooltip to show, if any.
+    TaskDone(Option<String>),
 }
 
 /// The primary application
 struct App {
     options: MenuId,
+    pause: MenuId,
+    pause_item: CustomMenuItem,
+    sync_now: MenuId,
+    push_to_xbox: MenuId,
+    push_to_steam: MenuId,
+    rescan: MenuId,
+    self_test: MenuId,
     quit: MenuId,
     black_icon: Vec<u8>,
     white_icon: Vec<u8>,
     window: Window,
+    status: StatusPanel,
+    refresh_at: Instant,
     menu: Option<SystemTray>,
+
+    /// Whether a tray action is running on a worker thread, see [`App::spawn_task`].
+    busy: bool,
+}
+
+impl App {
+    /// Show and focus the options window, with the current status.
+    fn show_window(&mut self, steeve: &Steeve) {
+        self.refresh_status(steeve);
+        self.window.set_visible(true);
+        self.window.set_focus();
+        self.save_window_state();
+    }
+
+    /// Show the current status in the options window, and schedule the next refresh.
+    fn refresh_status(&mut self, steeve: &Steeve) {
+        self.status.set_text(status_text(steeve));
+        self.refresh_at = Instant::now() + STATUS_REFRESH_INTERVAL;
+    }
+
+    /// Run a slow tray action on a worker thread, so the tray and window stay responsive.
+    ///
+    /// The tooltip that `task` returns is shown by the event loop when it finishes. Only one
+    /// action runs at a time; others are ignored until it finishes.
+    fn spawn_task<F>(&mut self, proxy: &EventLoopProxy<UserEvent>, name: &str, task: F)
+    where
+        F: FnOnce() -> Option<String> + Send + 'static,
+    {
+        if self.busy {
+            info!("{name} ignored, another tray action is still running");
+            return;
+        }
+        self.busy = true;
+
+        let proxy = proxy.clone();
+        std::thread::spawn(move || {
+            // The event loop is gone when quitting, and then nobody is waiting for the result
+            let _ = proxy.send_event(UserEvent::TaskDone(task()));
+        });
+    }
+
+    /// Remember where the options window is, and whether it is shown.
+    ///
+    /// This is saved when the window is shown or hidden, and on quit, not while it is being
+    /// moved or resized.
+    fn save_window_state(&self) {
+        let Ok(position) = self.window.outer_position() else {
+            return;
+        };
+        let size = self.window.inner_size();
+        let state = WindowState {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+            visible: self.window.is_visible(),
+        };
+        state.save();
+    }
+}
+
+/// Where the options window was last shown, saved next to the config file.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct WindowState {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    visible: bool,
+}
+
+impl WindowState {
+    /// Get the path of the window state file.
+    fn path() -> Option<PathBuf> {
+        Some(Config::path().ok()?.with_file_name("window.toml"))
+    }
+
+    /// Load the saved window state, if there is one.
+    fn load() -> Option<Self> {
+        let text = std::fs::read_to_string(Self::path()?).ok()?;
+
+        toml::from_str(&text).ok()
+    }
+
+    /// Save the window state, unless it is unchanged. Errors are only logged.
+    fn save(&self) {
+        if Self::load().as_ref() == Some(self) {
+            return;
+        }
+        let Some(path) = Self::path() else {
+            return;
+        };
+        let text = match toml::to_string(self) {
+            Ok(text) => text,
+            Err(err) => {
+                warn!("Unable to save the window position: {err}");
+                return;
+            }
+        };
+        if let Err(err) = std::fs::write(&path, text) {
+            warn!("Unable to save the window position to {path:?}: {err}");
+        }
+    }
+
+    /// Get where to put the window: where it was, or centered on the primary monitor when that
+    /// spot is no longer on any monitor, e.g. after a monitor is disconnected.
+    fn position(&self, event_loop: &EventLoop<UserEvent>) -> Option<PhysicalPosition<i32>> {
+        let on_screen = event_loop.available_monitors().any(|monitor| {
+            let (origin, size) = (monitor.position(), monitor.size());
+            let right = origin.x.saturating_add_unsigned(size.width);
+            let bottom = origin.y.saturating_add_unsigned(size.height);
+
+            (origin.x..right).contains(&self.x) && (origin.y..bottom).contains(&self.y)
+        });
+        if on_screen {
+            return Some(PhysicalPosition::new(self.x, self.y));
+        }
+
+        let monitor = event_loop.primary_monitor()?;
+        let (origin, size) = (monitor.position(), monitor.size());
+        let x = (i64::from(size.width) - i64::from(self.width)) / 2;
+        let y = (i64::from(size.height) - i64::from(self.height)) / 2;
+
+        Some(PhysicalPosition::new(
+            origin.x + i32::try_from(x.max(0)).unwrap_or(0),
+            origin.y + i32::try_from(y.max(0)).unwrap_or(0),
+        ))
+    }
+}
+
+/// The live sync status, shown in the options window.
+struct StatusPanel {
+    /// A read-only text box that fills the window.
+    #[cfg(windows)]
+    edit: isize,
+
+    /// The text that is shown, so it is only replaced when it changes.
+    text: String,
+}
+
+impl StatusPanel {
+    /// Add the status panel to `window`. Errors are only logged.
+    #[cfg(windows)]
+    fn new(window: &Window) -> Self {
+        use tao::platform::windows::WindowExtWindows;
+        use windows_sys::Win32::Graphics::Gdi::{GetStockObject, DEFAULT_GUI_FONT};
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            CreateWindowExW, SendMessageW, ES_AUTOVSCROLL, ES_MULTILINE, ES_READONLY, WM_SETFONT,
+            WS_CHILD, WS_VISIBLE, WS_VSCROLL,
+        };
+
+        let class = wide("EDIT");
+        let style = WS_CHILD
+            | WS_VISIBLE
+            | WS_VSCROLL
+            | (ES_MULTILINE | ES_READONLY | ES_AUTOVSCROLL) as u32;
+        let size = window.inner_size();
+        // SAFETY: `class` is NUL-terminated, and the text box is destroyed with its parent.
+        let edit = unsafe {
+            CreateWindowExW(
+                0,
+                class.as_ptr(),
+                std::ptr::null(),
+                style,
+                0,
+                0,
+                i32::try_from(size.width).unwrap_or(i32::MAX),
+                i32::try_from(size.height).unwrap_or(i32::MAX),
+                window.hwnd() as isize,
+                0,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if edit == 0 {
+            let err = std::io::Error::last_os_error();
+            warn!("Unable to show the status in the options window: {err}");
+        } else {
+            // SAFETY: `edit` is a valid window, and stock objects never need to be freed.
+            unsafe {
+                SendMessageW(
+                    edit,
+                    WM_SETFONT,
+                    GetStockObject(DEFAULT_GUI_FONT) as usize,
+                    1,
+                )
+            };
+        }
+
+        Self {
+            edit,
+            text: String::new(),
+        }
+    }
+
+    /// The status panel is only shown on Windows, elsewhere the status is logged.
+    #[cfg(not(windows))]
+    fn new(_window: &Window) -> Self {
+        Self {
+            text: String::new(),
+        }
+    }
+
+    /// Show `text`, unless it is already shown.
+    fn set_text(&mut self, text: String) {
+        if text == self.text {
+            return;
+        }
+
+        #[cfg(windows)]
+        {
+            use windows_sys::Win32::UI::WindowsAndMessaging::SetWindowTextW;
+
+            // Text boxes only break lines at CRLF
+            let wide_text = wide(&text.replace('\n', "\r\n"));
+            // SAFETY: `wide_text` is NUL-terminated. A null `edit` makes the call fail.
+            unsafe { SetWindowTextW(self.edit, wide_text.as_ptr()) };
+        }
+        #[cfg(not(windows))]
+        info!("{text}");
+
+        self.text = text;
+    }
+
+    /// Keep filling the window after it is resized.
+    fn resize(&self, size: PhysicalSize<u32>) {
+        #[cfg(windows)]
+        {
+            use windows_sys::Win32::UI::WindowsAndMessaging::MoveWindow;
+
+            let width = i32::try_from(size.width).unwrap_or(i32::MAX);
+            let height = i32::try_from(size.height).unwrap_or(i32::MAX);
+            // SAFETY: Takes no pointers. A null `edit` makes the call fail.
+            unsafe { MoveWindow(self.edit, 0, 0, width, height, 1) };
+        }
+        #[cfg(not(windows))]
+        let _ = size;
+    }
 }
 
 fn init_logger() -> Result<(Logger, Logger), AppError> {
     use simplelog::*;
     use time::UtcOffset;
 
+    let offset = UtcOffset::current_local_offset()?;
+
     let info_logger = Logger::default();
-    let info_memlogger = MemLogger::new(100, info_logger.clone());
+    let info_memlogger =
+        MemLogger::new(100, LevelFilter::Info, info_logger.clone()).with_time_offset(offset);
 
     let debug_logger = Logger::default();
-    let debug_memlogger = MemLogger::new(1000, debug_logger.clone());
+    let debug_memlogger =
+        MemLogger::new(1000, LevelFilter::Debug, debug_logger.clone()).with_time_offset(offset);
 
     let config = ConfigBuilder::new()
-        .add_filter_allow_str("steeve_sync")
-        .set_time_offset(UtcOffset::current_local_offset()?)
+        .add_filter_allow_str(LOG_TARGET)
+        .set_time_offset(offset)
         .set_time_format_custom(format_description!(
             "[year]-[month]-[day] [hour repr:24]:[minute]:[second].[subsecond digits:3]"
         ))
@@ -70,30 +358,86 @@ fn init_logger() -> Result<(Logger, Logger), AppError> {
     CombinedLogger::init(vec![
         TermLogger::new(
             LevelFilter::Info,
-            config.clone(),
+            config,
             TerminalMode::Mixed,
             ColorChoice::Auto,
         ),
-        WriteLogger::new(LevelFilter::Debug, config.clone(), debug_memlogger),
-        WriteLogger::new(LevelFilter::Info, config, info_memlogger),
+        Box::new(MemSink(debug_memlogger)),
+        Box::new(MemSink(info_memlogger)),
     ])?;
 
     Ok((debug_logger, info_logger))
 }
 
-fn create_app(event_loop: &EventLoop<()>) -> Result<App, AppError> {
+/// Only log records from this target are kept.
+const LOG_TARGET: &str = "steeve_sync";
+
+/// Plugs a [`MemLogger`] into simplelog, keeping only records from [`LOG_TARGET`].
+struct MemSink(MemLogger);
+
+impl log::Log for MemSink {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.target().starts_with(LOG_TARGET) && self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.0.log(record);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl simplelog::SharedLogger for MemSink {
+    fn level(&self) -> log::LevelFilter {
+        self.0.level()
+    }
+
+    fn config(&self) -> Option<&simplelog::Config> {
+        None
+    }
+
+    fn as_log(self: Box<Self>) -> Box<dyn log::Log> {
+        self
+    }
+}
+
+fn create_app(event_loop: &EventLoop<UserEvent>) -> Result<App, AppError> {
     let mut menu = ContextMenu::new();
 
     let options = menu.add_item(MenuItemAttributes::new("Options...")).id();
+    let pause_item = menu.add_item(MenuItemAttributes::new(PAUSE_TITLE));
+    let pause = pause_item.clone().id();
+    let sync_now = menu.add_item(MenuItemAttributes::new("Sync now")).id();
+    let push_to_xbox = menu
+        .add_item(MenuItemAttributes::new("Push Steam → Xbox"))
+        .id();
+    let push_to_steam = menu
+        .add_item(MenuItemAttributes::new("Push Xbox → Steam"))
+        .id();
+    let rescan = menu
+        .add_item(MenuItemAttributes::new("Re-scan directories"))
+        .id();
+    let self_test = menu.add_item(MenuItemAttributes::new("Self-test")).id();
     let quit = menu.add_item(MenuItemAttributes::new("Quit")).id();
 
     let black_icon = read_icon(include_bytes!("../assets/steeve-sync-black.ico"))?;
     let white_icon = read_icon(include_bytes!("../assets/steeve-sync-white.ico"))?;
 
-    let window = WindowBuilder::new()
+    let saved = WindowState::load();
+    let mut builder = WindowBuilder::new()
         .with_title("Steeve-Sync")
-        .with_visible(false)
-        .build(event_loop)?;
+        .with_visible(saved.as_ref().is_some_and(|state| state.visible));
+    if let Some(state) = &saved {
+        let size = PhysicalSize::new(state.width, state.height);
+        builder = builder.with_inner_size(size);
+        if let Some(position) = state.position(event_loop) {
+            builder = builder.with_position(position);
+        }
+    }
+    let window = builder.build(event_loop)?;
+    let status = StatusPanel::new(&window);
 
     let icon = if window.theme() == Theme::Dark {
         white_icon.clone()
@@ -108,11 +452,21 @@ fn create_app(event_loop: &EventLoop<()>) -> Result<App, AppError> {
 
     Ok(App {
         options,
+        pause,
+        pause_item,
+        sync_now,
+        push_to_xbox,
+        push_to_steam,
+        rescan,
+        self_test,
         quit,
         black_icon,
         white_icon,
         window,
+        status,
+        refresh_at: Instant::now(),
         menu,
+        busy: false,
     })
 }
 
@@ -122,34 +476,88 @@ fn run() -> Result<(), AppError> {
         event_loop::ControlFlow,
     };
 
+    // Console modes print to the terminal that started them, before the logger is set up
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.iter().any(|arg| CONSOLE_MODES.contains(&arg.as_str())) {
+        attach_console();
+    }
+
     // TODO: Use the loggers to show logs in the GUI
     let (_debug_logger, _info_logger) = init_logger()?;
 
     info!("Welcome, miners!");
 
-    // TODO: Make this configurable
-    let max_backups = 25;
-    let mut steeve = Steeve::new(max_backups)?;
+    let config = Config::load_or_default()?;
 
-    info!("Steeve is waiting for bugs to kill...");
+    // Dry runs only log what would be synced, in any mode that syncs
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+
+    // Self-test mode prints a report for bug reports and exits
+    if args.iter().any(|arg| arg == "--self-test") {
+        if dry_run {
+            return Err(AppError::DryRun("--self-test"));
+        }
+        println!("{}", Steeve::self_test());
+        return Ok(());
+    }
+
+    // Restore mode restores a chosen backup and exits
+    if let Some(index) = args.iter().position(|arg| arg == "--restore") {
+        if dry_run {
+            return Err(AppError::DryRun("--restore"));
+        }
+        return restore(&args[index + 1..], &config);
+    }
+
+    // One-shot mode syncs and exits without watching or showing the tray icon
+    if args.iter().any(|arg| arg == "--once") {
+        let steeve = config.builder().watch(false).dry_run(dry_run).build()?;
+        steeve.sync_now()?;
+
+        info!("See you next mission!");
+        return Ok(());
+    }
+
+    // Headless mode keeps syncing without the tray icon, until Ctrl-C
+    if args.iter().any(|arg| arg == "--headless") {
+        return headless(&config, dry_run);
+    }
+
+    let steeve = start(&config, dry_run)?;
+    notify_events(&steeve, &config);
+
+    // Slow tray actions share Steeve with a worker thread
+    let steeve = Arc::new(RwLock::new(steeve));
 
     // XXX: This must be the last use of the question-mark operator in the function.
     // Otherwise Obj-C panics on macOS from `rfd` and then `tao` catches the panic and hides the
     // reason for the failure.
-    let event_loop = EventLoop::new();
+    let event_loop = EventLoop::with_user_event();
+    let proxy = event_loop.create_proxy();
     let mut app = create_app(&event_loop)?;
 
     event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Wait;
+        // The status is only refreshed while it is shown, and not while a re-scan has Steeve
+        *control_flow = if app.window.is_visible() {
+            if Instant::now() >= app.refresh_at {
+                if let Some(steeve) = steeve.try_read() {
+                    app.refresh_status(&steeve);
+                }
+            }
+            ControlFlow::WaitUntil(app.refresh_at)
+        } else {
+            ControlFlow::Wait
+        };
 
         match event {
             // Quit events
             Event::MenuEvent { menu_id, .. } if menu_id == app.quit => {
                 // Remove tray icon from system
                 app.menu.take();
+                app.save_window_state();
 
                 // Stop watching the FS
-                let _ = steeve.stop();
+                let _ = steeve.write().stop();
 
                 info!("See you next mission!");
                 *control_flow = ControlFlow::Exit;
@@ -159,6 +567,13 @@ fn run() -> Result<(), AppError> {
                 ..
             } => {
                 app.window.set_visible(false);
+                app.save_window_state();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(size),
+                ..
+            } => {
+                app.status.resize(size);
             }
 
             // Theme events
@@ -184,12 +599,82 @@ fn run() -> Result<(), AppError> {
                 event: TrayEvent::LeftClick,
                 ..
             } => {
-                app.window.set_visible(true);
-                app.window.set_focus();
+                app.show_window(&steeve.read());
             }
             Event::MenuEvent { menu_id, .. } if menu_id == app.options => {
-                app.window.set_visible(true);
-                app.window.set_focus();
+                app.show_window(&steeve.read());
+            }
+            Event::MenuEvent { menu_id, .. } if menu_id == app.pause => {
+                let mut steeve = steeve.write();
+                if steeve.is_paused() {
+                    if let Err(err) = steeve.resume(true) {
+                        error!("Sync error after resuming: {err}");
+                    }
+                    app.pause_item.set_title(PAUSE_TITLE);
+                } else {
+                    steeve.pause();
+                    app.pause_item.set_title("Resume syncing");
+                }
+            }
+            Event::MenuEvent { menu_id, .. } if menu_id == app.sync_now => {
+                let steeve = steeve.clone();
+                app.spawn_task(&proxy, "Sync now", move || {
+                    Some(match steeve.read().sync_now() {
+                        Ok(()) => {
+                            info!("Manual sync finished");
+                            format!("Steeve-Sync: synced {}", format_time(SystemTime::now()))
+                        }
+                        Err(err) => {
+                            error!("Manual sync error: {err}");
+                            format!("Steeve-Sync: sync failed: {err}")
+                        }
+                    })
+                });
+            }
+            Event::MenuEvent { menu_id, .. }
+                if menu_id == app.push_to_xbox || menu_id == app.push_to_steam =>
+            {
+                let direction = if menu_id == app.push_to_xbox {
+                    Direction::SteamToXbox
+                } else {
+                    Direction::XboxToSteam
+                };
+                let steeve = steeve.clone();
+                app.spawn_task(&proxy, "Push", move || {
+                    Some(match steeve.read().force_sync(direction) {
+                        Ok(()) => {
+                            info!("Forced sync finished");
+                            format!("Steeve-Sync: pushed {}", format_time(SystemTime::now()))
+                        }
+                        Err(err) => {
+                            error!("Forced sync error: {err}");
+                            format!("Steeve-Sync: push failed: {err}")
+                        }
+                    })
+                });
+            }
+            Event::MenuEvent { menu_id, .. } if menu_id == app.rescan => {
+                let steeve = steeve.clone();
+                app.spawn_task(&proxy, "Re-scan directories", move || {
+                    if let Err(err) = steeve.write().rediscover() {
+                        error!("Re-scan error: {err}");
+                    }
+                    None
+                });
+            }
+            Event::MenuEvent { menu_id, .. } if menu_id == app.self_test => {
+                app.spawn_task(&proxy, "Self-test", || {
+                    info!("{}", Steeve::self_test());
+                    None
+                });
+            }
+
+            // Tray actions that finished on a worker thread
+            Event::UserEvent(UserEvent::TaskDone(tooltip)) => {
+                app.busy = false;
+                if let (Some(menu), Some(tooltip)) = (app.menu.as_mut(), tooltip) {
+                    menu.set_tooltip(&tooltip);
+                }
             }
 
             _ => (),
@@ -197,6 +682,291 @@ fn run() -> Result<(), AppError> {
     });
 }
 
+/// Start watching and syncing the saves, with the initial sync.
+fn start(config: &Config, dry_run: bool) -> Result<Steeve, AppError> {
+    let steeve = config.builder().dry_run(dry_run).build()?;
+
+    info!("Steeve is waiting for bugs to kill...");
+
+    Ok(steeve)
+}
+
+/// Command line modes that run without the tray icon, and print to the terminal.
+const CONSOLE_MODES: [&str; 4] = ["--headless", "--once", "--restore", "--self-test"];
+
+/// Use the console of the terminal that started Steeve, if there is one.
+///
+/// Release builds are Windows GUI programs, so they have no console of their own: without this,
+/// nothing is printed, the restore prompts cannot be answered, and Ctrl-C never arrives. The
+/// terminal does not wait for a GUI program, so its own prompt may show up between the logs.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // SAFETY: Takes no pointers. It fails harmlessly when there is no parent console, or when
+    // this is a debug build that already has a console.
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+/// Other platforms always print to the terminal that started the program.
+#[cfg(not(windows))]
+fn attach_console() {}
+
+/// How often headless mode checks whether Ctrl-C was pressed.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Set when Ctrl-C is pressed in headless mode.
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+/// Sync in the background without the tray icon, e.g. over SSH or in a container.
+///
+/// Logs are printed to the terminal. Ctrl-C stops the watchers and exits.
+fn headless(config: &Config, dry_run: bool) -> Result<(), AppError> {
+    handle_ctrl_c()?;
+    let mut steeve = start(config, dry_run)?;
+
+    info!("Press Ctrl-C to stop");
+    while !SHUTDOWN.load(Ordering::SeqCst) {
+        std::thread::sleep(SHUTDOWN_POLL_INTERVAL);
+    }
+    steeve.stop()?;
+
+    info!("See you next mission!");
+    Ok(())
+}
+
+/// Set [`SHUTDOWN`] on Ctrl-C, instead of exiting right away.
+#[cfg(windows)]
+fn handle_ctrl_c() -> Result<(), AppError> {
+    use windows_sys::Win32::System::Console::SetConsoleCtrlHandler;
+
+    unsafe extern "system" fn handler(_ctrl_type: u32) -> i32 {
+        SHUTDOWN.store(true, Ordering::SeqCst);
+        1
+    }
+
+    // SAFETY: The handler only stores to an atomic.
+    if unsafe { SetConsoleCtrlHandler(Some(handler), 1) } == 0 {
+        return Err(AppError::CtrlC(std::io::Error::last_os_error()));
+    }
+
+    Ok(())
+}
+
+/// Set [`SHUTDOWN`] on Ctrl-C or `SIGTERM`, instead of exiting right away.
+#[cfg(unix)]
+fn handle_ctrl_c() -> Result<(), AppError> {
+    extern "C" fn on_signal(_signal: libc::c_int) {
+        SHUTDOWN.store(true, Ordering::SeqCst);
+    }
+
+    let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: The handler only stores to an atomic, which is async-signal-safe.
+        let previous = unsafe { libc::signal(signal, handler) };
+        if previous == libc::SIG_ERR {
+            return Err(AppError::CtrlC(std::io::Error::last_os_error()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Restore a backup from the command line: `--restore [steam|xbox] [NUMBER|FILENAME]`.
+///
+/// Arguments that are not given are asked for on the console. The current save is backed up
+/// before it is overwritten.
+fn restore(args: &[String], config: &Config) -> Result<(), AppError> {
+    if game_running() {
+        return Err(AppError::GameRunning);
+    }
+
+    let mut args = args.iter().cloned();
+    let side = args
+        .next()
+        .or_else(|| prompt("Side to restore (steam or xbox): "));
+    let side = match side.as_deref().map(str::to_ascii_lowercase).as_deref() {
+        Some("steam") => Side::Steam,
+        Some("xbox") => Side::Xbox,
+        _ => return Err(AppError::Restore("choose steam or xbox".to_string())),
+    };
+
+    let steeve = config.builder().watch(false).build()?;
+    let backups = steeve.list_backups(side)?;
+    if backups.is_empty() {
+        println!("There are no {side:?} backups");
+        return Ok(());
+    }
+
+    println!("{side:?} backups, from oldest to newest:");
+    for (number, backup) in backups.iter().enumerate() {
+        println!(
+            "{:>4}  {}  {:>9} bytes  {}",
+            number + 1,
+            format_time(backup.timestamp),
+            backup.size,
+            backup
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy(),
+        );
+    }
+
+    let choice = args
+        .next()
+        .or_else(|| prompt("Backup to restore (number or file name): "))
+        .unwrap_or_default();
+    let backup = choice
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| backups.get(number.checked_sub(1)?))
+        .or_else(|| {
+            backups.iter().find(|backup| {
+                backup
+                    .path
+                    .file_name()
+                    .is_some_and(|name| name == choice.as_str())
+            })
+        })
+        .ok_or_else(|| AppError::Restore(format!("no such backup: {choice:?}")))?;
+
+    println!(
+        "Restoring {} backup from {} ({} bytes)",
+        backup.filename,
+        format_time(backup.timestamp),
+        backup.size,
+    );
+    steeve.restore_backup(side, backup)?;
+    println!("Restored! The previous save was backed up first.");
+
+    Ok(())
+}
+
+/// Ask a question on the console, returning the trimmed answer if there is one.
+fn prompt(question: &str) -> Option<String> {
+    print!("{question}");
+    std::io::stdout().flush().ok()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).ok()?;
+    let answer = answer.trim();
+
+    (!answer.is_empty()).then(|| answer.to_string())
+}
+
+/// Describe the sync state of both sides, for the options window.
+fn status_text(steeve: &Steeve) -> String {
+    use std::fmt::Write as _;
+
+    let status = steeve.sync_status();
+    let mut text = format!("Steeve is {:?}\n", steeve.status());
+    for (name, side) in [("Steam", &status.steam), ("Xbox", &status.xbox)] {
+        let last_sync = side.last_sync.map_or("never".to_string(), format_time);
+        let save_path = side
+            .save_path
+            .as_ref()
+            .map_or("not found".to_string(), |path| format!("{path:?}"));
+        let _ = writeln!(text, "\n{name} save: {save_path}");
+        let _ = writeln!(
+            text,
+            "Last sync: {last_sync}, {} backups kept",
+            side.backups,
+        );
+        let _ = writeln!(
+            text,
+            "Watcher: {:?} since {}, {}",
+            side.watcher.state,
+            format_time(side.watcher.since),
+            side.watcher.reason,
+        );
+        if let Some(err) = &side.last_error {
+            let _ = writeln!(text, "Last error: {err}");
+        }
+    }
+
+    text
+}
+
+/// Format a backup timestamp for the console.
+fn format_time(timestamp: SystemTime) -> String {
+    let format = time::macros::format_description!(
+        "[year]-[month]-[day] [hour repr:24]:[minute]:[second] UTC"
+    );
+
+    OffsetDateTime::from(timestamp)
+        .format(format)
+        .unwrap_or_default()
+}
+
+/// Show a desktop notification for each synced save and each sync error, on a new thread.
+///
+/// Success notifications can be silenced with [`Config::notify_success`]; errors are always shown.
+/// An error is only shown once until a save is copied again, so a sync that keeps being retried
+/// does not repeat it.
+fn notify_events(steeve: &Steeve, config: &Config) {
+    let events = steeve.event_channel();
+    let notify_success = config.notify_success;
+
+    // The channel closes when Steeve is dropped
+    std::thread::spawn(move || {
+        let mut shown_errors = HashSet::new();
+        for event in events {
+            match event {
+                SteeveEvent::SaveCopied {
+                    direction,
+                    dry_run: false,
+                    ..
+                } => {
+                    shown_errors.clear();
+                    if notify_success {
+                        let message = match direction {
+                            Direction::SteamToXbox => "Synced Steam \u{2192} Xbox",
+                            Direction::XboxToSteam => "Synced Xbox \u{2192} Steam",
+                        };
+                        notify(message, false);
+                    }
+                }
+                SteeveEvent::SyncError(err) => {
+                    notify_once(&mut shown_errors, format!("Sync failed: {err}"));
+                }
+                SteeveEvent::WatchError { side, .. } => {
+                    let message = format!("Lost track of the {side:?} saves, watching again");
+                    notify_once(&mut shown_errors, message);
+                }
+                _ => (),
+            }
+        }
+    });
+}
+
+/// Show an error notification, unless it is in `shown`.
+fn notify_once(shown: &mut HashSet<String>, message: String) {
+    if !shown.contains(&message) {
+        notify(&message, true);
+        shown.insert(message);
+    }
+}
+
+/// Show a desktop notification. Errors are only logged.
+fn notify(message: &str, is_error: bool) {
+    let summary = if is_error {
+        "Steeve-Sync error"
+    } else {
+        "Steeve-Sync"
+    };
+    let result = Notification::new().summary(summary).body(message).show();
+    if let Err(err) = result {
+        warn!("Unable to show the notification {message:?}: {err}");
+    }
+}
+
+/// Convert `text` to a NUL-terminated wide string.
+#[cfg(windows)]
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain([0]).collect()
+}
+
 fn read_icon(bytes: &[u8]) -> Result<Vec<u8>, AppError> {
     use image::{codecs::ico::IcoDecoder, ImageDecoder};
     use std::io::Cursor;
@@ -212,6 +982,16 @@ fn main() {
     use rfd::{MessageButtons, MessageDialog, MessageLevel};
 
     if let Err(err) = run() {
+        if let AppError::Steeve(SteeveError::AlreadyRunning) = err {
+            MessageDialog::new()
+                .set_level(MessageLevel::Info)
+                .set_title("Steeve-Sync")
+                .set_description("Steeve is already running. Look for him in the system tray!")
+                .set_buttons(MessageButtons::Ok)
+                .show();
+            return;
+        }
+
         error!("Error: {err}");
 
         // Show error in message box.
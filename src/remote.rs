@@ -0,0 +1,413 @@
+//! A copy of the Steam saves in a folder shared with other machines.
+//!
+//! The folder is kept in sync by something else, like Dropbox, OneDrive, or an SMB share. Those
+//! can deliver changes minutes late, in pieces, and more than once, so the shared folder is not
+//! synced like the two editions on this machine:
+//!
+//! - It is polled, because synced and network folders do not reliably report changes.
+//! - A change in the shared folder is only pulled once it has stayed the same for the settle
+//!   time, across polls.
+//! - Each slot remembers the hash of the save it last agreed on with the shared folder, as the
+//!   synced hash of the shared save in the Steam backup manifest. Only a side whose save differs
+//!   from it has changed, so a late echo of our own write is never seen as a change, however
+//!   long it takes to arrive.
+//! - When both sides changed since the last agreement, nothing is overwritten.
+
+use crate::saves::{validate_save, SaveError, SteamSave, SteeveSave};
+use crate::store::StoreEntry;
+use crate::BackupPolicy;
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// What syncing one slot with the shared folder did.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum RemoteSync {
+    /// Both sides already had the same save.
+    Identical,
+
+    /// The local save was copied to the shared folder.
+    Pushed,
+
+    /// The save in the shared folder was copied over the local save at this path.
+    Pulled(PathBuf),
+
+    /// Both sides still have the same conflicting saves as when the conflict was reported.
+    Unresolved,
+}
+
+/// The local and shared save hashes of a conflict.
+type ConflictHashes = (Vec<u8>, Vec<u8>);
+
+/// Syncs the Steam saves with a shared folder.
+#[derive(Debug)]
+pub(crate) struct RemoteSave {
+    remote_dir: PathBuf,
+    settle_time: Duration,
+
+    /// Locked for a whole sync, so polls and events take turns.
+    sync_lock: Mutex<()>,
+
+    /// Local and shared save hashes of the conflicts that were reported, by shared save path.
+    conflicts: Mutex<HashMap<PathBuf, ConflictHashes>>,
+
+    /// Changed saves in the shared folder, and when they were first seen like this.
+    pending: Mutex<HashMap<PathBuf, (StoreEntry, Instant)>>,
+}
+
+impl RemoteSave {
+    /// Create a shared folder, creating `remote_dir` if it does not exist, unless this is a dry
+    /// run.
+    pub(crate) fn new(
+        remote_dir: PathBuf,
+        settle_time: Duration,
+        dry_run: bool,
+    ) -> Result<Self, SaveError> {
+        if !dry_run {
+            std::fs::create_dir_all(&remote_dir)
+                .map_err(|_| SaveError::DirCreate(remote_dir.clone()))?;
+        }
+
+        Ok(Self {
+            remote_dir,
+            settle_time,
+            sync_lock: Mutex::new(()),
+            conflicts: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Get the shared folder.
+    pub(crate) fn remote_dir(&self) -> &Path {
+        &self.remote_dir
+    }
+
+    /// Sync every Steam save slot with the shared folder, in whichever direction is needed.
+    ///
+    /// Slots that only exist in the shared folder are pulled, e.g. on a new machine. Returns the
+    /// local saves that were replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error, except for expected skips. [`SaveError::Conflict`] means that the
+    /// save changed on both sides since they last agreed; the save from the shared folder is
+    /// backed up, and neither side is overwritten.
+    pub(crate) fn sync(&self, steam: &SteamSave) -> Result<Vec<PathBuf>, SaveError> {
+        let _lock = self.sync_lock.lock();
+
+        let mut slots = steam
+            .locate_save_paths()
+            .into_iter()
+            .map(|(path, filename)| (filename, path))
+            .collect::<BTreeMap<_, _>>();
+        for entry in steam.store().list(&self.remote_dir).unwrap_or_default() {
+            let path = steam
+                .save_dir()
+                .join(entry.path.file_name().unwrap_or_default());
+            if let Some(filename) = SteamSave::save_file(&entry.path) {
+                if steam.is_synced_file(&path) {
+                    slots.entry(filename).or_insert(path);
+                }
+            }
+        }
+
+        let mut pulled = Vec::new();
+        let mut result = Ok(());
+        for (filename, local) in slots {
+            match self.sync_slot(steam, &filename, &local) {
+                Ok(RemoteSync::Pulled(path)) => pulled.push(path),
+                Ok(_) => (),
+                Err(err) if err.is_benign() => (),
+                Err(err) => {
+                    debug!("{filename} shared folder error: {:?}", err);
+                    if result.is_ok() {
+                        result = Err(err);
+                    }
+                }
+            }
+        }
+        steam.save_hash_manifest();
+
+        result.map(|()| pulled)
+    }
+
+    /// Sync one slot with the shared folder.
+    fn sync_slot(
+        &self,
+        steam: &SteamSave,
+        filename: &str,
+        local: &Path,
+    ) -> Result<RemoteSync, SaveError> {
+        let remote = self.remote_dir.join(filename);
+        let store = steam.store();
+        let hash = |path: &Path| -> Result<Option<Vec<u8>>, SaveError> {
+            if store.stat(path).is_err() {
+                return Ok(None);
+            }
+            let hash = steam
+                .hash(path)
+                .map_err(|err| SaveError::SourceUnavailable(path.to_path_buf(), err))?;
+
+            Ok(Some(hash))
+        };
+        let local_hash = hash(local)?;
+        let remote_hash = hash(&remote)?;
+        let base = self.base(steam, &remote);
+        let base = base.as_ref();
+
+        let push = match (&local_hash, &remote_hash) {
+            (None, None) => return Err(SaveError::NoSave),
+            (Some(local_hash), Some(remote_hash)) if local_hash == remote_hash => {
+                self.set_base(steam, &remote, local_hash);
+                self.pending.lock().remove(&remote);
+                return Ok(RemoteSync::Identical);
+            }
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (Some(local_hash), Some(remote_hash)) => {
+                let local_changed = base != Some(local_hash);
+                let remote_changed = base != Some(remote_hash);
+                match (local_changed, remote_changed) {
+                    (true, false) => true,
+                    (false, true) => false,
+                    // Never agreed before, so the newest save wins
+                    _ if base.is_none() => {
+                        let local_entry = store.stat(local)?;
+                        let remote_entry = store.stat(&remote)?;
+                        match steam.compare_times(&local_entry, &remote_entry) {
+                            Ordering::Greater => true,
+                            Ordering::Less => false,
+                            Ordering::Equal => return Err(SaveError::ModifyTime),
+                        }
+                    }
+                    _ => {
+                        // Reported once, until either save changes or the conflict is resolved
+                        let hashes = (local_hash.clone(), remote_hash.clone());
+                        if self.conflicts.lock().get(&remote) == Some(&hashes) {
+                            debug!("{filename} is still in conflict with the shared folder");
+                            return Ok(RemoteSync::Unresolved);
+                        }
+
+                        warn!("{filename} changed both here and in the shared folder");
+                        steam.backup(&remote, filename)?;
+                        self.conflicts.lock().insert(remote.clone(), hashes);
+                        return Err(SaveError::Conflict {
+                            from: remote,
+                            to: local.to_path_buf(),
+                        });
+                    }
+                }
+            }
+        };
+
+        let had_local = local_hash.is_some();
+        let (from, to, hash) = if push {
+            (local, remote.as_path(), local_hash)
+        } else {
+            self.wait_for_settle(steam, &remote)?;
+            (remote.as_path(), local, remote_hash)
+        };
+        validate_save(store, from)?;
+        if !push && !store.is_dir(steam.save_dir()) {
+            return Err(SaveError::SaveDirMissing(steam.save_dir().to_path_buf()));
+        }
+
+        if steam.options().dry_run {
+            info!("Dry run: Steeve would sync {filename} with the shared folder");
+            debug!("Dry run copy shared save: {:?} -> {:?}", from, to);
+            return Ok(RemoteSync::Identical);
+        }
+
+        // Only the local save is backed up; the shared folder has its own history, if any
+        if !push && had_local && steam.options().backup_policy == BackupPolicy::Enabled {
+            steam.backup(local, filename)?;
+        }
+
+        if push {
+            info!("Steeve is copying {filename} to the shared folder");
+        } else {
+            info!("Steeve is copying {filename} from the shared folder");
+        }
+        debug!("Copy shared save: {:?} -> {:?}", from, to);
+        let modified = store.stat(from)?.modified;
+        steam.atomic_copy(from, to)?;
+        store.set_modified(to, modified)?;
+
+        if let Some(hash) = &hash {
+            self.set_base(steam, &remote, hash);
+        }
+
+        Ok(if push {
+            RemoteSync::Pushed
+        } else {
+            RemoteSync::Pulled(local.to_path_buf())
+        })
+    }
+
+    /// Let the local saves win on the next sync, even over changes in the shared folder.
+    ///
+    /// This resolves conflicts after the user chose a save, e.g. by restoring a backup: each
+    /// shared save becomes the base, so only the local save counts as changed.
+    pub(crate) fn keep_local(&self, steam: &SteamSave) {
+        let _lock = self.sync_lock.lock();
+        for entry in steam.store().list(&self.remote_dir).unwrap_or_default() {
+            if SteamSave::save_file(&entry.path).is_none() {
+                continue;
+            }
+            match steam.hash(&entry.path) {
+                Ok(hash) => self.set_base(steam, &entry.path, &hash),
+                Err(err) => warn!("Unable to read shared save {:?}: {err}", entry.path),
+            }
+        }
+        steam.save_hash_manifest();
+    }
+
+    /// Check that a changed save in the shared folder has stayed the same for the settle time.
+    ///
+    /// Synced folders may deliver a save in several writes, far apart. The save is checked on
+    /// each sync instead of waiting, and is only pulled once it has not changed for long enough.
+    fn wait_for_settle(&self, steam: &SteamSave, remote: &Path) -> Result<(), SaveError> {
+        if self.settle_time.is_zero() {
+            return Ok(());
+        }
+
+        let entry = steam.store().stat(remote)?;
+        let mut pending = self.pending.lock();
+        match pending.get(remote) {
+            Some((seen, since)) if seen == &entry => {
+                if since.elapsed() >= self.settle_time {
+                    pending.remove(remote);
+                    return Ok(());
+                }
+            }
+            _ => {
+                pending.insert(remote.to_path_buf(), (entry, Instant::now()));
+            }
+        }
+
+        Err(SaveError::StillWriting(remote.to_path_buf()))
+    }
+
+    /// Get the hash of the save that both sides last agreed on for the shared save at `remote`.
+    fn base(&self, steam: &SteamSave, remote: &Path) -> Option<Vec<u8>> {
+        let algo = steam.options().hash_algo;
+
+        steam.options().hash_cache.synced(algo, remote)
+    }
+
+    /// Remember that both sides agree on the save with `hash`, for the shared save at `remote`.
+    ///
+    /// It is kept in the Steam backup manifest with the other synced hashes, so a restart does
+    /// not mistake it for a conflict.
+    fn set_base(&self, steam: &SteamSave, remote: &Path, hash: &[u8]) {
+        self.conflicts.lock().remove(remote);
+        if steam.options().dry_run {
+            return;
+        }
+
+        let options = steam.options();
+        let cache = &options.hash_cache;
+        cache.set_synced(steam.backup_dir(), options.hash_algo, remote, hash.to_vec());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::saves::SaveOptions;
+    use crate::testing::{read_body, write_save, STEAM_NAME};
+    use tempfile::TempDir;
+
+    fn steam_save(dir: &TempDir) -> SteamSave {
+        let options = SaveOptions::for_test();
+        let save_dir = dir.path().join("steam");
+        std::fs::create_dir_all(&save_dir).unwrap();
+
+        SteamSave::new(options, save_dir, dir.path().join("backup")).unwrap()
+    }
+
+    #[test]
+    fn shared_saves_follow_the_changed_side() {
+        let dir = TempDir::new().unwrap();
+        let steam = steam_save(&dir);
+        let remote = RemoteSave::new(dir.path().join("shared"), Duration::ZERO, false).unwrap();
+        let local_path = dir.path().join("steam").join(STEAM_NAME);
+        let remote_path = dir.path().join("shared").join(STEAM_NAME);
+
+        // A new machine gets the save from the shared folder
+        write_save(&remote_path, b"desktop", Duration::from_secs(60));
+        assert_eq!(remote.sync(&steam).unwrap(), [local_path.as_path()]);
+        assert_eq!(read_body(&local_path), b"desktop");
+
+        // A local change is pushed, even when the shared save looks newer
+        write_save(&local_path, b"laptop", Duration::from_secs(60 * 60));
+        assert!(remote.sync(&steam).unwrap().is_empty());
+        assert_eq!(read_body(&remote_path), b"laptop");
+
+        // The echo of our own push changes nothing, and the agreement survives a restart
+        write_save(&remote_path, b"laptop", Duration::ZERO);
+        let remote = RemoteSave::new(dir.path().join("shared"), Duration::ZERO, false).unwrap();
+        assert!(remote.sync(&steam).unwrap().is_empty());
+        assert_eq!(read_body(&local_path), b"laptop");
+    }
+
+    #[test]
+    fn changes_on_both_sides_conflict() {
+        let dir = TempDir::new().unwrap();
+        let steam = steam_save(&dir);
+        let remote = RemoteSave::new(dir.path().join("shared"), Duration::ZERO, false).unwrap();
+        let local_path = dir.path().join("steam").join(STEAM_NAME);
+        let remote_path = dir.path().join("shared").join(STEAM_NAME);
+        write_save(&local_path, b"agreed", Duration::from_secs(60));
+        remote.sync(&steam).unwrap();
+
+        write_save(&local_path, b"laptop", Duration::ZERO);
+        write_save(&remote_path, b"desktop", Duration::ZERO);
+        let result = remote.sync(&steam);
+        assert!(matches!(result, Err(SaveError::Conflict { .. })));
+        assert_eq!(read_body(&local_path), b"laptop");
+        assert_eq!(read_body(&remote_path), b"desktop");
+
+        // The shared save is kept as a local backup
+        let backups = steam.list_backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(read_body(&backups[0].path), b"desktop");
+
+        // The conflict is only reported once
+        assert_eq!(remote.sync(&steam).unwrap(), Vec::<PathBuf>::new());
+        assert_eq!(steam.list_backups().unwrap().len(), 1);
+
+        // Until either save changes
+        write_save(&remote_path, b"desktop again", Duration::ZERO);
+        let result = remote.sync(&steam);
+        assert!(matches!(result, Err(SaveError::Conflict { .. })));
+
+        // Keeping the local save resolves it
+        remote.keep_local(&steam);
+        assert_eq!(remote.sync(&steam).unwrap(), Vec::<PathBuf>::new());
+        assert_eq!(read_body(&local_path), b"laptop");
+        assert_eq!(read_body(&remote_path), b"laptop");
+    }
+
+    #[test]
+    fn shared_changes_wait_to_settle() {
+        let dir = TempDir::new().unwrap();
+        let steam = steam_save(&dir);
+        let settle_time = Duration::from_millis(50);
+        let remote = RemoteSave::new(dir.path().join("shared"), settle_time, false).unwrap();
+        let local_path = dir.path().join("steam").join(STEAM_NAME);
+        let remote_path = dir.path().join("shared").join(STEAM_NAME);
+
+        write_save(&remote_path, b"partial", Duration::from_secs(60));
+        let result = remote.sync(&steam);
+        assert!(matches!(result, Err(SaveError::StillWriting(_))));
+        assert!(!local_path.exists());
+
+        std::thread::sleep(settle_time);
+        assert_eq!(remote.sync(&steam).unwrap(), [local_path.as_path()]);
+        assert_eq!(read_body(&local_path), b"partial");
+    }
+}
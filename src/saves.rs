@@ -1,21 +1,88 @@
+use crate::gvas::SaveInfo;
+use crate::hash::HashCache;
+use crate::store::{FsStore, SaveStore, StoreEntry};
+use crate::wgs;
+use crate::{
+    BackupOutcome, BackupPolicy, BackupTimestamp, Direction, EventSink, HashAlgo, Side, SlotFilter,
+    SteeveBuilder, SteeveEvent, SyncDecision, SyncOutcome,
+};
 use directories::BaseDirs;
-use log::{debug, info};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use log::{debug, info, warn};
 use std::{
-    collections::hash_map::DefaultHasher,
-    hash::Hasher,
+    cmp::Ordering,
+    io::{ErrorKind, Read},
     path::{Path, PathBuf},
-    time::SystemTime,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
 };
 use steamlocate::SteamDir;
 use thiserror::Error;
-use walkdir::WalkDir;
+use time::{format_description::FormatItem, macros::format_description};
+use time::{OffsetDateTime, PrimitiveDateTime};
 
 /// Steam app ID for Deep Rock Galactic.
 /// See: https://steamdb.info/app/548430/
 const DRG_APP_ID: &u32 = &548430;
 
+/// Default install directory name for Deep Rock Galactic in a Steam library.
+const STEAM_INSTALL_DIR: &str = "Deep Rock Galactic";
+
+/// Path of the save directory in the game's Proton prefix, under `compatdata/{DRG_APP_ID}`.
+#[cfg(target_os = "linux")]
+const PROTON_SAVE_DIR: [&str; 9] = [
+    "pfx",
+    "drive_c",
+    "users",
+    "steamuser",
+    "AppData",
+    "Local",
+    "FSD",
+    "Saved",
+    "SaveGames",
+];
+
+/// Xbox package name for Deep Rock Galactic, and its prefix without the publisher ID.
+const XBOX_PACKAGE: &str = "CoffeeStainStudios.DeepRockGalactic_496a1srhmar9w";
+const XBOX_PACKAGE_PREFIX: &str = "CoffeeStainStudios.DeepRockGalactic_";
+
+/// Well-known Xbox save container directory.
+const XBOX_CONTAINER: &str = "000901F266032D3B_882901006F2042808DB0569531F199CB";
+
+/// Magic bytes at the start of every Unreal Engine save game.
+pub(crate) const GVAS_MAGIC: &[u8; 4] = b"GVAS";
+
+/// Smallest size of a plausible save file. Anything smaller is truncated, even a bare GVAS header.
+pub(crate) const MIN_SAVE_SIZE: u64 = 64;
+
+/// ISO 8601 timestamp format for backup file names, with dashes in place of colons.
+const ISO_TIMESTAMP: &[FormatItem<'static>] =
+    format_description!("[year]-[month]-[day]T[hour]-[minute]-[second]Z");
+
+/// Name of the trash directory inside the backup directory.
+pub(crate) const TRASH_DIR: &str = "Trash";
+
+/// Extension of the temporary file that a save is copied to before it replaces the destination.
+const TEMP_EXTENSION: &str = "steeve-tmp";
+
+/// Prefix of the copies kept of saves that lost a conflict, see [`SaveError::Conflict`].
+const CONFLICT_PREFIX: &str = "conflict_";
+
+/// Extension added to the names of compressed backups.
+const COMPRESSED_EXTENSION: &str = "gz";
+
+/// Delays between attempts to copy a save that is briefly locked, e.g. by the game or antivirus.
+const RETRY_DELAYS: [Duration; 2] = [Duration::from_millis(250), Duration::from_millis(750)];
+
+/// Environment variable used for the local data directory when it cannot be resolved otherwise.
+const LOCAL_DATA_ENV: &str = "LOCALAPPDATA";
+
 /// All the ways in which save file and backup handling can fail.
+///
+/// More variants may be added, see [`SaveError::is_benign`] for telling expected skips apart
+/// from real errors.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum SaveError {
     #[error("Could not find home directory")]
     HomeDir,
@@ -23,8 +90,8 @@ pub enum SaveError {
     #[error("Could not find Steam")]
     SteamDir,
 
-    #[error("Could not find Deep Rock Galactic on Steam")]
-    SteamApp,
+    #[error("Could not find Deep Rock Galactic in any Steam library: {0:?}")]
+    SteamApp(Vec<PathBuf>),
 
     #[error("Unable to create directory: {0}")]
     DirCreate(PathBuf),
@@ -32,286 +99,3422 @@ pub enum SaveError {
     #[error("No save file")]
     NoSave,
 
+    #[error("Save directory does not exist: {0}")]
+    SaveDirMissing(PathBuf),
+
+    #[error("Unable to read the save being synced: {0}")]
+    SourceUnavailable(PathBuf, #[source] std::io::Error),
+
+    #[error("Not a valid save file: {0}")]
+    NotASave(PathBuf),
+
+    #[error("Not a backup of this save: {0}")]
+    NotABackup(PathBuf),
+
     #[error("Destination was modified more recently than source")]
     ModifyTime,
 
+    #[error("Sync deferred by pre-sync hook")]
+    Deferred,
+
+    #[error("Sync held until the game exits")]
+    GameRunning,
+
+    #[error("Save is still being written: {0}")]
+    StillWriting(PathBuf),
+
+    #[error("Save slot {slot} could be synced to any of {candidates:?}, pair it with one")]
+    AmbiguousSlot {
+        /// The slot of the save being synced: its file name without the extension.
+        slot: String,
+
+        /// The file names of the saves on the other side that it could be synced over.
+        candidates: Vec<String>,
+    },
+
+    #[error("Save version {from} has a newer format than the destination's {to}")]
+    VersionMismatch { from: SaveVersion, to: SaveVersion },
+
+    #[error("Synced save does not match the source and was rolled back: {0}")]
+    SyncVerify(PathBuf),
+
+    #[error("Staged save does not match the source: {0}")]
+    Staging(PathBuf),
+
+    #[error("Both saves changed since the last sync, neither was overwritten: {from} and {to}")]
+    Conflict {
+        /// The save that would have been copied: the other edition's save, or the shared save.
+        from: PathBuf,
+
+        /// The save that would have been overwritten.
+        to: PathBuf,
+    },
+
+    #[error("Not a valid Xbox containers index: {0}")]
+    BadIndex(PathBuf),
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("{} syncs failed: {}", .0.len(), join_errors(.0))]
+    Several(Vec<SaveError>),
 }
 
-/// Manages Steam directories for saves and backups.
-#[derive(Clone, Debug)]
-pub(crate) struct SteamSave {
-    max_backups: usize,
-    backup_dir: PathBuf,
-    save_dir: PathBuf,
+/// Join the messages of several errors into one line.
+fn join_errors(errors: &[SaveError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
-impl SteamSave {
-    pub(crate) fn new(max_backups: usize, mut backup_dir: PathBuf) -> Result<Self, SaveError> {
-        // Get the save path for Steam
-        let mut save_dir = SteamDir::locate()
-            .ok_or(SaveError::SteamDir)?
-            .app(DRG_APP_ID)
-            .ok_or(SaveError::SteamApp)?
-            .path
-            .clone();
-        save_dir.push("FSD");
-        save_dir.push("Saved");
-        save_dir.push("SaveGames");
+impl SaveError {
+    /// Check if this is an expected reason for not syncing, rather than a real error.
+    ///
+    /// A missing save or save directory, a destination that is already up to date, a sync
+    /// deferred by the pre-sync hook, and a sync held while the game runs are all benign.
+    pub fn is_benign(&self) -> bool {
+        matches!(
+            self,
+            Self::NoSave
+                | Self::SaveDirMissing(_)
+                | Self::ModifyTime
+                | Self::Deferred
+                | Self::GameRunning
+        )
+    }
+}
 
-        backup_dir.push("Steam");
+/// The result of looking for a save in a save directory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LocateResult {
+    /// The save file was found, with this path and file name.
+    Found(PathBuf, String),
 
-        // Create backup path
-        std::fs::create_dir_all(&backup_dir)
-            .map_err(|_| SaveError::DirCreate(backup_dir.clone()))?;
+    /// The save directory exists, but none of the files in it look like a save.
+    ///
+    /// With no files at all, the edition has probably never been played. Otherwise the save may
+    /// have been renamed, e.g. by a game update.
+    NoMatch {
+        /// Number of files that were checked.
+        scanned: usize,
+    },
 
-        Ok(Self {
-            max_backups,
-            save_dir,
-            backup_dir,
-        })
+    /// The save directory does not exist.
+    DirMissing,
+}
+
+impl LocateResult {
+    /// Get the path and file name of the save, if it was found.
+    pub fn found(self) -> Option<(PathBuf, String)> {
+        match self {
+            Self::Found(path, filename) => Some((path, filename)),
+            _ => None,
+        }
     }
 }
 
-/// Manages Xbox directories for saves and backups.
-#[derive(Clone, Debug)]
-pub(crate) struct XboxSave {
-    max_backups: usize,
-    backup_dir: PathBuf,
-    save_dir: PathBuf,
+/// A summary of the backups for one side.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BackupStats {
+    /// Number of backups.
+    pub count: usize,
+
+    /// Total size of all backups, in bytes.
+    pub total_bytes: u64,
+
+    /// Time of the oldest backup, if there are any.
+    ///
+    /// This is the time in the backup name, which is kept when backups are copied or touched.
+    pub oldest: Option<SystemTime>,
+
+    /// Time of the newest backup, if there are any.
+    pub newest: Option<SystemTime>,
 }
 
-impl XboxSave {
-    pub(crate) fn new(max_backups: usize, mut backup_dir: PathBuf) -> Result<Self, SaveError> {
-        // Get the save path for Xbox
-        let mut save_dir = BaseDirs::new()
-            .ok_or(SaveError::HomeDir)?
-            .data_local_dir()
-            .to_path_buf();
-        save_dir.push("Packages");
-        save_dir.push("CoffeeStainStudios.DeepRockGalactic_496a1srhmar9w");
-        save_dir.push("SystemAppData");
-        save_dir.push("wgs");
-        save_dir.push("000901F266032D3B_882901006F2042808DB0569531F199CB");
+/// The format and engine versions in a save's GVAS header.
+///
+/// Versions are ordered from oldest to newest, by format version first and engine build last.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SaveVersion {
+    /// Version of the save game format.
+    pub save_game: u32,
 
-        backup_dir.push("Xbox");
+    /// Unreal Engine 4 package format version.
+    pub package: u32,
 
-        // Create backup path
-        std::fs::create_dir_all(&backup_dir)
-            .map_err(|_| SaveError::DirCreate(backup_dir.clone()))?;
+    /// Unreal Engine 5 package format version, zero for older saves.
+    pub package_ue5: u32,
 
-        Ok(Self {
-            max_backups,
-            save_dir,
-            backup_dir,
+    /// Engine major, minor, and patch version.
+    pub engine: (u16, u16, u16),
+
+    /// Engine build (changelist) number.
+    pub build: u32,
+}
+
+impl SaveVersion {
+    /// Save game format version that added the Unreal Engine 5 package version.
+    pub(crate) const UE5_SAVE_GAME: u32 = 3;
+
+    /// Parse the version from the start of a save file.
+    ///
+    /// Returns `None` if the bytes are too short, or do not start with a GVAS header.
+    pub(crate) fn parse(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.strip_prefix(GVAS_MAGIC)?;
+        let u16_at = |offset: usize| {
+            let bytes = bytes.get(offset..offset + 2)?;
+            Some(u16::from_le_bytes(bytes.try_into().ok()?))
+        };
+        let u32_at = |offset: usize| {
+            let bytes = bytes.get(offset..offset + 4)?;
+            Some(u32::from_le_bytes(bytes.try_into().ok()?))
+        };
+
+        let save_game = u32_at(0)?;
+        let package = u32_at(4)?;
+        let (package_ue5, engine) = if save_game >= Self::UE5_SAVE_GAME {
+            (u32_at(8)?, 12)
+        } else {
+            (0, 8)
+        };
+
+        Some(Self {
+            save_game,
+            package,
+            package_ue5,
+            engine: (u16_at(engine)?, u16_at(engine + 2)?, u16_at(engine + 4)?),
+            build: u32_at(engine + 6)?,
         })
     }
+
+    /// Check if this save has a newer format than the `other` save, so the game that wrote the
+    /// other save may not be able to read it.
+    ///
+    /// Only the save game and package format versions count. A newer engine build alone does not
+    /// change the format, and the editions often run different builds of the same game version.
+    pub fn has_newer_format(&self, other: &Self) -> bool {
+        self.save_game > other.save_game
+            || self.package > other.package
+            || self.package_ue5 > other.package_ue5
+    }
+
+    /// Read the version of the save at `path`.
+    fn read(store: &dyn SaveStore, path: &Path) -> Option<Self> {
+        let mut header = Vec::new();
+        store
+            .open(path)
+            .ok()?
+            .take(MIN_SAVE_SIZE)
+            .read_to_end(&mut header)
+            .ok()?;
+
+        Self::parse(&header)
+    }
 }
 
-/// A handy internal trait for keeping save directory handling DRY.
-pub(crate) trait SteeveSave {
-    /// Get the implementation name.
-    fn name(&self) -> &str;
+impl std::fmt::Display for SaveVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (major, minor, patch) = self.engine;
 
-    /// Get maximum number of backups to retain.
-    fn max_backups(&self) -> usize;
+        write!(f, "{major}.{minor}.{patch}-{}", self.build)
+    }
+}
 
-    /// Get the backup directory.
-    fn backup_dir(&self) -> &Path;
+/// A backup of one side's save.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackupEntry {
+    /// Path to the backup file.
+    pub path: PathBuf,
 
-    /// Get the save directory.
-    fn save_dir(&self) -> &Path;
+    /// When the backup was made, from the timestamp in its file name.
+    pub timestamp: SystemTime,
 
-    /// Get the file (leaf) name if the path looks like the current save file.
-    fn save_file<P: AsRef<Path>>(path: P) -> Option<String>;
+    /// File name of the save that was backed up.
+    pub filename: String,
 
-    /// Copy the given save file to one that we can locate.
-    fn copy_save<P: AsRef<Path>>(&self, from: P) -> Result<(), SaveError> {
-        let from = from.as_ref();
+    /// Size of the backup, in bytes.
+    pub size: u64,
 
-        let (to, filename) = match self.locate_save_path() {
-            Some((path, filename)) => (path, filename),
-            None => return Err(SaveError::NoSave),
-        };
+    /// Whether the backup is compressed with gzip.
+    pub compressed: bool,
+}
 
-        // Compare the file modify times
-        let from_time = from.metadata()?.modified()?;
-        let to_time = to.metadata()?.modified()?;
-        if from_time <= to_time {
-            return Err(SaveError::ModifyTime);
+/// The save that a verified copy replaces, to roll back to when the copy does not match.
+#[derive(Debug)]
+pub(crate) enum Original {
+    /// The contents and modify time of the replaced save.
+    Save(Vec<u8>, SystemTime),
+
+    /// No save had the exact name, e.g. when a stand-in copy was synced, so it is removed.
+    Missing,
+}
+
+/// Settings for save and backup handling.
+#[derive(Clone, Debug)]
+pub(crate) struct SaveOptions {
+    /// Maximum number of backups to retain.
+    pub(crate) max_backups: usize,
+
+    /// Trash for pruned backups, if enabled.
+    pub(crate) trash: Option<Trash>,
+
+    /// Whether saves are backed up before they are overwritten.
+    pub(crate) backup_policy: BackupPolicy,
+
+    /// Timestamp format for new backup file names.
+    pub(crate) backup_timestamp: BackupTimestamp,
+
+    /// Compress new backups with gzip.
+    pub(crate) compress_backups: bool,
+
+    /// Hash algorithm for detecting duplicate backups.
+    pub(crate) hash_algo: HashAlgo,
+
+    /// Save slots that take part in syncing.
+    pub(crate) slot_filter: SlotFilter,
+
+    /// Steam and Xbox save slots that are synced with each other.
+    pub(crate) slot_pairs: Vec<(String, String)>,
+
+    /// Directory to stage copies in before moving them over the destination, relative to the
+    /// save directory. Copies go straight to the destination when `None`.
+    pub(crate) staging_dir: Option<PathBuf>,
+
+    /// Refuse to sync a save with a newer version than the destination.
+    pub(crate) check_versions: bool,
+
+    /// Read back each synced save, and roll back when it does not match the source.
+    pub(crate) verify_syncs: bool,
+
+    /// Log and report syncs and backups without writing anything.
+    pub(crate) dry_run: bool,
+
+    /// How long a source save must stay unchanged before it is copied.
+    pub(crate) settle_time: Duration,
+
+    /// How far apart modify times can be and still count as the same time.
+    pub(crate) mtime_tolerance: Duration,
+
+    /// Storage for saves and backups.
+    pub(crate) store: Arc<dyn SaveStore>,
+
+    /// Cached backup hashes.
+    pub(crate) hash_cache: Arc<HashCache>,
+
+    /// Where sync events are sent.
+    pub(crate) events: Arc<EventSink>,
+}
+
+impl SaveOptions {
+    /// Take the save handling settings from a builder, with the Steam backup limit.
+    ///
+    /// There is no trash, and each call gets its own hash cache and event sink.
+    pub(crate) fn from_builder(builder: &SteeveBuilder) -> Self {
+        Self {
+            max_backups: builder.steam_max_backups,
+            trash: None,
+            backup_policy: builder.backup_policy,
+            backup_timestamp: builder.backup_timestamp,
+            compress_backups: builder.compress_backups,
+            hash_algo: builder.hash_algo,
+            slot_filter: builder.slot_filter.clone(),
+            slot_pairs: builder.slot_pairs.clone(),
+            staging_dir: builder.staging_dir.clone(),
+            check_versions: builder.check_versions,
+            verify_syncs: builder.verify_syncs,
+            dry_run: builder.dry_run,
+            settle_time: builder.settle_time,
+            mtime_tolerance: builder.mtime_tolerance,
+            store: Arc::new(FsStore::new(builder.follow_links)),
+            hash_cache: Arc::default(),
+            events: Arc::default(),
         }
+    }
+}
 
-        // Backup the destination save file
-        self.backup(&to, &filename)?;
+/// Manages Steam directories for saves and backups.
+#[derive(Clone, Debug)]
+pub(crate) struct SteamSave {
+    options: SaveOptions,
+    backup_dir: PathBuf,
+    save_dir: PathBuf,
+}
 
-        // Do the final copy
-        info!("Steeve is syncing a new save to {}", self.name());
-        debug!("Copy {} save: {:?} -> {:?}", self.name(), from, to);
-        std::fs::copy(from, &to)?;
+impl SteamSave {
+    /// Create a save manager, creating `backup_dir` if it does not exist, unless this is a dry
+    /// run.
+    pub(crate) fn new(
+        options: SaveOptions,
+        save_dir: PathBuf,
+        backup_dir: PathBuf,
+    ) -> Result<Self, SaveError> {
+        // Create backup path
+        if !options.dry_run {
+            std::fs::create_dir_all(&backup_dir)
+                .map_err(|_| SaveError::DirCreate(backup_dir.clone()))?;
+        }
+        options.hash_cache.load(options.store.as_ref(), &backup_dir);
 
-        Ok(())
+        Ok(Self {
+            options,
+            save_dir: resolve_links(&save_dir),
+            backup_dir,
+        })
     }
 
-    /// Find a file in the save directory that looks like the current save file.
-    fn locate_save_path(&self) -> Option<(PathBuf, String)> {
-        WalkDir::new(self.save_dir())
+    /// Find the save path for Steam.
+    pub(crate) fn locate_save_dir() -> Result<PathBuf, SaveError> {
+        Self::discover_save_dirs()?
             .into_iter()
-            .filter_map(|result| result.ok())
-            .find_map(|entry| {
-                Self::save_file(entry.path()).map(|filename| (entry.path().to_path_buf(), filename))
-            })
+            .next()
+            .ok_or(SaveError::SteamApp(Vec::new()))
     }
 
-    /// Backup the save file.
-    fn backup<P: AsRef<Path>>(&self, save_path: P, filename: &str) -> Result<bool, SaveError> {
-        let save_path = save_path.as_ref();
+    /// Find all plausible save paths for Steam, starting with the one for the installed app.
+    ///
+    /// Fails with the list of searched libraries when the game is not installed in any of them.
+    fn discover_save_dirs() -> Result<Vec<PathBuf>, SaveError> {
+        let mut steam_dir = SteamDir::locate().ok_or(SaveError::SteamDir)?;
 
-        if self.is_dupe_backup(save_path)? {
-            debug!("{} save backup de-duped: {:?}", self.name(), save_path);
-            return Ok(false);
+        let mut install_dirs = Vec::new();
+        if let Some(app) = steam_dir.app(DRG_APP_ID) {
+            install_dirs.push(app.path.clone());
         }
 
-        self.remove_old_backups()?;
+        // The library list in `steamlocate` is empty past the main library when it cannot parse
+        // `libraryfolders.vdf`, so the file is also scanned here
+        let mut libraries = steam_dir.libraryfolders().paths.clone();
+        let vdf_path = steam_dir.path.join("steamapps").join("libraryfolders.vdf");
+        if let Ok(vdf) = std::fs::read_to_string(vdf_path) {
+            for library in vdf_library_paths(&vdf) {
+                if !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+            }
+        }
 
-        let timestamp = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        // Look for the game in every library, in case the app lookup missed one
+        for library in &libraries {
+            let path = library.join("common").join(steam_install_dir(library));
+            if path.is_dir() && !install_dirs.contains(&path) {
+                install_dirs.push(path);
+            }
+        }
+        if install_dirs.is_empty() {
+            return Err(SaveError::SteamApp(libraries));
+        }
 
-        let mut backup_path = self.backup_dir().to_path_buf();
-        backup_path.push(format!("{}_{}", timestamp, filename));
+        let save_dirs = install_dirs
+            .into_iter()
+            .map(|mut save_dir| {
+                save_dir.push("FSD");
+                save_dir.push("Saved");
+                save_dir.push("SaveGames");
+                save_dir
+            })
+            .collect::<Vec<_>>();
 
-        debug!(
-            "Backup {} save: {:?} -> {:?}",
-            self.name(),
-            save_path,
-            backup_path,
-        );
-        std::fs::copy(save_path, backup_path)?;
+        // Proton saves come first, they are the ones the game uses on Linux
+        #[cfg(target_os = "linux")]
+        let save_dirs = proton_save_dirs(&libraries)
+            .into_iter()
+            .chain(save_dirs)
+            .collect::<Vec<_>>();
+        debug!("Steam save directory candidates: {:?}", save_dirs);
 
-        Ok(true)
+        Ok(save_dirs)
     }
 
-    /// Check if the file is already backed up.
-    fn is_dupe_backup<P: AsRef<Path>>(&self, save_path: P) -> Result<bool, SaveError> {
-        let save_path = save_path.as_ref();
+    /// Replace the save path, e.g. after the Steam library has moved.
+    pub(crate) fn set_save_dir(&mut self, save_dir: PathBuf) {
+        self.save_dir = resolve_links(&save_dir);
+    }
+}
+
+/// Manages Xbox directories for saves and backups.
+#[derive(Clone, Debug)]
+pub(crate) struct XboxSave {
+    options: SaveOptions,
+    backup_dir: PathBuf,
+    save_dir: PathBuf,
+}
 
-        // File comparison is done by hashing its contents
-        let bytes = std::fs::read(save_path)?;
-        let mut hasher = DefaultHasher::new();
-        hasher.write(&bytes);
-        let save_hash = hasher.finish();
+impl XboxSave {
+    /// Create a save manager, creating `backup_dir` if it does not exist, unless this is a dry
+    /// run.
+    pub(crate) fn new(
+        options: SaveOptions,
+        save_dir: PathBuf,
+        backup_dir: PathBuf,
+    ) -> Result<Self, SaveError> {
+        // Create backup path
+        if !options.dry_run {
+            std::fs::create_dir_all(&backup_dir)
+                .map_err(|_| SaveError::DirCreate(backup_dir.clone()))?;
+        }
+        options.hash_cache.load(options.store.as_ref(), &backup_dir);
 
-        let is_dupe = WalkDir::new(self.backup_dir())
-            .into_iter()
-            .filter_map(|result| result.ok())
-            .any(|entry| {
-                if !entry.file_type().is_file() {
-                    return false;
-                }
+        Ok(Self {
+            options,
+            save_dir: resolve_links(&save_dir),
+            backup_dir,
+        })
+    }
 
-                let bytes = match std::fs::read(entry.path()) {
-                    Ok(bytes) => bytes,
-                    Err(_) => return false,
-                };
-                let mut hasher = DefaultHasher::new();
-                hasher.write(&bytes);
+    /// Find the save path for Xbox.
+    pub(crate) fn locate_save_dir() -> Result<PathBuf, SaveError> {
+        Self::pick_save_dir(Self::discover_save_dirs()?).ok_or(SaveError::HomeDir)
+    }
 
-                hasher.finish() == save_hash
-            });
+    /// Pick the well-known container from the candidates, or follow the save to another one.
+    ///
+    /// The container can change when the game re-provisions its storage, e.g. after an update.
+    /// When the well-known container is gone, the first other container with a valid save is
+    /// used instead.
+    fn pick_save_dir(candidates: Vec<PathBuf>) -> Option<PathBuf> {
+        let mut candidates = candidates.into_iter();
+        let save_dir = candidates.next()?;
+        if save_dir.is_dir() {
+            return Some(save_dir);
+        }
 
-        Ok(is_dupe)
+        let store = FsStore::default();
+        let moved = candidates.find(|candidate| {
+            store
+                .walk(candidate)
+                .unwrap_or_default()
+                .iter()
+                .any(|entry| {
+                    save_name::<Self>(&entry.path).is_some()
+                        && validate_save(&store, &entry.path).is_ok()
+                })
+        });
+        match moved {
+            Some(moved) => {
+                info!("Xbox save container {save_dir:?} is gone, following the save to {moved:?}");
+                Some(moved)
+            }
+            None => Some(save_dir),
+        }
     }
 
-    /// Remove old backups.
-    fn remove_old_backups(&self) -> Result<(), SaveError> {
-        let files = WalkDir::new(self.backup_dir())
-            .sort_by_key(|entry| match entry.metadata() {
-                Ok(meta) => match meta.modified() {
-                    Ok(mtime) => mtime,
-                    Err(_) => SystemTime::UNIX_EPOCH,
-                },
-                Err(_) => SystemTime::UNIX_EPOCH,
+    /// Find all plausible save paths for Xbox, starting with the well-known container.
+    ///
+    /// Falls back to the `LOCALAPPDATA` environment variable in minimal environments (service
+    /// accounts, WSL without a proper home) where the standard directories cannot be resolved.
+    fn discover_save_dirs() -> Result<Vec<PathBuf>, SaveError> {
+        let mut packages_dir = match BaseDirs::new() {
+            Some(base_dirs) => base_dirs.data_local_dir().to_path_buf(),
+            None => std::env::var_os(LOCAL_DATA_ENV)
+                .map(PathBuf::from)
+                .ok_or(SaveError::HomeDir)?,
+        };
+        packages_dir.push("Packages");
+
+        let mut save_dir = packages_dir.join(XBOX_PACKAGE);
+        save_dir.push("SystemAppData");
+        save_dir.push("wgs");
+        save_dir.push(XBOX_CONTAINER);
+
+        // Every container directory in every package for the game is a candidate
+        let mut containers = read_dirs(&packages_dir)
+            .filter(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().starts_with(XBOX_PACKAGE_PREFIX))
+                    .unwrap_or(false)
             })
-            .into_iter()
-            .filter_map(|result| result.ok())
-            .filter(|entry| entry.file_type().is_file())
+            .flat_map(|package| read_dirs(&package.join("SystemAppData").join("wgs")))
+            .filter(|path| path != &save_dir)
             .collect::<Vec<_>>();
+        containers.sort();
+
+        let mut save_dirs = vec![save_dir];
+        save_dirs.extend(containers);
+        debug!("Xbox save directory candidates: {:?}", save_dirs);
+
+        Ok(save_dirs)
+    }
+
+    /// Replace the save path, e.g. after the game has been reinstalled.
+    pub(crate) fn set_save_dir(&mut self, save_dir: PathBuf) {
+        self.save_dir = resolve_links(&save_dir);
+    }
+}
+
+/// Holds pruned backups for a recovery window before they are removed for good.
+#[derive(Clone, Debug)]
+pub(crate) struct Trash {
+    max_files: usize,
+    dir: PathBuf,
+}
+
+impl Trash {
+    /// Create a trash in `dir`, creating it if it does not exist, unless this is a dry run.
+    pub(crate) fn new(
+        max_files: usize,
+        mut dir: PathBuf,
+        dry_run: bool,
+    ) -> Result<Self, SaveError> {
+        dir.push(TRASH_DIR);
+
+        // Create trash path
+        if !dry_run {
+            std::fs::create_dir_all(&dir).map_err(|_| SaveError::DirCreate(dir.clone()))?;
+        }
+
+        Ok(Self { max_files, dir })
+    }
 
-        let max_backups = self.max_backups() - 1;
-        if files.len() > max_backups {
-            for entry in files.iter().take(files.len() - max_backups) {
-                let path = entry.path();
-                debug!("Removing old {} backup: {:?}", self.name(), path);
+    /// Move a file into the trash, permanently removing the oldest files over the cap.
+    pub(crate) fn put(&self, path: &Path) -> Result<(), SaveError> {
+        let mut trash_path = self.dir.clone();
+        trash_path.push(path.file_name().ok_or(SaveError::NoSave)?);
+
+        debug!("Trash backup: {:?} -> {:?}", path, trash_path);
+        std::fs::rename(path, trash_path)?;
+
+        let files = self.files();
+        if files.len() > self.max_files {
+            for entry in files.iter().take(files.len() - self.max_files) {
+                let path = &entry.path;
+                debug!("Removing old trash: {:?}", path);
                 std::fs::remove_file(path)?;
             }
         }
 
         Ok(())
     }
-}
 
-impl SteeveSave for SteamSave {
-    fn name(&self) -> &str {
-        "Steam"
+    /// Permanently remove all files in the trash.
+    pub(crate) fn empty(&self) -> Result<(), SaveError> {
+        for entry in self.files() {
+            let path = &entry.path;
+            debug!("Removing trash: {:?}", path);
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
     }
 
-    fn max_backups(&self) -> usize {
-        self.max_backups
+    /// Get the files in the trash, from oldest to newest modify time.
+    fn files(&self) -> Vec<StoreEntry> {
+        FsStore::default().list(&self.dir).unwrap_or_default()
     }
+}
 
-    fn backup_dir(&self) -> &Path {
-        &self.backup_dir
+/// A handy internal trait for keeping save directory handling DRY.
+pub(crate) trait SteeveSave {
+    /// Get the implementation name.
+    fn name(&self) -> &str;
+
+    /// Get the side that this save belongs to.
+    fn side(&self) -> Side;
+
+    /// Get the save and backup handling settings.
+    fn options(&self) -> &SaveOptions;
+
+    /// Get the backup directory.
+    fn backup_dir(&self) -> &Path;
+
+    /// Get the save directory.
+    fn save_dir(&self) -> &Path;
+
+    /// Get the storage for saves and backups.
+    fn store(&self) -> &dyn SaveStore {
+        &*self.options().store
     }
 
-    fn save_dir(&self) -> &Path {
-        &self.save_dir
+    /// Hash the contents of a stored file with the configured algorithm.
+    ///
+    /// Compressed backups are hashed by the save they contain.
+    fn hash(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        let reader = self.store().open(path)?;
+        let algo = self.options().hash_algo;
+        if is_compressed(path) {
+            algo.hash_reader(GzDecoder::new(reader))
+        } else {
+            algo.hash_reader(reader)
+        }
     }
 
+    /// Check if a file name looks like the current save file.
+    fn is_save_name(filename: &str) -> bool;
+
+    /// Get the file (leaf) name if the path is a file that looks like the current save file.
     fn save_file<P: AsRef<Path>>(path: P) -> Option<String> {
         let path = path.as_ref();
-        let filename = match (path.is_file(), path.file_name()) {
-            (true, Some(filename)) => filename.to_string_lossy().to_string(),
-            _ => return None,
-        };
-
-        if filename.ends_with("_Player.sav") {
-            Some(filename)
-        } else {
-            None
+        if !path.is_file() {
+            return None;
         }
-    }
-}
 
-impl SteeveSave for XboxSave {
-    fn name(&self) -> &str {
-        "Xbox"
+        save_name::<Self>(path)
     }
 
-    fn max_backups(&self) -> usize {
-        self.max_backups
+    /// Copy the given save file to one that we can locate.
+    ///
+    /// The `pre_sync` hook is called with the destination path after it has been backed up, and
+    /// decides whether the copy goes ahead.
+    fn copy_save<P, F>(&self, from: P, pre_sync: F) -> Result<SyncOutcome, SaveError>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&Path) -> SyncDecision,
+    {
+        self.copy_save_with(from.as_ref(), pre_sync, false)
     }
 
-    fn backup_dir(&self) -> &Path {
-        &self.backup_dir
+    /// Copy the given save file over the one that we can locate, even when that one is newer.
+    ///
+    /// This is [`SteeveSave::copy_save`] without the modify time guard and the conflict check.
+    /// The source is still validated, and the destination is always backed up first, even with
+    /// [`BackupPolicy::Disabled`].
+    fn force_copy_save<P, F>(&self, from: P, pre_sync: F) -> Result<SyncOutcome, SaveError>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&Path) -> SyncDecision,
+    {
+        self.copy_save_with(from.as_ref(), pre_sync, true)
     }
 
-    fn save_dir(&self) -> &Path {
-        &self.save_dir
-    }
+    /// Copy a save, see [`SteeveSave::copy_save`] and [`SteeveSave::force_copy_save`].
+    fn copy_save_with<F>(
+        &self,
+        from: &Path,
+        pre_sync: F,
+        force: bool,
+    ) -> Result<SyncOutcome, SaveError>
+    where
+        F: FnOnce(&Path) -> SyncDecision,
+    {
+        // The game only reads the save with the exact name, so a copy like `_Player (1).sav`
+        // that stands in for a missing save is compared and backed up, but never written
+        let (found, filename) = self.locate_dest(from)?;
+        let filename = exact_save_name(&filename);
+        let to = found.with_file_name(&filename);
 
-    fn save_file<P: AsRef<Path>>(path: P) -> Option<String> {
-        let path = path.as_ref();
-        let filename = match (path.is_file(), path.file_name()) {
-            (true, Some(filename)) => filename.to_string_lossy().to_string(),
-            _ => return None,
-        };
+        // Compare the file modify times
+        let from_entry = self
+            .store()
+            .stat(from)
+            .map_err(|err| SaveError::SourceUnavailable(from.to_path_buf(), err))?;
+        let to_entry = self.store().stat(&found)?;
+        let order = if force {
+            Ordering::Greater
+        } else {
+            self.compare_times(&from_entry, &to_entry)
+        };
+        let hashes = match order {
+            Ordering::Greater => None,
+            Ordering::Less => return Err(SaveError::ModifyTime),
+            Ordering::Equal => {
+                // Too close to call by modify time, so decide by contents
+                let hashes = self.hash_pair(from, &found)?;
+                if hashes.0 == hashes.1 {
+                    debug!("{} save is identical to {:?}", self.name(), from);
+                    self.record_synced(from, &found, &hashes.0);
+                    return Ok(SyncOutcome::Identical);
+                }
+                let from_key = (from_entry.len, from_entry.modified);
+                if from_key <= (to_entry.len, to_entry.modified) {
+                    return Err(SaveError::ModifyTime);
+                }
+                Some(hashes)
+            }
+        };
+
+        // The game may still be writing the save when the debounced event arrives
+        self.wait_for_settle(&from_entry)?;
+
+        // Never propagate an empty or truncated save over a good one
+        validate_save(self.store(), from).map_err(|err| match err {
+            SaveError::Io(err) => SaveError::SourceUnavailable(from.to_path_buf(), err),
+            err => err,
+        })?;
+
+        // A save from an updated edition can corrupt the profile in one that is not updated yet
+        if self.options().check_versions {
+            let versions = (
+                SaveVersion::read(self.store(), from),
+                SaveVersion::read(self.store(), &found),
+            );
+            if let (Some(from), Some(to)) = versions {
+                if from.has_newer_format(&to) {
+                    return Err(SaveError::VersionMismatch { from, to });
+                }
+            }
+        }
+
+        // The game may rewrite a save without changing it. The hashes from a tie are still
+        // current, because the source did not change while settling.
+        let (from_hash, to_hash) = match hashes {
+            Some(hashes) => hashes,
+            None => self.hash_pair(from, &found)?,
+        };
+        if from_hash == to_hash {
+            debug!("{} save is identical to {:?}", self.name(), from);
+            self.record_synced(from, &found, &from_hash);
+            return Ok(SyncOutcome::Identical);
+        }
 
-        if filename.len() == 32 && filename.chars().all(|ch| ch.is_ascii_hexdigit()) {
-            Some(filename)
+        // Both editions were played since the last sync, e.g. while Steeve was not running
+        if !force && self.is_conflict(from, &from_hash, &found, &to_hash) {
+            warn!(
+                "Both saves changed since the last sync, keeping a copy of the {} save",
+                self.name()
+            );
+            self.conflict_copy(&found, &filename)?;
+            return Err(SaveError::Conflict {
+                from: from.to_path_buf(),
+                to: found,
+            });
+        }
+
+        // Keep the save that is replaced in memory to roll back to, when verifying the copy
+        let verify = self.options().verify_syncs;
+        let original = if verify {
+            Some(self.original_save(&to)?)
         } else {
             None
+        };
+
+        // Backup the destination save file
+        if force || self.options().backup_policy == BackupPolicy::Enabled {
+            self.backup(&found, &filename)?;
+        }
+
+        if self.options().dry_run {
+            let bytes = self
+                .store()
+                .stat(from)
+                .map_err(|err| SaveError::SourceUnavailable(from.to_path_buf(), err))?
+                .len;
+            info!("Dry run: Steeve would sync a new save to {}", self.name());
+            debug!("Dry run copy {} save: {:?} -> {:?}", self.name(), from, to);
+            let direction = match self.side() {
+                Side::Steam => Direction::XboxToSteam,
+                Side::Xbox => Direction::SteamToXbox,
+            };
+            self.options().events.emit(SteeveEvent::SaveCopied {
+                direction,
+                from: from.to_path_buf(),
+                to,
+                dry_run: true,
+            });
+
+            return Ok(SyncOutcome::DryRun { bytes });
+        }
+
+        match pre_sync(&to) {
+            SyncDecision::Proceed => (),
+            SyncDecision::Skip => {
+                info!("Sync to {} skipped by pre-sync hook", self.name());
+                return Ok(SyncOutcome::Skipped);
+            }
+            SyncDecision::Defer => return Err(SaveError::Deferred),
+        }
+
+        // Do the final copy
+        info!("Steeve is syncing a new save to {}", self.name());
+        debug!("Copy {} save: {:?} -> {:?}", self.name(), from, to);
+        let (bytes, duration) = match self.staging_dir() {
+            Some(staging_dir) => {
+                let start = Instant::now();
+                let bytes = self.staged_copy(from, &to, &staging_dir)?;
+                (bytes, start.elapsed())
+            }
+            None => self.atomic_copy(from, &to)?,
+        };
+
+        // Keep the source modify time, so the copy is never seen as newer than its source
+        self.store().set_modified(&to, from_entry.modified)?;
+
+        if let Some(original) = original {
+            self.verify_copy(&to, &from_hash, &original)?;
+        }
+        self.finish_write(&to)?;
+        self.record_synced(from, &to, &from_hash);
+
+        Ok(SyncOutcome::Copied {
+            bytes,
+            duration,
+            verified: verify,
+        })
+    }
+
+    /// Hash the source and destination saves.
+    fn hash_pair(&self, from: &Path, to: &Path) -> Result<(Vec<u8>, Vec<u8>), SaveError> {
+        let from_hash = self
+            .hash(from)
+            .map_err(|err| SaveError::SourceUnavailable(from.to_path_buf(), err))?;
+
+        Ok((from_hash, self.hash(to)?))
+    }
+
+    /// Remember that the saves at `from` and `to` both had `hash` after a sync.
+    fn record_synced(&self, from: &Path, to: &Path, hash: &[u8]) {
+        if self.options().dry_run {
+            return;
+        }
+
+        let cache = &self.options().hash_cache;
+        let algo = self.options().hash_algo;
+        for path in [from, to] {
+            cache.set_synced(self.backup_dir(), algo, path, hash.to_vec());
+        }
+        self.save_hash_manifest();
+    }
+
+    /// Check if the saves at `from` and `to` have both changed since they were last synced.
+    ///
+    /// Saves that were never synced have nothing to conflict with, so the newest one wins.
+    fn is_conflict(&self, from: &Path, from_hash: &[u8], to: &Path, to_hash: &[u8]) -> bool {
+        let cache = &self.options().hash_cache;
+        let algo = self.options().hash_algo;
+        match (cache.synced(algo, from), cache.synced(algo, to)) {
+            (Some(from_synced), Some(to_synced)) => {
+                from_synced != from_hash && to_synced != to_hash
+            }
+            _ => false,
+        }
+    }
+
+    /// Copy a save that lost a conflict to `conflict_{timestamp}_{filename}` in the backup
+    /// directory, unless an identical copy is already there.
+    ///
+    /// Conflict copies are not backups, so they are never pruned or restored; they are kept
+    /// until the user reconciles the saves.
+    fn conflict_copy(
+        &self,
+        save_path: &Path,
+        filename: &str,
+    ) -> Result<Option<PathBuf>, SaveError> {
+        let suffix = format!("_{filename}");
+        let hash = self.hash(save_path)?;
+        let copied = self
+            .store()
+            .list(self.backup_dir())
+            .unwrap_or_default()
+            .into_iter()
+            .any(|entry| {
+                let name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+                name.starts_with(CONFLICT_PREFIX)
+                    && name.ends_with(&suffix)
+                    && self.hash(&entry.path).is_ok_and(|copy| copy == hash)
+            });
+        if copied {
+            return Ok(None);
+        }
+
+        let timestamp = format_timestamp(self.options().backup_timestamp, SystemTime::now());
+        let copy_path = self
+            .backup_dir()
+            .join(format!("{CONFLICT_PREFIX}{timestamp}{suffix}"));
+        if self.options().dry_run {
+            info!(
+                "Dry run: Steeve would keep a conflict copy of the {} save",
+                self.name()
+            );
+            return Ok(Some(copy_path));
+        }
+
+        info!(
+            "Keeping a conflict copy of the {} save: {:?}",
+            self.name(),
+            copy_path
+        );
+        self.store()
+            .create_dir_all(self.backup_dir())
+            .map_err(|_| SaveError::DirCreate(self.backup_dir().to_path_buf()))?;
+        timed_copy(self.store(), save_path, &copy_path)?;
+
+        Ok(Some(copy_path))
+    }
+
+    /// Read the save at `to` before it is replaced, see [`SteeveSave::verify_copy`].
+    ///
+    /// This is the save with the exact name, which is not the save that was compared when a
+    /// stand-in copy like `_Player (1).sav` was found instead.
+    fn original_save(&self, to: &Path) -> Result<Original, SaveError> {
+        match self.store().stat(to) {
+            Ok(entry) => Ok(Original::Save(self.store().read(to)?, entry.modified)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Original::Missing),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Check that the copy at `to` has the `expected` hash, or restore the `original` save.
+    ///
+    /// The original modify time is restored too, so the rolled back save is not synced back. When
+    /// there was no original, the copy is removed.
+    fn verify_copy(
+        &self,
+        to: &Path,
+        expected: &[u8],
+        original: &Original,
+    ) -> Result<(), SaveError> {
+        if self.hash(to)? == expected {
+            return Ok(());
+        }
+
+        warn!(
+            "{} save does not match the source, rolling back",
+            self.name()
+        );
+        match original {
+            Original::Save(bytes, modified) => {
+                retry_transient(|| self.store().write(to, bytes))?;
+                self.store().set_modified(to, *modified)?;
+            }
+            Original::Missing => retry_transient(|| self.store().remove(to))?,
+        }
+
+        Err(SaveError::SyncVerify(to.to_path_buf()))
+    }
+
+    /// Compare the modify times of a source and destination save, within the tolerance.
+    ///
+    /// `Equal` means the times are too close to tell which save is newer. Without a tolerance,
+    /// the source must be strictly newer, so the same time counts as older.
+    fn compare_times(&self, from: &StoreEntry, to: &StoreEntry) -> Ordering {
+        let tolerance = self.options().mtime_tolerance;
+        if tolerance.is_zero() {
+            return if from.modified > to.modified {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        if from
+            .modified
+            .duration_since(to.modified)
+            .is_ok_and(|by| by > tolerance)
+        {
+            Ordering::Greater
+        } else if to
+            .modified
+            .duration_since(from.modified)
+            .is_ok_and(|by| by > tolerance)
+        {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Check that a save stays unchanged for the settle time.
+    fn wait_for_settle(&self, before: &StoreEntry) -> Result<(), SaveError> {
+        let settle_time = self.options().settle_time;
+        if settle_time.is_zero() {
+            return Ok(());
+        }
+
+        std::thread::sleep(settle_time);
+        let path = &before.path;
+        let after = self
+            .store()
+            .stat(path)
+            .map_err(|err| SaveError::SourceUnavailable(path.to_path_buf(), err))?;
+        if after.len != before.len || after.modified != before.modified {
+            debug!(
+                "{:?} changed while settling: {:?} -> {:?}",
+                path, before, after
+            );
+            return Err(SaveError::StillWriting(path.to_path_buf()));
+        }
+
+        Ok(())
+    }
+
+    /// Get the staging directory, when staging is enabled.
+    fn staging_dir(&self) -> Option<PathBuf> {
+        self.options()
+            .staging_dir
+            .as_ref()
+            .map(|staging_dir| self.save_dir().join(staging_dir))
+    }
+
+    /// Copy the save into the staging directory, verify it, then rename it over the destination.
+    ///
+    /// The rename is atomic on the same file system, so the game never sees a partial save. The
+    /// rename always happens on the local file system.
+    fn staged_copy(&self, from: &Path, to: &Path, staging_dir: &Path) -> Result<u64, SaveError> {
+        self.store()
+            .create_dir_all(staging_dir)
+            .map_err(|_| SaveError::DirCreate(staging_dir.to_path_buf()))?;
+        let staged = staging_dir.join(to.file_name().ok_or(SaveError::NoSave)?);
+
+        let (bytes, _) = retry_transient(|| timed_copy(self.store(), from, &staged))
+            .map_err(|err| copy_error(self.store(), from, err))?;
+        let verified = validate_save(self.store(), &staged).is_ok()
+            && self.hash(&staged)? == self.hash(from)?;
+        if !verified {
+            let _ = self.store().remove(&staged);
+            return Err(SaveError::Staging(staged));
+        }
+
+        debug!("Move staged {} save: {:?} -> {:?}", self.name(), staged, to);
+        retry_transient(|| move_file(self.store(), &staged, to))?;
+
+        Ok(bytes)
+    }
+
+    /// Copy the save to a temporary file next to the destination, then rename it over the
+    /// destination.
+    ///
+    /// The destination is either the old save or the complete new one, even when the copy is
+    /// interrupted.
+    fn atomic_copy(&self, from: &Path, to: &Path) -> Result<(u64, Duration), SaveError> {
+        let temp = to.with_extension(TEMP_EXTENSION);
+        let copied = retry_transient(|| timed_copy(self.store(), from, &temp))
+            .map_err(|err| copy_error(self.store(), from, err))
+            .and_then(|copied| {
+                retry_transient(|| move_file(self.store(), &temp, to))?;
+                Ok(copied)
+            });
+        if copied.is_err() {
+            let _ = self.store().remove(&temp);
+        }
+
+        copied
+    }
+
+    /// Write a save to a temporary file next to the destination, then rename it over the
+    /// destination, like [`SteeveSave::atomic_copy`].
+    fn atomic_write(&self, to: &Path, bytes: &[u8]) -> Result<(), SaveError> {
+        let temp = to.with_extension(TEMP_EXTENSION);
+        let written = retry_transient(|| self.store().write(&temp, bytes))
+            .and_then(|()| retry_transient(|| move_file(self.store(), &temp, to)));
+        if written.is_err() {
+            let _ = self.store().remove(&temp);
+        }
+
+        Ok(written?)
+    }
+
+    /// Get the directory to watch for changes.
+    ///
+    /// This is the resolved target of a linked save directory, also when the link was created
+    /// after the save directory was set.
+    fn watch_dir(&self) -> PathBuf {
+        resolve_links(self.save_dir())
+    }
+
+    /// Find a file in the save directory that looks like the current save file.
+    fn locate_save_path(&self) -> Option<(PathBuf, String)> {
+        self.locate().found()
+    }
+
+    /// Find every save file in the save directory that takes part in syncing, sorted by path.
+    fn locate_save_paths(&self) -> Vec<(PathBuf, String)> {
+        let mut saves = self
+            .save_files()
+            .into_iter()
+            .filter(|path| self.is_synced_file(path))
+            .filter_map(|path| save_name::<Self>(&path).map(|filename| (path, filename)))
+            .collect::<Vec<_>>();
+        saves.sort();
+
+        saves
+    }
+
+    /// Get the files that may be saves: the ones listed by the save directory's index, or all
+    /// files in the save directory when it has none.
+    fn save_files(&self) -> Vec<PathBuf> {
+        self.indexed_saves().unwrap_or_else(|| {
+            self.store()
+                .walk(self.save_dir())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| entry.path)
+                .collect()
+        })
+    }
+
+    /// Get the save files listed by an index in the save directory, if it has one.
+    fn indexed_saves(&self) -> Option<Vec<PathBuf>> {
+        None
+    }
+
+    /// Bring anything that describes the save file up to date after it was rewritten.
+    fn finish_write(&self, _path: &Path) -> Result<(), SaveError> {
+        Ok(())
+    }
+
+    /// Find the save that the other side's save at `from` is synced over.
+    ///
+    /// Slots are matched by the pairs from
+    /// [`SteeveBuilder::pair_slots`](crate::SteeveBuilder::pair_slots). An unpaired slot is
+    /// synced with the only unpaired save on this side; when there are more, the sync is refused
+    /// instead of guessing.
+    fn locate_dest(&self, from: &Path) -> Result<(PathBuf, String), SaveError> {
+        if !self.store().is_dir(self.save_dir()) {
+            return Err(SaveError::SaveDirMissing(self.save_dir().to_path_buf()));
+        }
+
+        let from_slot = from
+            .file_name()
+            .map(|filename| slot_name(&filename.to_string_lossy()).to_string())
+            .unwrap_or_default();
+        let pairs = &self.options().slot_pairs;
+        let side = self.side();
+        let mut candidates = self.locate_save_paths();
+        match paired_slot(pairs, side, &from_slot) {
+            Some(paired) => {
+                candidates.retain(|(_, filename)| slot_name(filename).eq_ignore_ascii_case(paired))
+            }
+            None => candidates.retain(|(_, filename)| !is_paired(pairs, side, slot_name(filename))),
+        }
+
+        match candidates.len() {
+            0 => {
+                debug!(
+                    "No {} save for slot {} in {:?}",
+                    self.name(),
+                    from_slot,
+                    self.save_dir(),
+                );
+                Err(SaveError::NoSave)
+            }
+            1 => Ok(candidates.remove(0)),
+            _ => Err(SaveError::AmbiguousSlot {
+                slot: from_slot,
+                candidates: candidates
+                    .into_iter()
+                    .map(|(_, filename)| filename)
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Find the current save file, or explain why there is none.
+    fn locate(&self) -> LocateResult {
+        if !self.store().is_dir(self.save_dir()) {
+            return LocateResult::DirMissing;
+        }
+
+        let files = self.save_files();
+        let scanned = files.len();
+        let found = files
+            .into_iter()
+            .filter(|path| self.is_synced_file(path))
+            .find_map(|path| save_name::<Self>(&path).map(|filename| (path, filename)));
+
+        match found {
+            Some((path, filename)) => LocateResult::Found(path, filename),
+            None => LocateResult::NoMatch { scanned },
+        }
+    }
+
+    /// Check if the path is a save that takes part in syncing.
+    ///
+    /// It must be in an included slot, and not a copy in the staging directory.
+    fn is_synced_save(&self, path: &Path) -> bool {
+        self.is_synced_file(path)
+            && self
+                .indexed_saves()
+                .is_none_or(|saves| saves.iter().any(|save| save == path))
+    }
+
+    /// Check if the path is named like a save that takes part in syncing, without checking the
+    /// index.
+    ///
+    /// A copy of a save is only synced when there is no save with the exact name.
+    fn is_synced_file(&self, path: &Path) -> bool {
+        if let Some(staging_dir) = self.staging_dir() {
+            if path.starts_with(staging_dir) {
+                return false;
+            }
+        }
+
+        save_name::<Self>(path).is_some_and(|filename| {
+            self.options().slot_filter.includes(slot_name(&filename))
+                && !self.is_shadowed_copy(path, &filename)
+        })
+    }
+
+    /// Check if the save file is a copy, e.g. from cloud sync, of a save that also exists.
+    fn is_shadowed_copy(&self, path: &Path, filename: &str) -> bool {
+        let exact = exact_save_name(filename);
+
+        exact != filename && self.store().stat(&path.with_file_name(exact)).is_ok()
+    }
+
+    /// Backup the save file.
+    ///
+    /// Returns the path of the new backup, or `None` when it is a duplicate of an existing one.
+    fn backup<P: AsRef<Path>>(
+        &self,
+        save_path: P,
+        filename: &str,
+    ) -> Result<Option<PathBuf>, SaveError> {
+        let save_path = save_path.as_ref();
+
+        // The backup directory may have been removed while running, e.g. by a cleanup tool
+        let backup_dir = self.backup_dir();
+        let dry_run = self.options().dry_run;
+        if !dry_run && !self.store().is_dir(backup_dir) {
+            warn!(
+                "{} backup directory is missing, recreating it: {:?}",
+                self.name(),
+                backup_dir
+            );
+            self.store()
+                .create_dir_all(backup_dir)
+                .map_err(|_| SaveError::DirCreate(backup_dir.to_path_buf()))?;
+        }
+
+        if self.is_dupe_backup(save_path)? {
+            debug!("{} save backup de-duped: {:?}", self.name(), save_path);
+            self.options().events.emit(SteeveEvent::DedupSkipped {
+                side: self.side(),
+                path: save_path.to_path_buf(),
+            });
+            return Ok(None);
+        }
+
+        let mut backup_path = self.backup_dir().to_path_buf();
+        backup_path.push(self.backup_name(filename, SystemTime::now()));
+        if self.options().compress_backups {
+            backup_path
+                .as_mut_os_string()
+                .push(format!(".{COMPRESSED_EXTENSION}"));
+        }
+
+        if dry_run {
+            info!("Dry run: Steeve would back up the {} save", self.name());
+            debug!(
+                "Dry run backup {} save: {:?} -> {:?}",
+                self.name(),
+                save_path,
+                backup_path
+            );
+            self.options().events.emit(SteeveEvent::BackupCreated {
+                side: self.side(),
+                path: backup_path.clone(),
+                dry_run: true,
+            });
+            return Ok(Some(backup_path));
+        }
+
+        debug!(
+            "Backup {} save: {:?} -> {:?}",
+            self.name(),
+            save_path,
+            backup_path,
+        );
+        retry_transient(|| {
+            if self.options().compress_backups {
+                compressed_copy(self.store(), save_path, &backup_path)
+            } else {
+                timed_copy(self.store(), save_path, &backup_path).map(|(bytes, _)| bytes)
+            }
+        })?;
+        self.cache_backup_hash(&backup_path);
+        self.options().events.emit(SteeveEvent::BackupCreated {
+            side: self.side(),
+            path: backup_path.clone(),
+            dry_run: false,
+        });
+
+        // The new backup is safely written, so a failure to prune does not fail the sync
+        if let Err(err) = self.remove_old_backups() {
+            warn!("Unable to remove old {} backups: {:?}", self.name(), err);
+        }
+
+        Ok(Some(backup_path))
+    }
+
+    /// Backup the current save right away, regardless of modify times.
+    fn backup_now(&self) -> Result<BackupOutcome, SaveError> {
+        let Some((save_path, filename)) = self.locate_save_path() else {
+            return Ok(BackupOutcome::NoSave);
+        };
+
+        Ok(match self.backup(&save_path, &filename)? {
+            Some(backup_path) => BackupOutcome::Created(backup_path),
+            None => BackupOutcome::Duplicate,
+        })
+    }
+
+    /// Get the file name for a backup of `filename` made at `when`.
+    ///
+    /// Backups are named `{timestamp}_{side}_{filename}`, with `.gz` added when they are
+    /// compressed. Implementations that change the naming scheme must also override
+    /// [`SteeveSave::parse_backup_name`] to match.
+    fn backup_name(&self, filename: &str, when: SystemTime) -> String {
+        let timestamp = format_timestamp(self.options().backup_timestamp, when);
+
+        format!("{}_{}_{}", timestamp, self.name().to_lowercase(), filename)
+    }
+
+    /// Parse a backup file name created by [`SteeveSave::backup_name`].
+    ///
+    /// Returns `None` for files that are not backups.
+    fn parse_backup_name(&self, name: &str) -> Option<BackupName> {
+        parse_backup_name(name)
+    }
+
+    /// Check if a file in the backup directory is a backup of this side.
+    ///
+    /// Both sides may share a backup directory with
+    /// [`BackupLayout::Flat`](crate::BackupLayout::Flat), so backups made by the other side are
+    /// excluded. Legacy backups without a side are always included.
+    fn is_own_backup(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name() else {
+            return false;
+        };
+
+        match self.parse_backup_name(&name.to_string_lossy()) {
+            Some(BackupName {
+                side: Some(side), ..
+            }) => side.eq_ignore_ascii_case(self.name()),
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Check if the file is already backed up.
+    ///
+    /// Backup hashes are cached, so only the save is read on every check.
+    fn is_dupe_backup<P: AsRef<Path>>(&self, save_path: P) -> Result<bool, SaveError> {
+        let save_path = save_path.as_ref();
+        let save_len = self.store().stat(save_path)?.len;
+        let backups = self.own_backups().unwrap_or_default();
+
+        // Forget the hashes of pruned backups
+        let cache = &self.options().hash_cache;
+        cache.retain(|path| {
+            path.parent() != Some(self.backup_dir())
+                || !self.is_own_backup(path)
+                || backups.iter().any(|entry| entry.path == path)
+        });
+
+        // File comparison is done by hashing its contents, and only same-sized files can match.
+        // The size of a compressed backup says nothing about the save inside it.
+        let mut save_hash = None;
+        let mut is_dupe = false;
+        let candidates = backups
+            .iter()
+            .filter(|entry| entry.len == save_len || is_compressed(&entry.path));
+        for entry in candidates {
+            let save_hash = match &save_hash {
+                Some(save_hash) => save_hash,
+                None => save_hash.insert(self.hash(save_path)?),
+            };
+            let algo = self.options().hash_algo;
+            if cache
+                .hash(algo, entry, || self.hash(&entry.path))
+                .is_ok_and(|hash| hash == *save_hash)
+            {
+                is_dupe = true;
+                break;
+            }
+        }
+        self.save_hash_manifest();
+
+        Ok(is_dupe)
+    }
+
+    /// Remember the hash of a new backup, so it is never read for duplicate detection.
+    fn cache_backup_hash(&self, backup_path: &Path) {
+        let algo = self.options().hash_algo;
+        if let Ok(entry) = self.store().stat(backup_path) {
+            let _ = self
+                .options()
+                .hash_cache
+                .hash(algo, &entry, || self.hash(backup_path));
+        }
+        self.save_hash_manifest();
+    }
+
+    /// Write the backup hash manifest, if it changed.
+    fn save_hash_manifest(&self) {
+        if self.options().dry_run {
+            return;
+        }
+
+        let cache = &self.options().hash_cache;
+        if let Err(err) = cache.save(self.store(), self.backup_dir()) {
+            warn!("Unable to write {} backup manifest: {}", self.name(), err);
+        }
+    }
+
+    /// List the backups for this side, from oldest to newest.
+    ///
+    /// Backups are ordered by the time in their file name, which stays the same when the files
+    /// are copied around, restored, or touched by cloud sync or antivirus. The modify time only
+    /// breaks ties. Subdirectories are not searched, so the trash is never mistaken for backups.
+    fn own_backups(&self) -> std::io::Result<Vec<StoreEntry>> {
+        let mut entries = self.store().list(self.backup_dir())?;
+        entries.retain(|entry| self.is_own_backup(&entry.path));
+        entries.sort_by_cached_key(|entry| (self.backup_time(entry), entry.modified));
+
+        Ok(entries)
+    }
+
+    /// Get when a backup was made, from its file name, or its modify time when the name has no
+    /// timestamp.
+    fn backup_time(&self, entry: &StoreEntry) -> SystemTime {
+        entry
+            .path
+            .file_name()
+            .and_then(|name| self.parse_backup_name(&name.to_string_lossy()))
+            .map_or(entry.modified, |name| {
+                SystemTime::UNIX_EPOCH + Duration::from_secs(name.timestamp)
+            })
+    }
+
+    /// Get the paths of all backups for this side, from oldest to newest.
+    fn backup_paths(&self) -> Vec<PathBuf> {
+        self.own_backups()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect()
+    }
+
+    /// Count the backups and add up their sizes.
+    fn backup_stats(&self) -> Result<BackupStats, SaveError> {
+        let backups = self.own_backups()?;
+
+        // Backups are sorted by the time in their names, which is also what retention uses
+        Ok(BackupStats {
+            count: backups.len(),
+            total_bytes: backups.iter().map(|entry| entry.len).sum(),
+            oldest: backups.first().map(|entry| self.backup_time(entry)),
+            newest: backups.last().map(|entry| self.backup_time(entry)),
+        })
+    }
+
+    /// List the backups for this side, from oldest to newest by the timestamp in their names.
+    fn list_backups(&self) -> Result<Vec<BackupEntry>, SaveError> {
+        let mut backups = self
+            .own_backups()?
+            .into_iter()
+            .filter_map(|entry| {
+                let name = entry.path.file_name()?.to_string_lossy();
+                let name = self.parse_backup_name(&name)?;
+
+                Some(BackupEntry {
+                    timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(name.timestamp),
+                    filename: name.filename,
+                    size: entry.len,
+                    compressed: is_compressed(&entry.path),
+                    path: entry.path,
+                })
+            })
+            .collect::<Vec<_>>();
+        backups.sort_by_key(|backup| backup.timestamp);
+
+        Ok(backups)
+    }
+
+    /// Get the newest backup for this side, by the timestamp in its file name.
+    fn latest_backup(&self) -> Result<Option<BackupEntry>, SaveError> {
+        Ok(self.list_backups()?.pop())
+    }
+
+    /// Restore the newest backup over the current save.
+    ///
+    /// See [`SteeveSave::restore`]. Returns the restored backup, or `None` when there are no
+    /// backups.
+    fn restore_latest(&self) -> Result<Option<BackupEntry>, SaveError> {
+        let Some(backup) = self.latest_backup()? else {
+            return Ok(None);
+        };
+        self.restore(&backup)?;
+
+        Ok(Some(backup))
+    }
+
+    /// Restore a backup for this side over the current save.
+    ///
+    /// The current save is backed up first, unless it is already backed up. A new safety backup
+    /// becomes the newest backup, so restoring the latest backup undoes the restore.
+    fn restore(&self, backup: &BackupEntry) -> Result<(), SaveError> {
+        let path = &backup.path;
+        self.check_backup(path)?;
+        let (save_path, filename) = self.locate_save_path().ok_or(SaveError::NoSave)?;
+
+        // Read the backup before the safety backup, which may prune it
+        let mut bytes = Vec::new();
+        let mut reader = self.store().open(path)?;
+        if is_compressed(path) {
+            GzDecoder::new(reader)
+                .read_to_end(&mut bytes)
+                .map_err(|_| SaveError::NotASave(path.clone()))?;
+        } else {
+            reader.read_to_end(&mut bytes)?;
+        }
+        if !validate_bytes(&bytes) {
+            return Err(SaveError::NotASave(path.clone()));
         }
+        self.backup(&save_path, &filename)?;
+
+        info!("Restoring {} backup: {:?}", self.name(), path);
+        self.atomic_write(&save_path, &bytes)?;
+        self.finish_write(&save_path)?;
+
+        // A restored save takes precedence over changes on the other side, even conflicting ones
+        self.options().hash_cache.forget_synced(&save_path);
+        self.save_hash_manifest();
+
+        Ok(())
+    }
+
+    /// Read a summary of the current save, e.g. to check that it is the right player's save.
+    fn read_info(&self) -> Result<SaveInfo, SaveError> {
+        let (path, _) = self.locate_save_path().ok_or(SaveError::NoSave)?;
+        let mut bytes = Vec::new();
+        self.store().open(&path)?.read_to_end(&mut bytes)?;
+
+        SaveInfo::parse(&bytes).ok_or(SaveError::NotASave(path))
+    }
+
+    /// Delete a backup for this side, or move it to the trash when it is enabled.
+    fn delete_backup(&self, backup: &BackupEntry) -> Result<(), SaveError> {
+        self.check_backup(&backup.path)?;
+        info!("Deleting {} backup: {:?}", self.name(), backup.path);
+
+        self.discard_backup(&backup.path)
+    }
+
+    /// Show the backup directory in the file manager.
+    fn open_backup_dir(&self) -> Result<(), SaveError> {
+        let program = if cfg!(windows) {
+            "explorer"
+        } else if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+
+        // The file manager is not waited for, since this is called from the tray and holds the
+        // save's lock. Explorer exits with an error even when it opens the directory anyway, so
+        // only a failure to start it is reported.
+        std::process::Command::new(program)
+            .arg(self.backup_dir())
+            .spawn()?;
+
+        Ok(())
+    }
+
+    /// Check that a path is one of this side's backups, directly in the backup directory.
+    fn check_backup(&self, path: &Path) -> Result<(), SaveError> {
+        if path.parent() != Some(self.backup_dir()) || !self.is_own_backup(path) {
+            return Err(SaveError::NotABackup(path.to_path_buf()));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a backup, or move it to the trash when it is enabled.
+    fn discard_backup(&self, path: &Path) -> Result<(), SaveError> {
+        match &self.options().trash {
+            Some(trash) => trash.put(path),
+            None => {
+                debug!("Removing {} backup: {:?}", self.name(), path);
+                Ok(self.store().remove(path)?)
+            }
+        }
+    }
+
+    /// Remove the oldest backups over the cap, or move them to the trash when it is enabled.
+    ///
+    /// This runs after a new backup is written, and keeps exactly `max_backups`. Only this
+    /// side's backups directly in the backup directory count; conflict copies, other files, and
+    /// the trash are left alone.
+    fn remove_old_backups(&self) -> Result<(), SaveError> {
+        let files = self.own_backups().unwrap_or_default();
+
+        let excess = files.len().saturating_sub(self.options().max_backups);
+        for entry in files.iter().take(excess) {
+            self.discard_backup(&entry.path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Run an I/O operation, retrying with backoff while it fails with transient errors.
+fn retry_transient<T>(mut op: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+    for delay in RETRY_DELAYS {
+        match op() {
+            Err(err) if is_transient(&err) => {
+                debug!("Transient I/O error, retrying in {:?}: {}", delay, err);
+                std::thread::sleep(delay);
+            }
+            result => return result,
+        }
+    }
+
+    op()
+}
+
+/// Check if an I/O error is likely to clear up on its own, like a file that is briefly locked.
+fn is_transient(err: &std::io::Error) -> bool {
+    // Windows sharing and lock violations
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    const ERROR_LOCK_VIOLATION: i32 = 33;
+
+    matches!(
+        err.kind(),
+        ErrorKind::PermissionDenied | ErrorKind::WouldBlock
+    ) || (cfg!(windows)
+        && matches!(
+            err.raw_os_error(),
+            Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+        ))
+}
+
+/// Find all plausible Steam save directories, one for each Steam library with the game installed.
+///
+/// The first directory is the one used by default.
+pub fn discover_steam_save_dirs() -> Vec<PathBuf> {
+    SteamSave::discover_save_dirs().unwrap_or_default()
+}
+
+/// Find all plausible Xbox save directories, one for each save container.
+///
+/// The first directory is the one used by default.
+pub fn discover_xbox_save_dirs() -> Vec<PathBuf> {
+    XboxSave::discover_save_dirs().unwrap_or_default()
+}
+
+/// Get the `steamapps` directories of the libraries listed in a `libraryfolders.vdf` file.
+///
+/// Both the legacy format, with a path for each numbered key, and the modern format, with a
+/// `path` key in each numbered section, are accepted.
+fn vdf_library_paths(vdf: &str) -> Vec<PathBuf> {
+    vdf.lines()
+        .filter_map(|line| {
+            let mut tokens = line.split('"').skip(1).step_by(2);
+            let (key, value) = (tokens.next()?, tokens.next()?);
+            let is_path =
+                key == "path" || (key.parse::<u32>().is_ok() && value.contains(['/', '\\']));
+
+            is_path.then(|| PathBuf::from(value.replace("\\\\", "\\")).join("steamapps"))
+        })
+        .collect()
+}
+
+/// Get the install directory of the game in a library, from its app manifest.
+///
+/// Falls back to the usual directory name when the manifest is missing or has no `installdir`.
+fn steam_install_dir(library: &Path) -> String {
+    let manifest = library.join(format!("appmanifest_{DRG_APP_ID}.acf"));
+
+    std::fs::read_to_string(manifest)
+        .ok()
+        .and_then(|manifest| {
+            manifest.lines().find_map(|line| {
+                let mut tokens = line.split('"').skip(1).step_by(2);
+                match (tokens.next(), tokens.next()) {
+                    (Some(key), Some(value)) if key.eq_ignore_ascii_case("installdir") => {
+                        Some(value.to_string())
+                    }
+                    _ => None,
+                }
+            })
+        })
+        .unwrap_or_else(|| STEAM_INSTALL_DIR.to_string())
+}
+
+/// Find the save directories in the game's Proton prefix in each library.
+///
+/// Proton runs the game in a Windows prefix under `compatdata`, where it keeps its saves instead
+/// of in the install directory.
+#[cfg(target_os = "linux")]
+fn proton_save_dirs(libraries: &[PathBuf]) -> Vec<PathBuf> {
+    libraries
+        .iter()
+        .map(|library| {
+            let mut save_dir = library.join("compatdata").join(DRG_APP_ID.to_string());
+            save_dir.extend(PROTON_SAVE_DIR);
+            save_dir
+        })
+        .filter(|save_dir| save_dir.is_dir())
+        .collect()
+}
+
+/// Get the subdirectories of a directory, ignoring errors.
+fn read_dirs(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|result| result.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+}
+
+/// Copy a file, returning the number of bytes copied and how long it took.
+fn timed_copy(store: &dyn SaveStore, from: &Path, to: &Path) -> std::io::Result<(u64, Duration)> {
+    let start = Instant::now();
+    let bytes = store.copy(from, to)?;
+    let duration = start.elapsed();
+
+    // Avoid dividing by zero on very fast copies
+    let mib_per_sec = bytes as f64 / duration.as_secs_f64().max(1e-6) / (1024.0 * 1024.0);
+    debug!(
+        "Copied {} bytes in {:?} ({:.1} MiB/s)",
+        bytes, duration, mib_per_sec,
+    );
+
+    Ok((bytes, duration))
+}
+
+/// Move a file with a rename, or with a copy and remove when it crosses file systems.
+fn move_file(store: &dyn SaveStore, from: &Path, to: &Path) -> std::io::Result<()> {
+    match store.rename(from, to) {
+        Err(err) if err.kind() == ErrorKind::CrossesDevices => {
+            store.copy(from, to)?;
+            store.remove(from)
+        }
+        result => result,
+    }
+}
+
+/// Get the file name of a path if it looks like the current save file for `S`.
+fn save_name<S: SteeveSave + ?Sized>(path: &Path) -> Option<String> {
+    let filename = path.file_name()?.to_string_lossy();
+
+    S::is_save_name(&filename).then(|| filename.to_string())
+}
+
+/// Extension of Steam save files, matched without regard to case.
+const SAVE_EXTENSION: &str = ".sav";
+
+/// Get the slot name of a save file name: the file name without the `.sav` extension, trailing
+/// whitespace, or the ` (1)` suffix that file managers and cloud sync services add to copies.
+fn slot_name(filename: &str) -> &str {
+    let name = filename.trim_end();
+    let name = strip_save_extension(name).unwrap_or(name);
+
+    strip_copy_number(name.trim_end())
+}
+
+/// Get the name of the save that a save file name may be a copy of, by removing trailing
+/// whitespace and a copy number like ` (1)`, and keeping the `.sav` extension as it is.
+fn exact_save_name(filename: &str) -> String {
+    let name = filename.trim_end();
+    let extension = strip_save_extension(name).map_or("", |stem| &name[stem.len()..]);
+
+    format!("{}{extension}", slot_name(name))
+}
+
+/// Strip the `.sav` extension from a file name, in any case.
+fn strip_save_extension(filename: &str) -> Option<&str> {
+    let split = filename.len().checked_sub(SAVE_EXTENSION.len())?;
+    let (name, extension) = (filename.get(..split)?, filename.get(split..)?);
+
+    extension
+        .eq_ignore_ascii_case(SAVE_EXTENSION)
+        .then_some(name)
+}
+
+/// Strip a copy number like ` (1)` from the end of a name.
+fn strip_copy_number(name: &str) -> &str {
+    let stripped = name.strip_suffix(')').and_then(|rest| {
+        let (stem, number) = rest.rsplit_once('(')?;
+        let is_number = !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit());
+
+        is_number.then(|| stem.trim_end())
+    });
+
+    stripped.unwrap_or(name)
+}
+
+/// Get the slot on `side` that the other side's `slot` is paired with.
+fn paired_slot<'a>(pairs: &'a [(String, String)], side: Side, slot: &str) -> Option<&'a str> {
+    pairs.iter().find_map(|(steam, xbox)| match side {
+        Side::Steam => xbox.eq_ignore_ascii_case(slot).then_some(steam.as_str()),
+        Side::Xbox => steam.eq_ignore_ascii_case(slot).then_some(xbox.as_str()),
+    })
+}
+
+/// Check if the `slot` on `side` is paired with a slot on the other side.
+fn is_paired(pairs: &[(String, String)], side: Side, slot: &str) -> bool {
+    pairs.iter().any(|(steam, xbox)| match side {
+        Side::Steam => steam.eq_ignore_ascii_case(slot),
+        Side::Xbox => xbox.eq_ignore_ascii_case(slot),
+    })
+}
+
+/// Check if a backup is compressed, by its extension.
+fn is_compressed(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == COMPRESSED_EXTENSION)
+}
+
+/// Copy a file into a new gzip-compressed file, returning the number of bytes written.
+fn compressed_copy(store: &dyn SaveStore, from: &Path, to: &Path) -> std::io::Result<u64> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    std::io::copy(&mut store.open(from)?, &mut encoder)?;
+    let bytes = encoder.finish()?;
+    store.write(to, &bytes)?;
+
+    Ok(bytes.len() as u64)
+}
+
+/// Blame a failed copy on the source when it can no longer be read, or on the destination.
+fn copy_error(store: &dyn SaveStore, from: &Path, err: std::io::Error) -> SaveError {
+    match store.open(from) {
+        Ok(_) => SaveError::Io(err),
+        Err(_) => SaveError::SourceUnavailable(from.to_path_buf(), err),
+    }
+}
+
+/// Check that the file looks like a complete save, see [`validate_bytes`].
+pub(crate) fn validate_save(store: &dyn SaveStore, path: &Path) -> Result<(), SaveError> {
+    let mut head = Vec::new();
+    store
+        .open(path)?
+        .take(MIN_SAVE_SIZE)
+        .read_to_end(&mut head)?;
+    if !validate_bytes(&head) {
+        return Err(SaveError::NotASave(path.to_path_buf()));
+    }
+
+    Ok(())
+}
+
+/// Check that the bytes look like a complete save: big enough, and starting with a GVAS header.
+///
+/// Only the first [`MIN_SAVE_SIZE`] bytes are needed.
+pub(crate) fn validate_bytes(bytes: &[u8]) -> bool {
+    bytes.len() as u64 >= MIN_SAVE_SIZE && bytes.starts_with(GVAS_MAGIC)
+}
+
+/// Resolve symbolic links (and junctions) in a directory path.
+///
+/// Falls back to the original path if it cannot be resolved, e.g. when it does not exist. On
+/// Windows, the `\\?\` prefix is left off when the path does not need it, so the path still
+/// compares equal to the paths from Steam and the Xbox app.
+pub(crate) fn resolve_links(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Format `when` for a backup file name.
+///
+/// Falls back to Unix seconds when the time cannot be formatted as ISO 8601, e.g. past the year
+/// 9999.
+fn format_timestamp(format: BackupTimestamp, when: SystemTime) -> String {
+    let unix = || {
+        when.duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            .to_string()
+    };
+
+    match format {
+        BackupTimestamp::Unix => unix(),
+        BackupTimestamp::Iso8601 => OffsetDateTime::from(when)
+            .format(ISO_TIMESTAMP)
+            .unwrap_or_else(|_| unix()),
+    }
+}
+
+/// The parts of a backup file name.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct BackupName {
+    /// Unix timestamp (seconds) when the backup was made.
+    pub(crate) timestamp: u64,
+
+    /// Lowercase name of the side that was backed up, absent in the legacy naming scheme.
+    pub(crate) side: Option<String>,
+
+    /// Original save file name.
+    pub(crate) filename: String,
+}
+
+/// Parse a backup file name.
+///
+/// Backups are named `{timestamp}_{side}_{filename}`. The legacy `{timestamp}_{filename}` scheme
+/// is also accepted. Timestamps are either Unix seconds or ISO 8601, see [`BackupTimestamp`]. The
+/// `.gz` extension of compressed backups is not part of the save file name.
+pub(crate) fn parse_backup_name(name: &str) -> Option<BackupName> {
+    let name = name
+        .strip_suffix(&format!(".{COMPRESSED_EXTENSION}"))
+        .unwrap_or(name);
+    let (timestamp, rest) = name.split_once('_')?;
+    let timestamp = match timestamp.parse() {
+        Ok(timestamp) => timestamp,
+        Err(_) => PrimitiveDateTime::parse(timestamp, ISO_TIMESTAMP)
+            .ok()?
+            .assume_utc()
+            .unix_timestamp()
+            .try_into()
+            .ok()?,
+    };
+
+    let (side, filename) = match rest.split_once('_') {
+        Some((side @ ("steam" | "xbox"), filename)) => (Some(side.to_string()), filename),
+        _ => (None, rest),
+    };
+    if filename.is_empty() {
+        return None;
+    }
+
+    Some(BackupName {
+        timestamp,
+        side,
+        filename: filename.to_string(),
+    })
+}
+
+impl SteeveSave for SteamSave {
+    fn name(&self) -> &str {
+        "Steam"
+    }
+
+    fn side(&self) -> Side {
+        Side::Steam
+    }
+
+    fn options(&self) -> &SaveOptions {
+        &self.options
+    }
+
+    fn backup_dir(&self) -> &Path {
+        &self.backup_dir
+    }
+
+    fn save_dir(&self) -> &Path {
+        &self.save_dir
+    }
+
+    fn is_save_name(filename: &str) -> bool {
+        strip_save_extension(filename.trim_end()).is_some()
+            && slot_name(filename).ends_with("_Player")
+    }
+}
+
+impl SteeveSave for XboxSave {
+    fn name(&self) -> &str {
+        "Xbox"
+    }
+
+    fn side(&self) -> Side {
+        Side::Xbox
+    }
+
+    fn options(&self) -> &SaveOptions {
+        &self.options
+    }
+
+    fn backup_dir(&self) -> &Path {
+        &self.backup_dir
+    }
+
+    fn save_dir(&self) -> &Path {
+        &self.save_dir
+    }
+
+    fn is_save_name(filename: &str) -> bool {
+        filename.len() == 32 && filename.chars().all(|ch| ch.is_ascii_hexdigit())
+    }
+
+    fn indexed_saves(&self) -> Option<Vec<PathBuf>> {
+        match wgs::locate_save_blobs(&self.save_dir) {
+            Ok(blobs) => blobs,
+            Err(err) => {
+                warn!("{err}, looking for the Xbox save without it");
+                None
+            }
+        }
+    }
+
+    fn finish_write(&self, path: &Path) -> Result<(), SaveError> {
+        wgs::update_blob(&self.save_dir, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::HASH_MANIFEST;
+    use crate::store::InMemoryStore;
+    use crate::testing::{fake_save, write_file, STEAM_NAME};
+    use tempfile::TempDir;
+
+    /// Create a Steam save with its save and backup directories in `dir`.
+    fn steam_save(dir: &TempDir) -> SteamSave {
+        let save_dir = dir.path().join("save");
+        let backup_dir = dir.path().join("backup");
+        std::fs::create_dir_all(&save_dir).unwrap();
+        std::fs::create_dir_all(&backup_dir).unwrap();
+
+        SteamSave {
+            options: SaveOptions::for_test(),
+            backup_dir,
+            save_dir,
+        }
+    }
+
+    /// Copy a new source file over an older, valid save.
+    fn copy_source(source: &[u8]) -> (Result<SyncOutcome, SaveError>, Vec<u8>) {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+
+        let to = steam_save.save_dir.join(STEAM_NAME);
+        let good = fake_save(b"good");
+        write_file(&to, &good, Duration::from_secs(60));
+
+        let from = dir.path().join(STEAM_NAME);
+        write_file(&from, source, Duration::ZERO);
+
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+
+        (result, std::fs::read(&to).unwrap())
+    }
+
+    #[test]
+    fn copy_save_refuses_empty_source() {
+        let (result, dest) = copy_source(b"");
+
+        assert!(matches!(result, Err(SaveError::NotASave(_))));
+        assert_eq!(dest, fake_save(b"good"));
+    }
+
+    #[test]
+    fn copy_save_refuses_one_byte_source() {
+        let (result, dest) = copy_source(b"G");
+
+        assert!(matches!(result, Err(SaveError::NotASave(_))));
+        assert_eq!(dest, fake_save(b"good"));
+    }
+
+    #[test]
+    fn copy_save_refuses_source_without_header() {
+        let (result, dest) = copy_source(&[0; MIN_SAVE_SIZE as usize]);
+
+        assert!(matches!(result, Err(SaveError::NotASave(_))));
+        assert_eq!(dest, fake_save(b"good"));
+    }
+
+    #[test]
+    fn copy_save_accepts_valid_source() {
+        let (result, dest) = copy_source(&fake_save(b"new"));
+
+        assert!(matches!(result, Ok(SyncOutcome::Copied { .. })));
+        assert_eq!(dest, fake_save(b"new"));
+    }
+
+    #[test]
+    fn copy_save_skips_identical_source() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+
+        let to = steam_save.save_dir.join(STEAM_NAME);
+        write_file(&to, &fake_save(b"same"), Duration::from_secs(60));
+        let from = dir.path().join(STEAM_NAME);
+        write_file(&from, &fake_save(b"same"), Duration::ZERO);
+
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Ok(SyncOutcome::Identical)));
+        assert!(steam_save.backup_paths().is_empty());
+    }
+
+    #[test]
+    fn copy_save_waits_for_source_to_settle() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        steam_save.options.settle_time = Duration::from_millis(200);
+
+        let to = steam_save.save_dir.join(STEAM_NAME);
+        write_file(&to, &fake_save(b"old"), Duration::from_secs(60));
+        let from = dir.path().join(STEAM_NAME);
+        write_file(&from, &fake_save(b"new"), Duration::ZERO);
+
+        // The game is still writing the source
+        let writer = {
+            let from = from.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                write_file(&from, &fake_save(b"newer"), Duration::ZERO);
+            })
+        };
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        writer.join().unwrap();
+        assert!(matches!(result, Err(SaveError::StillWriting(path)) if path == from));
+        assert_eq!(std::fs::read(&to).unwrap(), fake_save(b"old"));
+        assert!(steam_save.backup_paths().is_empty());
+
+        // Once it settles, the copy goes ahead
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Ok(SyncOutcome::Copied { .. })));
+        assert_eq!(std::fs::read(&to).unwrap(), fake_save(b"newer"));
+    }
+
+    #[test]
+    fn dry_run_writes_nothing() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        steam_save.options.dry_run = true;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        *steam_save.options.events.handler.lock() = Some(Arc::new(move |event| {
+            let _ = sender.send(event);
+        }));
+
+        let to = steam_save.save_dir.join(STEAM_NAME);
+        write_file(&to, &fake_save(b"old"), Duration::from_secs(60));
+        let from = dir.path().join(STEAM_NAME);
+        write_file(&from, &fake_save(b"new"), Duration::ZERO);
+
+        // Missing directories are not created
+        let missing = dir.path().join("missing");
+        let options = steam_save.options.clone();
+        SteamSave::new(options, steam_save.save_dir.clone(), missing.clone()).unwrap();
+        assert!(!missing.exists());
+
+        let result = steam_save.copy_save(&from, |_| panic!("The pre-sync hook was called"));
+        let bytes = fake_save(b"new").len() as u64;
+        assert!(matches!(result, Ok(SyncOutcome::DryRun { bytes: b }) if b == bytes));
+        assert_eq!(std::fs::read(&to).unwrap(), fake_save(b"old"));
+        assert!(steam_save.backup_paths().is_empty());
+        assert!(!steam_save.backup_dir.join(HASH_MANIFEST).exists());
+
+        // The plan is still reported
+        let events = receiver.try_iter().collect::<Vec<_>>();
+        assert!(matches!(
+            events.as_slice(),
+            [
+                SteeveEvent::BackupCreated {
+                    side: Side::Steam,
+                    dry_run: true,
+                    ..
+                },
+                SteeveEvent::SaveCopied {
+                    direction: Direction::XboxToSteam,
+                    dry_run: true,
+                    ..
+                },
+            ]
+        ));
+
+        // The modify time check still runs
+        write_file(&to, &fake_save(b"old"), Duration::ZERO);
+        write_file(&from, &fake_save(b"new"), Duration::from_secs(60));
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Err(SaveError::ModifyTime)));
+    }
+
+    #[test]
+    fn verified_copy_rolls_back_on_mismatch() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        steam_save.options.verify_syncs = true;
+
+        let to = steam_save.save_dir.join(STEAM_NAME);
+        write_file(&to, &fake_save(b"old"), Duration::from_secs(60));
+        let from = dir.path().join(STEAM_NAME);
+        write_file(&from, &fake_save(b"new"), Duration::ZERO);
+
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(matches!(
+            result,
+            Ok(SyncOutcome::Copied { verified: true, .. }),
+        ));
+
+        // Simulate a corrupted write
+        let then = SystemTime::now() - Duration::from_secs(60);
+        write_file(&to, &fake_save(b"bad"), Duration::ZERO);
+        let expected = steam_save.hash(&from).unwrap();
+        assert!(matches!(
+            steam_save.verify_copy(&to, &expected, &Original::Save(fake_save(b"old"), then)),
+            Err(SaveError::SyncVerify(_)),
+        ));
+        assert_eq!(std::fs::read(&to).unwrap(), fake_save(b"old"));
+        assert_eq!(to.metadata().unwrap().modified().unwrap(), then);
+    }
+
+    #[test]
+    fn verified_copy_over_stand_in_rolls_back_to_no_save() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        steam_save.options.verify_syncs = true;
+
+        // Only a stand-in copy exists, so the copy is written to the exact name
+        let copy_path = steam_save
+            .save_dir
+            .join(STEAM_NAME.replace(".sav", " (1).sav"));
+        write_file(&copy_path, &fake_save(b"copy"), Duration::from_secs(60));
+        let to = steam_save.save_dir.join(STEAM_NAME);
+        assert!(matches!(
+            steam_save.original_save(&to),
+            Ok(Original::Missing)
+        ));
+        let from = dir.path().join(STEAM_NAME);
+        write_file(&from, &fake_save(b"new"), Duration::ZERO);
+
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(matches!(
+            result,
+            Ok(SyncOutcome::Copied { verified: true, .. }),
+        ));
+
+        // A corrupted write is removed, and the stand-in is left alone
+        write_file(&to, &fake_save(b"bad"), Duration::ZERO);
+        let expected = steam_save.hash(&from).unwrap();
+        assert!(matches!(
+            steam_save.verify_copy(&to, &expected, &Original::Missing),
+            Err(SaveError::SyncVerify(_)),
+        ));
+        assert!(!to.exists());
+        assert_eq!(std::fs::read(&copy_path).unwrap(), fake_save(b"copy"));
+    }
+
+    /// Create a plausible save file with the given save game format and engine build.
+    fn versioned_save(save_game: u32, build: u32) -> Vec<u8> {
+        let mut bytes = GVAS_MAGIC.to_vec();
+        bytes.extend_from_slice(&save_game.to_le_bytes());
+        bytes.extend_from_slice(&522_u32.to_le_bytes());
+        if save_game >= 3 {
+            bytes.extend_from_slice(&1009_u32.to_le_bytes());
+        }
+        for part in [4_u16, 27, 2] {
+            bytes.extend_from_slice(&part.to_le_bytes());
+        }
+        bytes.extend_from_slice(&build.to_le_bytes());
+        bytes.resize(MIN_SAVE_SIZE as usize, 0);
+        bytes
+    }
+
+    #[test]
+    fn parse_save_versions() {
+        let version = SaveVersion::parse(&versioned_save(2, 18319896)).unwrap();
+        assert_eq!(
+            version,
+            SaveVersion {
+                save_game: 2,
+                package: 522,
+                package_ue5: 0,
+                engine: (4, 27, 2),
+                build: 18319896,
+            },
+        );
+        assert_eq!(version.to_string(), "4.27.2-18319896");
+
+        let version = SaveVersion::parse(&versioned_save(3, 1)).unwrap();
+        assert_eq!((version.package_ue5, version.engine), (1009, (4, 27, 2)));
+
+        assert_eq!(SaveVersion::parse(b"GVAS\x02"), None);
+        assert_eq!(SaveVersion::parse(&[0; MIN_SAVE_SIZE as usize]), None);
+    }
+
+    #[test]
+    fn copy_save_refuses_newer_version() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+
+        let to = steam_save.save_dir.join(STEAM_NAME);
+        let old = versioned_save(2, 100);
+        write_file(&to, &old, Duration::from_secs(60));
+        let from = dir.path().join(STEAM_NAME);
+        write_file(&from, &versioned_save(3, 100), Duration::ZERO);
+
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Err(SaveError::VersionMismatch { .. })));
+        assert_eq!(std::fs::read(&to).unwrap(), old);
+
+        // A newer engine build with the same format is synced
+        let newer_build = dir.path().join("build").join(STEAM_NAME);
+        std::fs::create_dir(dir.path().join("build")).unwrap();
+        write_file(&newer_build, &versioned_save(2, 200), Duration::ZERO);
+        let result = steam_save.copy_save(&newer_build, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Ok(SyncOutcome::Copied { .. })));
+        write_file(&to, &old, Duration::from_secs(60));
+
+        // Users on matching versions can opt out
+        steam_save.options.check_versions = false;
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Ok(SyncOutcome::Copied { .. })));
+    }
+
+    #[test]
+    fn parse_unix_backup_names() {
+        let expected = BackupName {
+            timestamp: 1704207845,
+            side: Some("steam".to_string()),
+            filename: STEAM_NAME.to_string(),
+        };
+        let name = format!("1704207845_steam_{STEAM_NAME}");
+        assert_eq!(parse_backup_name(&name), Some(expected));
+
+        // Legacy names without a side
+        let expected = BackupName {
+            timestamp: 1704207845,
+            side: None,
+            filename: STEAM_NAME.to_string(),
+        };
+        let name = format!("1704207845_{STEAM_NAME}");
+        assert_eq!(parse_backup_name(&name), Some(expected));
+    }
+
+    #[test]
+    fn parse_iso_backup_names() {
+        let expected = BackupName {
+            timestamp: 1704207845,
+            side: Some("xbox".to_string()),
+            filename: "0123456789ABCDEF0123456789ABCDEF".to_string(),
+        };
+        let name = "2024-01-02T15-04-05Z_xbox_0123456789ABCDEF0123456789ABCDEF";
+        assert_eq!(parse_backup_name(name), Some(expected));
+    }
+
+    #[test]
+    fn parse_invalid_backup_names() {
+        assert_eq!(parse_backup_name("Player.sav"), None);
+        assert_eq!(parse_backup_name("1704207845_"), None);
+        assert_eq!(parse_backup_name("2024-01-02T15:04:05Z_foo"), None);
+    }
+
+    #[test]
+    fn backup_names_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        let when = SystemTime::UNIX_EPOCH + Duration::from_secs(1704207845);
+
+        for backup_timestamp in [BackupTimestamp::Unix, BackupTimestamp::Iso8601] {
+            steam_save.options.backup_timestamp = backup_timestamp;
+            let name = steam_save.backup_name(STEAM_NAME, when);
+            let parsed = steam_save.parse_backup_name(&name).unwrap();
+
+            assert_eq!(parsed.timestamp, 1704207845);
+            assert_eq!(parsed.filename, STEAM_NAME);
+        }
+    }
+
+    #[test]
+    fn remove_old_backups_ignores_other_side() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        let backup_dir = steam_save.backup_dir().to_path_buf();
+        let xbox_name = "0123456789ABCDEF0123456789ABCDEF";
+
+        // A flat layout, with Xbox backups and a trash directory alongside the Steam backups
+        for age in 1..=5 {
+            let path = backup_dir.join(format!("{age}_steam_{STEAM_NAME}"));
+            write_file(&path, b"steam", Duration::from_secs(age * 60));
+            let path = backup_dir.join(format!("{age}_xbox_{xbox_name}"));
+            write_file(&path, b"xbox", Duration::from_secs(age * 60));
+        }
+        std::fs::create_dir(backup_dir.join(TRASH_DIR)).unwrap();
+        let path = backup_dir
+            .join(TRASH_DIR)
+            .join(format!("9_steam_{STEAM_NAME}"));
+        write_file(&path, b"trash", Duration::from_secs(60 * 60));
+
+        steam_save.remove_old_backups().unwrap();
+
+        let count = |side: &str| {
+            FsStore::default()
+                .list(&backup_dir)
+                .unwrap()
+                .iter()
+                .filter_map(|entry| parse_backup_name(&entry.path.file_name()?.to_string_lossy()))
+                .filter(|name| name.side.as_deref() == Some(side))
+                .count()
+        };
+        assert_eq!(count("steam"), 3);
+        assert_eq!(count("xbox"), 5);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn backups_never_exceed_max_backups() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        let backup_dir = steam_save.backup_dir().to_path_buf();
+        let max_backups = steam_save.options().max_backups;
+
+        // Files that are not this side's backups are never counted or removed
+        let conflict_copy = backup_dir.join(format!("{CONFLICT_PREFIX}1_{STEAM_NAME}"));
+        write_file(&conflict_copy, b"conflict", Duration::from_secs(60 * 60));
+        let notes = backup_dir.join("notes.txt");
+        write_file(&notes, b"notes", Duration::from_secs(60 * 60));
+
+        for index in 0..max_backups * 2 {
+            // Saves keep their modify time when copied, so backups can look older than they are
+            let body = format!("save {index}");
+            write_file(
+                &save_path,
+                &fake_save(body.as_bytes()),
+                Duration::from_secs(60 * 60),
+            );
+            steam_save.backup(&save_path, STEAM_NAME).unwrap();
+
+            let backups = steam_save.list_backups().unwrap();
+            assert!(backups.len() <= max_backups);
+            let newest = backups
+                .iter()
+                .max_by_key(|backup| backup.timestamp)
+                .unwrap();
+            assert_eq!(
+                std::fs::read(&newest.path).unwrap(),
+                fake_save(body.as_bytes())
+            );
+        }
+        assert!(conflict_copy.exists());
+        assert!(notes.exists());
+    }
+
+    #[test]
+    fn old_backups_are_ordered_by_name() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        let backup_dir = steam_save.backup_dir().to_path_buf();
+
+        // The oldest backups by name were touched most recently, e.g. by a cloud sync client
+        for timestamp in 1..=5u64 {
+            let path = backup_dir.join(format!("{timestamp}_steam_{STEAM_NAME}"));
+            let body = format!("backup {timestamp}");
+            write_file(&path, body.as_bytes(), Duration::from_secs(timestamp * 60));
+        }
+
+        steam_save.remove_old_backups().unwrap();
+
+        let timestamps = steam_save
+            .list_backups()
+            .unwrap()
+            .iter()
+            .map(|backup| {
+                backup
+                    .timestamp
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps, [3, 4, 5].map(Duration::from_secs));
+    }
+
+    #[test]
+    fn dupe_backups_detected_with_each_hash() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        let backup_path = steam_save
+            .backup_dir()
+            .join(format!("1_steam_{STEAM_NAME}"));
+        write_file(&backup_path, &fake_save(b"backup"), Duration::ZERO);
+
+        for hash_algo in [HashAlgo::Blake3, HashAlgo::Sha256, HashAlgo::XxHash] {
+            steam_save.options.hash_algo = hash_algo;
+
+            write_file(&save_path, &fake_save(b"backup"), Duration::ZERO);
+            assert!(
+                steam_save.is_dupe_backup(&save_path).unwrap(),
+                "{hash_algo:?}"
+            );
+
+            write_file(&save_path, &fake_save(b"changed"), Duration::ZERO);
+            assert!(
+                !steam_save.is_dupe_backup(&save_path).unwrap(),
+                "{hash_algo:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn dupe_check_caches_backup_hashes() {
+        let store = Arc::new(InMemoryStore::default());
+        let (steam_save, _) = in_memory_saves(&store);
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        let backup_path = steam_save
+            .backup_dir()
+            .join(format!("1_steam_{STEAM_NAME}"));
+
+        let then = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        store.insert(&backup_path, &fake_save(b"one"), then);
+        store.insert(&save_path, &fake_save(b"two"), then);
+        assert!(!steam_save.is_dupe_backup(&save_path).unwrap());
+
+        // The backup is not read again while its size and modify time are unchanged
+        store.insert(&backup_path, &fake_save(b"two"), then);
+        assert!(!steam_save.is_dupe_backup(&save_path).unwrap());
+
+        store.insert(&backup_path, &fake_save(b"two"), SystemTime::now());
+        assert!(steam_save.is_dupe_backup(&save_path).unwrap());
+    }
+
+    #[test]
+    fn backup_hashes_survive_restarts() {
+        let store = Arc::new(InMemoryStore::default());
+        let (mut steam_save, _) = in_memory_saves(&store);
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        let backup_dir = steam_save.backup_dir().to_path_buf();
+
+        store.insert(&save_path, &fake_save(b"one"), SystemTime::now());
+        let BackupOutcome::Created(backup_path) = steam_save.backup_now().unwrap() else {
+            panic!("expected a new backup");
+        };
+        assert!(store.read(&backup_dir.join(HASH_MANIFEST)).is_ok());
+
+        // Change the backup behind the manifest's back, keeping its size and modify time
+        let modified = store.stat(&backup_path).unwrap().modified;
+        store.insert(&backup_path, &fake_save(b"two"), modified);
+        store.insert(&save_path, &fake_save(b"two"), SystemTime::now());
+
+        steam_save.options.hash_cache = Arc::default();
+        steam_save
+            .options
+            .hash_cache
+            .load(store.as_ref(), &backup_dir);
+        assert!(!steam_save.is_dupe_backup(&save_path).unwrap());
+
+        // Changed files are hashed again
+        store.insert(&backup_path, &fake_save(b"two"), SystemTime::now());
+        steam_save.options.hash_cache = Arc::default();
+        steam_save
+            .options
+            .hash_cache
+            .load(store.as_ref(), &backup_dir);
+        assert!(steam_save.is_dupe_backup(&save_path).unwrap());
+    }
+
+    #[test]
+    fn locate_save_path_skips_excluded_slots() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        write_file(&save_path, &fake_save(b"save"), Duration::ZERO);
+        let slot = STEAM_NAME.trim_end_matches(".sav").to_string();
+
+        assert_eq!(steam_save.locate_save_path().unwrap().0, save_path);
+
+        steam_save.options.slot_filter = SlotFilter::Allow(vec![]);
+        assert_eq!(steam_save.locate_save_path().unwrap().0, save_path);
+
+        steam_save.options.slot_filter = SlotFilter::Allow(vec![slot.clone()]);
+        assert_eq!(steam_save.locate_save_path().unwrap().0, save_path);
+
+        steam_save.options.slot_filter = SlotFilter::Allow(vec!["Experimental".to_string()]);
+        assert!(steam_save.locate_save_path().is_none());
+
+        steam_save.options.slot_filter = SlotFilter::Deny(vec![slot.to_uppercase()]);
+        assert!(steam_save.locate_save_path().is_none());
+    }
+
+    #[test]
+    fn staged_copy_replaces_destination() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        steam_save.options.staging_dir = Some(PathBuf::from(".staging"));
+
+        let dest = steam_save.save_dir().join(STEAM_NAME);
+        write_file(&dest, &fake_save(b"old"), Duration::from_secs(60 * 60));
+        let source = dir.path().join("source.sav");
+        let bytes = fake_save(b"new");
+        write_file(&source, &bytes, Duration::ZERO);
+
+        let result = steam_save.copy_save(&source, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Ok(SyncOutcome::Copied { .. })));
+        assert_eq!(std::fs::read(&dest).unwrap(), bytes);
+
+        // The staged copy was moved, and is never mistaken for the save
+        let staged = steam_save.save_dir().join(".staging").join(STEAM_NAME);
+        assert!(!staged.exists());
+        write_file(&staged, &bytes, Duration::ZERO);
+        assert!(!steam_save.is_synced_save(&staged));
+        assert_eq!(steam_save.locate_save_path().unwrap().0, dest);
+    }
+
+    #[test]
+    fn backup_stats_counts_own_backups() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        let backup_dir = steam_save.backup_dir().to_path_buf();
+
+        assert_eq!(steam_save.backup_stats().unwrap(), BackupStats::default());
+
+        // The oldest backup was touched last, e.g. by cloud sync
+        write_file(
+            &backup_dir.join(format!("1_steam_{STEAM_NAME}")),
+            b"old",
+            Duration::ZERO,
+        );
+        write_file(
+            &backup_dir.join(format!("2_steam_{STEAM_NAME}")),
+            b"newer",
+            Duration::from_secs(60),
+        );
+        write_file(
+            &backup_dir.join("notes.txt"),
+            b"not a backup",
+            Duration::ZERO,
+        );
+
+        let stats = steam_save.backup_stats().unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.total_bytes, 8);
+        let since_epoch = |time: SystemTime| time.duration_since(SystemTime::UNIX_EPOCH).unwrap();
+        assert_eq!(since_epoch(stats.oldest.unwrap()), Duration::from_secs(1));
+        assert_eq!(since_epoch(stats.newest.unwrap()), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn backup_now_dedupes() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        assert_eq!(steam_save.backup_now().unwrap(), BackupOutcome::NoSave);
+
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        write_file(&save_path, &fake_save(b"save"), Duration::ZERO);
+        match steam_save.backup_now().unwrap() {
+            BackupOutcome::Created(path) => {
+                assert_eq!(std::fs::read(path).unwrap(), fake_save(b"save"));
+            }
+            outcome => panic!("Unexpected outcome: {outcome:?}"),
+        }
+        assert_eq!(steam_save.backup_now().unwrap(), BackupOutcome::Duplicate);
+    }
+
+    #[test]
+    fn compressed_backups_dedupe_and_restore() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        steam_save.options.compress_backups = true;
+        let backup_dir = steam_save.backup_dir().to_path_buf();
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        write_file(&save_path, &fake_save(b"save"), Duration::ZERO);
+
+        let BackupOutcome::Created(path) = steam_save.backup_now().unwrap() else {
+            panic!("Expected a new backup");
+        };
+        assert!(is_compressed(&path));
+        assert!(std::fs::metadata(&path).unwrap().len() < fake_save(b"save").len() as u64);
+        assert_eq!(
+            steam_save.hash(&path).unwrap(),
+            steam_save.hash(&save_path).unwrap()
+        );
+
+        // The uncompressed save matches the compressed backup, whatever the setting
+        assert_eq!(steam_save.backup_now().unwrap(), BackupOutcome::Duplicate);
+        steam_save.options.compress_backups = false;
+        assert_eq!(steam_save.backup_now().unwrap(), BackupOutcome::Duplicate);
+        std::fs::remove_file(&path).unwrap();
+
+        let when = SystemTime::now() - Duration::from_secs(60);
+        let older = backup_dir.join(format!("{}.gz", steam_save.backup_name(STEAM_NAME, when)));
+        write_file(&save_path, &fake_save(b"older"), Duration::ZERO);
+        compressed_copy(steam_save.store(), &save_path, &older).unwrap();
+        write_file(&save_path, &fake_save(b"broken"), Duration::ZERO);
+
+        let latest = steam_save.latest_backup().unwrap().unwrap();
+        assert_eq!(latest.path, older);
+        assert_eq!(latest.filename, STEAM_NAME);
+        steam_save.restore(&latest).unwrap();
+        assert_eq!(std::fs::read(&save_path).unwrap(), fake_save(b"older"));
+
+        // Corrupt compressed backups are refused
+        std::fs::write(&older, b"not gzip").unwrap();
+        let result = steam_save.restore(&latest);
+        assert!(matches!(result, Err(SaveError::NotASave(_))));
+    }
+
+    #[test]
+    fn restore_latest_takes_safety_backup() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        let backup_dir = steam_save.backup_dir().to_path_buf();
+        assert_eq!(steam_save.restore_latest().unwrap(), None);
+
+        let now = SystemTime::now();
+        for (age, body) in [(120, b"older"), (60, b"newer")] {
+            let name = steam_save.backup_name(STEAM_NAME, now - Duration::from_secs(age));
+            write_file(&backup_dir.join(name), &fake_save(body), Duration::ZERO);
+        }
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        write_file(&save_path, &fake_save(b"broken"), Duration::ZERO);
+
+        let latest = steam_save.latest_backup().unwrap().unwrap();
+        assert_eq!(latest.filename, STEAM_NAME);
+        assert_eq!(std::fs::read(&latest.path).unwrap(), fake_save(b"newer"));
+
+        assert_eq!(steam_save.restore_latest().unwrap(), Some(latest));
+        assert_eq!(std::fs::read(&save_path).unwrap(), fake_save(b"newer"));
+
+        // The broken save was kept as the newest backup
+        let safety = steam_save.latest_backup().unwrap().unwrap();
+        assert_eq!(std::fs::read(safety.path).unwrap(), fake_save(b"broken"));
+    }
+
+    #[test]
+    fn restore_chosen_backup() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        let backup_dir = steam_save.backup_dir().to_path_buf();
+
+        let now = SystemTime::now();
+        for (age, body) in [(60, b"newer"), (120, b"older")] {
+            let name = steam_save.backup_name(STEAM_NAME, now - Duration::from_secs(age));
+            write_file(&backup_dir.join(name), &fake_save(body), Duration::ZERO);
+        }
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        write_file(&save_path, &fake_save(b"current"), Duration::ZERO);
+
+        // Listed by the timestamp in the name, not the modify time
+        let backups = steam_save.list_backups().unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(
+            std::fs::read(&backups[0].path).unwrap(),
+            fake_save(b"older")
+        );
+
+        steam_save.restore(&backups[0]).unwrap();
+        assert_eq!(std::fs::read(&save_path).unwrap(), fake_save(b"older"));
+        assert_eq!(steam_save.list_backups().unwrap().len(), 3);
+
+        // Only this side's backups can be restored
+        let stranger = BackupEntry {
+            path: dir.path().join(backups[1].path.file_name().unwrap()),
+            ..backups[1].clone()
+        };
+        assert!(matches!(
+            steam_save.restore(&stranger),
+            Err(SaveError::NotABackup(_)),
+        ));
+    }
+
+    #[test]
+    fn delete_chosen_backup() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        write_file(&save_path, &fake_save(b"plain"), Duration::ZERO);
+        steam_save.backup_now().unwrap();
+        steam_save.options.compress_backups = true;
+        write_file(&save_path, &fake_save(b"compressed"), Duration::ZERO);
+        steam_save.backup_now().unwrap();
+
+        let backups = steam_save.list_backups().unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(backups.iter().any(|backup| backup.compressed));
+        assert!(backups.iter().any(|backup| !backup.compressed));
+
+        // Only this side's backups can be deleted
+        let stranger = BackupEntry {
+            path: dir.path().join(backups[0].path.file_name().unwrap()),
+            ..backups[0].clone()
+        };
+        assert!(matches!(
+            steam_save.delete_backup(&stranger),
+            Err(SaveError::NotABackup(_)),
+        ));
+
+        steam_save.delete_backup(&backups[0]).unwrap();
+        assert!(!backups[0].path.exists());
+        assert_eq!(steam_save.list_backups().unwrap(), &backups[1..]);
+    }
+
+    #[test]
+    fn backup_recreates_missing_backup_dir() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        std::fs::remove_dir_all(steam_save.backup_dir()).unwrap();
+
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        write_file(&save_path, &fake_save(b"save"), Duration::ZERO);
+        assert!(matches!(
+            steam_save.backup_now().unwrap(),
+            BackupOutcome::Created(_),
+        ));
+    }
+
+    #[test]
+    fn backups_work_in_memory() {
+        let store = Arc::new(InMemoryStore::default());
+        let (steam_save, _) = in_memory_saves(&store);
+
+        let now = SystemTime::now();
+        for (age, body) in [(3, b"one"), (2, b"two"), (1, b"six")] {
+            let when = now - Duration::from_secs(age * 60);
+            let name = steam_save.backup_name(STEAM_NAME, when);
+            store.insert(steam_save.backup_dir().join(name), &fake_save(body), when);
+        }
+
+        // Duplicates are detected by hashing the stored files
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        store.insert(&save_path, &fake_save(b"two"), now);
+        assert_eq!(steam_save.backup(&save_path, STEAM_NAME).unwrap(), None);
+
+        // The oldest backup is pruned to make room
+        store.insert(&save_path, &fake_save(b"new"), now);
+        let backup = steam_save.backup(&save_path, STEAM_NAME).unwrap();
+        assert_eq!(steam_save.backup_paths().len(), 3);
+        let latest = steam_save.latest_backup().unwrap().unwrap();
+        assert_eq!(Some(latest.path), backup);
+        assert_eq!(steam_save.backup_stats().unwrap().count, 3);
+    }
+
+    /// Create Steam and Xbox saves sharing an in-memory store.
+    fn in_memory_saves(store: &Arc<InMemoryStore>) -> (SteamSave, XboxSave) {
+        let mut steam_save = steam_save(&TempDir::new().unwrap());
+        steam_save.options.store = store.clone();
+        steam_save.save_dir = PathBuf::from("steam");
+        steam_save.backup_dir = PathBuf::from("backup/steam");
+
+        let xbox_save = XboxSave {
+            options: steam_save.options.clone(),
+            save_dir: PathBuf::from("xbox"),
+            backup_dir: PathBuf::from("backup/xbox"),
+        };
+
+        (steam_save, xbox_save)
+    }
+
+    #[test]
+    fn failed_restore_keeps_save() {
+        let store = Arc::new(InMemoryStore::default());
+        let (steam_save, _) = in_memory_saves(&store);
+        let save_path = steam_save.save_dir().join(STEAM_NAME);
+        let now = SystemTime::now();
+        store.insert(&save_path, &fake_save(b"current"), now);
+        let name = steam_save.backup_name(STEAM_NAME, now - Duration::from_secs(60));
+        store.insert(
+            steam_save.backup_dir().join(name),
+            &fake_save(b"backup"),
+            now,
+        );
+
+        let backup = steam_save.latest_backup().unwrap().unwrap();
+        store.fail_writes_into(Some(steam_save.save_dir()));
+        assert!(matches!(steam_save.restore(&backup), Err(SaveError::Io(_))));
+        assert_eq!(store.read(&save_path).unwrap(), fake_save(b"current"));
+        assert_eq!(store.walk(steam_save.save_dir()).unwrap().len(), 1);
+
+        store.fail_writes_into(None);
+        steam_save.restore(&backup).unwrap();
+        assert_eq!(store.read(&save_path).unwrap(), fake_save(b"backup"));
+        assert_eq!(store.walk(steam_save.save_dir()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn failed_copy_keeps_destination() {
+        let store = Arc::new(InMemoryStore::default());
+        let (steam_save, xbox_save) = in_memory_saves(&store);
+        let steam_path = steam_save.save_dir().join(STEAM_NAME);
+        let xbox_path = xbox_save
+            .save_dir()
+            .join("0123456789ABCDEF0123456789ABCDEF");
+        let now = SystemTime::now();
+        store.insert(&steam_path, &fake_save(b"steam"), now);
+        store.insert(
+            &xbox_path,
+            &fake_save(b"xbox"),
+            now - Duration::from_secs(60),
+        );
+
+        store.fail_writes_into(Some(xbox_save.save_dir()));
+        let result = xbox_save.copy_save(&steam_path, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Err(SaveError::Io(_))));
+        assert_eq!(store.read(&xbox_path).unwrap(), fake_save(b"xbox"));
+
+        // The partial copy is cleaned up
+        let files = store.walk(xbox_save.save_dir()).unwrap();
+        assert_eq!(files.len(), 1);
+
+        store.fail_writes_into(None);
+        let result = xbox_save.copy_save(&steam_path, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Ok(SyncOutcome::Copied { .. })));
+        assert_eq!(store.read(&xbox_path).unwrap(), fake_save(b"steam"));
+    }
+
+    #[test]
+    fn copy_save_retries_transient_errors() {
+        let store = Arc::new(InMemoryStore::default());
+        let (steam_save, xbox_save) = in_memory_saves(&store);
+        let steam_path = steam_save.save_dir().join(STEAM_NAME);
+        let xbox_path = xbox_save
+            .save_dir()
+            .join("0123456789ABCDEF0123456789ABCDEF");
+        let now = SystemTime::now();
+        store.insert(&steam_path, &fake_save(b"steam"), now);
+        store.insert(
+            &xbox_path,
+            &fake_save(b"xbox"),
+            now - Duration::from_secs(60),
+        );
+
+        // Like the game holding the save open for a moment
+        store.fail_next_copies(RETRY_DELAYS.len());
+        let result = xbox_save.copy_save(&steam_path, |_| SyncDecision::Proceed);
+        assert!(matches!(result, Ok(SyncOutcome::Copied { .. })));
+        assert_eq!(store.read(&xbox_path).unwrap(), fake_save(b"steam"));
+        assert_eq!(xbox_save.list_backups().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn copy_save_compares_exact_mtimes() {
+        let store = Arc::new(InMemoryStore::default());
+        let (steam_save, xbox_save) = in_memory_saves(&store);
+        let steam_path = steam_save.save_dir().join(STEAM_NAME);
+        let xbox_path = xbox_save
+            .save_dir()
+            .join("0123456789ABCDEF0123456789ABCDEF");
+        let proceed = |_: &Path| SyncDecision::Proceed;
+
+        let then = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        store.insert(&steam_path, &fake_save(b"steam"), then);
+        store.insert(&xbox_path, &fake_save(b"xbox"), then);
+
+        // The same modify time, down to the nanosecond, is never synced
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, proceed),
+            Err(SaveError::ModifyTime),
+        ));
+
+        let newer = then + Duration::from_nanos(1);
+        store.set_modified(&steam_path, newer).unwrap();
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, proceed),
+            Ok(SyncOutcome::Copied { bytes, .. }) if bytes == fake_save(b"steam").len() as u64,
+        ));
+        assert_eq!(store.read(&xbox_path).unwrap(), fake_save(b"steam"));
+        assert_eq!(store.stat(&xbox_path).unwrap().modified, newer);
+
+        // The overwritten save was backed up, and the copy does not bounce back
+        let backups = xbox_save.backup_paths();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(store.read(&backups[0]).unwrap(), fake_save(b"xbox"));
+        assert!(matches!(
+            steam_save.copy_save(&xbox_path, proceed),
+            Err(SaveError::ModifyTime),
+        ));
+    }
+
+    #[test]
+    fn copy_save_breaks_mtime_ties_by_contents() {
+        let store = Arc::new(InMemoryStore::default());
+        let (mut steam_save, mut xbox_save) = in_memory_saves(&store);
+        steam_save.options.mtime_tolerance = Duration::from_secs(2);
+        xbox_save.options.mtime_tolerance = Duration::from_secs(2);
+        let steam_path = steam_save.save_dir().join(STEAM_NAME);
+        let xbox_path = xbox_save
+            .save_dir()
+            .join("0123456789ABCDEF0123456789ABCDEF");
+        let proceed = |_: &Path| SyncDecision::Proceed;
+        let then = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        // Each case replaces both saves, which is only a conflict after they were synced
+        let cache = steam_save.options.hash_cache.clone();
+        let never_synced = || {
+            cache.forget_synced(&steam_path);
+            cache.forget_synced(&xbox_path);
+        };
+
+        // Identical saves within the tolerance are left alone
+        store.insert(
+            &steam_path,
+            &fake_save(b"same"),
+            then + Duration::from_secs(1),
+        );
+        store.insert(&xbox_path, &fake_save(b"same"), then);
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, proceed),
+            Ok(SyncOutcome::Identical),
+        ));
+
+        // The larger save wins, even when its modify time is a little behind
+        never_synced();
+        store.insert(&steam_path, &fake_save(b"longer"), then);
+        store.insert(
+            &xbox_path,
+            &fake_save(b"short"),
+            then + Duration::from_secs(1),
+        );
+        assert!(matches!(
+            steam_save.copy_save(&xbox_path, proceed),
+            Err(SaveError::ModifyTime),
+        ));
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, proceed),
+            Ok(SyncOutcome::Copied { .. }),
+        ));
+        assert_eq!(store.read(&xbox_path).unwrap(), fake_save(b"longer"));
+
+        // Between saves of the same size, the one written last wins
+        never_synced();
+        store.insert(
+            &steam_path,
+            &fake_save(b"steam"),
+            then + Duration::from_secs(1),
+        );
+        store.insert(&xbox_path, &fake_save(b"xbox!"), then);
+        assert!(matches!(
+            steam_save.copy_save(&xbox_path, proceed),
+            Err(SaveError::ModifyTime),
+        ));
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, proceed),
+            Ok(SyncOutcome::Copied { .. }),
+        ));
+
+        // Beyond the tolerance, the modify time decides
+        never_synced();
+        store.insert(&steam_path, &fake_save(b"longer"), then);
+        store.insert(
+            &xbox_path,
+            &fake_save(b"short"),
+            then + Duration::from_secs(3),
+        );
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, proceed),
+            Err(SaveError::ModifyTime),
+        ));
+    }
+
+    #[test]
+    fn copy_save_detects_conflicts() {
+        let store = Arc::new(InMemoryStore::default());
+        let (steam_save, xbox_save) = in_memory_saves(&store);
+        let steam_path = steam_save.save_dir().join(STEAM_NAME);
+        let xbox_path = xbox_save
+            .save_dir()
+            .join("0123456789ABCDEF0123456789ABCDEF");
+        let proceed = |_: &Path| SyncDecision::Proceed;
+        let now = SystemTime::now();
+        store.insert(&steam_path, &fake_save(b"synced"), now);
+        store.insert(
+            &xbox_path,
+            &fake_save(b"old"),
+            now - Duration::from_secs(60),
+        );
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, proceed),
+            Ok(SyncOutcome::Copied { .. }),
+        ));
+
+        // Only one side changed since the sync
+        store.insert(
+            &steam_path,
+            &fake_save(b"steam"),
+            now + Duration::from_secs(60),
+        );
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, proceed),
+            Ok(SyncOutcome::Copied { .. }),
+        ));
+
+        // Both sides changed, so the older one is kept aside instead of overwritten
+        store.insert(
+            &steam_path,
+            &fake_save(b"steam2"),
+            now + Duration::from_secs(120),
+        );
+        store.insert(
+            &xbox_path,
+            &fake_save(b"xbox"),
+            now + Duration::from_secs(90),
+        );
+        for _ in 0..2 {
+            assert!(matches!(
+                xbox_save.copy_save(&steam_path, proceed),
+                Err(SaveError::Conflict { .. }),
+            ));
+        }
+        assert_eq!(store.read(&xbox_path).unwrap(), fake_save(b"xbox"));
+        let copies = store.list(xbox_save.backup_dir()).unwrap();
+        let copies = copies
+            .iter()
+            .filter(|entry| entry.path.to_string_lossy().contains(CONFLICT_PREFIX))
+            .collect::<Vec<_>>();
+        assert_eq!(copies.len(), 1);
+        assert_eq!(store.read(&copies[0].path).unwrap(), fake_save(b"xbox"));
+
+        // The last synced hashes survive restarts
+        let cache = HashCache::default();
+        cache.load(&*store, xbox_save.backup_dir());
+        let algo = xbox_save.options().hash_algo;
+        let synced = algo.hash_reader(&fake_save(b"steam")[..]).unwrap();
+        assert_eq!(cache.synced(algo, &steam_path), Some(synced.clone()));
+        assert_eq!(cache.synced(algo, &xbox_path), Some(synced));
+    }
+
+    #[test]
+    fn copies_only_stand_in_for_missing_saves() {
+        let dir = TempDir::new().unwrap();
+        let steam_save = steam_save(&dir);
+        let save_path = steam_save.save_dir.join(STEAM_NAME);
+        let copy_name = STEAM_NAME.replace(".sav", " (1).sav");
+        let copy_path = steam_save.save_dir.join(&copy_name);
+        assert_eq!(exact_save_name(&copy_name), STEAM_NAME);
+
+        // Cloud sync left a newer copy next to the save
+        write_file(&save_path, &fake_save(b"save"), Duration::from_secs(60));
+        write_file(&copy_path, &fake_save(b"copy"), Duration::ZERO);
+        assert_eq!(
+            steam_save.locate(),
+            LocateResult::Found(save_path.clone(), STEAM_NAME.to_string())
+        );
+        assert!(!steam_save.is_synced_save(&copy_path));
+
+        let from = dir.path().join(STEAM_NAME);
+        write_file(&from, &fake_save(b"new"), Duration::from_secs(30));
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(
+            matches!(result, Ok(SyncOutcome::Copied { .. })),
+            "{result:?}"
+        );
+        assert_eq!(std::fs::read(&save_path).unwrap(), fake_save(b"new"));
+        assert_eq!(std::fs::read(&copy_path).unwrap(), fake_save(b"copy"));
+
+        // Without the save, the copy stands in for it, but the save is written
+        std::fs::remove_file(&save_path).unwrap();
+        assert!(steam_save.is_synced_save(&copy_path));
+        write_file(&from, &fake_save(b"newest"), Duration::ZERO);
+        let result = steam_save.copy_save(&from, |_| SyncDecision::Proceed);
+        assert!(
+            matches!(result, Ok(SyncOutcome::Copied { .. })),
+            "{result:?}"
+        );
+        assert_eq!(std::fs::read(&save_path).unwrap(), fake_save(b"newest"));
+    }
+
+    #[test]
+    fn save_names_are_matched() {
+        for name in [
+            "76561197960287930_Player.sav",
+            "76561197960287930_Player.SAV",
+            "76561197960287930_Player.Sav",
+            "76561197960287930_Player.sav ",
+            "76561197960287930_Player (1).sav",
+            "76561197960287930_Player(12).SAV",
+        ] {
+            assert!(SteamSave::is_save_name(name), "{name:?} should be a save");
+            assert_eq!(slot_name(name), "76561197960287930_Player");
+        }
+        for name in [
+            "76561197960287930_Player.sav.bak",
+            "76561197960287930_Player.txt",
+            "76561197960287930_Player",
+            "76561197960287930_Player (copy).sav",
+            "76561197960287930_Player ().sav",
+            "76561197960287930_Player (1) x.sav",
+            "76561197960287930_player.sav",
+            "76561197960287930_Player_Backup.sav",
+            "SaveGames.json",
+        ] {
+            assert!(
+                !SteamSave::is_save_name(name),
+                "{name:?} should not be a save"
+            );
+        }
+
+        for name in [
+            "0123456789ABCDEF0123456789ABCDEF",
+            "0123456789abcdef0123456789abcdef",
+            "0123456789AbCdEf0123456789aBcDeF",
+        ] {
+            assert!(XboxSave::is_save_name(name), "{name:?} should be a save");
+        }
+        for name in [
+            "0123456789ABCDEF0123456789ABCDE",
+            "0123456789ABCDEF0123456789ABCDEF0",
+            "0123456789ABCDEF0123456789ABCDEG",
+            "container.1",
+            "containers.index",
+        ] {
+            assert!(
+                !XboxSave::is_save_name(name),
+                "{name:?} should not be a save"
+            );
+        }
+    }
+
+    #[test]
+    fn vdf_library_paths_accepts_both_formats() {
+        let legacy = r#"
+"LibraryFolders"
+{
+	"TimeNextStatsReport"		"1680000000"
+	"ContentStatsID"		"-1234"
+	"1"		"D:\\SteamLibrary"
+}
+"#;
+        assert_eq!(
+            vdf_library_paths(legacy),
+            [PathBuf::from("D:\\SteamLibrary").join("steamapps")],
+        );
+
+        let modern = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"/home/steeve/.steam/steam"
+		"apps"
+		{
+			"548430"		"3400000000"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/SteamLibrary"
+	}
+}
+"#;
+        assert_eq!(
+            vdf_library_paths(modern),
+            [
+                PathBuf::from("/home/steeve/.steam/steam/steamapps"),
+                PathBuf::from("/mnt/games/SteamLibrary/steamapps"),
+            ],
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn proton_save_dirs_are_found_in_compatdata() {
+        let dir = TempDir::new().unwrap();
+        let home = dir.path().join(".steam").join("steam").join("steamapps");
+        let games = dir.path().join("games").join("steamapps");
+        let save_dir = games
+            .join("compatdata")
+            .join("548430")
+            .join("pfx")
+            .join("drive_c")
+            .join("users")
+            .join("steamuser")
+            .join("AppData")
+            .join("Local")
+            .join("FSD")
+            .join("Saved")
+            .join("SaveGames");
+        std::fs::create_dir_all(&save_dir).unwrap();
+        std::fs::create_dir_all(home.join("compatdata").join("548430")).unwrap();
+
+        // Only libraries whose prefix has the save directory are candidates
+        assert_eq!(proton_save_dirs(&[home.clone(), games]), [save_dir]);
+        assert!(proton_save_dirs(&[home]).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn linked_save_dir_is_resolved() {
+        let dir = TempDir::new().unwrap();
+        let options = steam_save(&dir).options;
+        let real_dir = dir.path().join("elsewhere");
+        let link = dir.path().join("linked");
+        std::fs::create_dir_all(&real_dir).unwrap();
+        std::os::unix::fs::symlink(&real_dir, &link).unwrap();
+        let real_dir = real_dir.canonicalize().unwrap();
+
+        let save_path = real_dir.join(STEAM_NAME);
+        std::fs::write(&save_path, fake_save(b"linked")).unwrap();
+
+        let mut steam_save =
+            SteamSave::new(options, link.clone(), dir.path().join("backup")).unwrap();
+        assert_eq!(steam_save.save_dir(), real_dir);
+        assert_eq!(steam_save.watch_dir(), real_dir);
+        let (path, _) = steam_save.locate_save_path().unwrap();
+        assert_eq!(path, save_path);
+
+        steam_save.set_save_dir(dir.path().join("save"));
+        steam_save.set_save_dir(link);
+        assert_eq!(steam_save.save_dir(), real_dir);
+    }
+
+    #[test]
+    fn xbox_save_follows_relocated_container() {
+        let dir = TempDir::new().unwrap();
+        let wgs = dir.path().join("wgs");
+        let default = wgs.join("default");
+        let empty = wgs.join("empty");
+        let moved = wgs.join("moved");
+        std::fs::create_dir_all(&empty).unwrap();
+        std::fs::create_dir_all(moved.join("0123456789ABCDEF0123456789ABCDEF")).unwrap();
+        write_file(
+            &moved
+                .join("0123456789ABCDEF0123456789ABCDEF")
+                .join("FEDCBA9876543210FEDCBA9876543210"),
+            &fake_save(b"save"),
+            Duration::ZERO,
+        );
+
+        let candidates = vec![default.clone(), empty, moved.clone()];
+        assert_eq!(XboxSave::pick_save_dir(candidates.clone()), Some(moved));
+
+        // The well-known container is always preferred while it exists
+        std::fs::create_dir_all(&default).unwrap();
+        assert_eq!(XboxSave::pick_save_dir(candidates), Some(default));
+    }
+
+    #[test]
+    fn copy_save_blames_missing_source() {
+        let store = Arc::new(InMemoryStore::default());
+        let (_, xbox_save) = in_memory_saves(&store);
+        let xbox_path = xbox_save
+            .save_dir()
+            .join("0123456789ABCDEF0123456789ABCDEF");
+        store.insert(&xbox_path, &fake_save(b"xbox"), SystemTime::now());
+
+        let steam_path = Path::new("steam").join(STEAM_NAME);
+        assert!(matches!(
+            xbox_save.copy_save(&steam_path, |_| SyncDecision::Proceed),
+            Err(SaveError::SourceUnavailable(path, _)) if path == steam_path,
+        ));
+    }
+
+    #[test]
+    fn locate_explains_missing_saves() {
+        let dir = TempDir::new().unwrap();
+        let mut steam_save = steam_save(&dir);
+        let save_dir = steam_save.save_dir().to_path_buf();
+
+        assert_eq!(steam_save.locate(), LocateResult::NoMatch { scanned: 0 });
+
+        write_file(&save_dir.join("Player.sav"), b"save", Duration::ZERO);
+        assert_eq!(steam_save.locate(), LocateResult::NoMatch { scanned: 1 });
+
+        let save_path = save_dir.join(STEAM_NAME);
+        write_file(&save_path, b"save", Duration::ZERO);
+        assert_eq!(
+            steam_save.locate(),
+            LocateResult::Found(save_path, STEAM_NAME.to_string()),
+        );
+
+        steam_save.set_save_dir(dir.path().join("missing"));
+        assert_eq!(steam_save.locate(), LocateResult::DirMissing);
+    }
+
+    #[test]
+    fn retry_transient_recovers() {
+        let mut attempts = 0;
+        let result = retry_transient(|| {
+            attempts += 1;
+            if attempts == 1 {
+                Err(std::io::Error::from(ErrorKind::PermissionDenied))
+            } else {
+                Ok(())
+            }
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn retry_transient_gives_up() {
+        let mut attempts = 0;
+        let result: std::io::Result<()> = retry_transient(|| {
+            attempts += 1;
+            Err(std::io::Error::from(ErrorKind::PermissionDenied))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, RETRY_DELAYS.len() + 1);
+
+        let mut attempts = 0;
+        let result: std::io::Result<()> = retry_transient(|| {
+            attempts += 1;
+            Err(std::io::Error::from(ErrorKind::NotFound))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
     }
 }
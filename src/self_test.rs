@@ -0,0 +1,406 @@
+//! A self-test that runs the sync pipeline on fake saves in a temporary directory.
+
+use crate::saves::{discover_steam_save_dirs, discover_xbox_save_dirs, BackupEntry};
+use crate::saves::{SaveOptions, SteamSave, SteeveSave, XboxSave, GVAS_MAGIC, MIN_SAVE_SIZE};
+use crate::wgs::{self, IndexReport};
+use crate::{SteeveBuilder, SyncDecision, SyncOutcome};
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// File name for the fake Steam save.
+pub(crate) const STEAM_NAME: &str = "76561197960287930_Player.sav";
+
+/// File name for the fake Xbox save.
+pub(crate) const XBOX_NAME: &str = "0123456789ABCDEF0123456789ABCDEF";
+
+/// The stages of [`Steeve::self_test`](crate::Steeve::self_test), in the order they run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SelfTestStage {
+    /// Create the temporary save and backup directories with fake saves.
+    Setup,
+
+    /// Sync the newer Steam save over the older Xbox save.
+    Sync,
+
+    /// Back up a save that is already backed up.
+    Dedupe,
+
+    /// Restore the backup made by the sync over the Xbox save.
+    Restore,
+
+    /// Remove old backups beyond the limit.
+    Prune,
+}
+
+impl SelfTestStage {
+    const ALL: [Self; 5] = [
+        Self::Setup,
+        Self::Sync,
+        Self::Dedupe,
+        Self::Restore,
+        Self::Prune,
+    ];
+}
+
+/// The result of a single [`SelfTestStage`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StageStatus {
+    /// The stage did what it should.
+    Passed,
+
+    /// The stage failed, for this reason.
+    Failed(String),
+
+    /// The stage was not run because an earlier stage failed.
+    NotRun,
+}
+
+/// A save directory found on this machine, and whether it can be written.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DirReport {
+    /// The save directory.
+    pub path: PathBuf,
+
+    /// Whether the directory can be listed.
+    pub readable: bool,
+
+    /// Whether the directory is not read-only.
+    pub writable: bool,
+}
+
+/// The report from [`Steeve::self_test`](crate::Steeve::self_test).
+///
+/// The [`Display`] impl formats it as plain text, suitable for pasting into a bug report.
+#[derive(Clone, Debug)]
+pub struct SelfTestReport {
+    /// The result of every stage, in the order they run.
+    pub stages: Vec<(SelfTestStage, StageStatus)>,
+
+    /// The Steam save directories found on this machine, see [`discover_steam_save_dirs`].
+    pub steam_dirs: Vec<DirReport>,
+
+    /// The Xbox save directories found on this machine, see [`discover_xbox_save_dirs`].
+    pub xbox_dirs: Vec<DirReport>,
+
+    /// Consistency of the `containers.index` in each Xbox save directory that has one.
+    pub xbox_indexes: Vec<Result<IndexReport, String>>,
+}
+
+impl SelfTestReport {
+    /// Check if all stages passed.
+    pub fn passed(&self) -> bool {
+        self.stages
+            .iter()
+            .all(|(_, status)| *status == StageStatus::Passed)
+    }
+}
+
+impl Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = if self.passed() { "PASSED" } else { "FAILED" };
+        writeln!(
+            f,
+            "Steeve-Sync {} self-test {result}",
+            env!("CARGO_PKG_VERSION")
+        )?;
+
+        for (stage, status) in &self.stages {
+            match status {
+                StageStatus::Passed => writeln!(f, "  {stage:?}: passed")?,
+                StageStatus::Failed(reason) => writeln!(f, "  {stage:?}: FAILED: {reason}")?,
+                StageStatus::NotRun => writeln!(f, "  {stage:?}: not run")?,
+            }
+        }
+
+        for (name, dirs) in [("Steam", &self.steam_dirs), ("Xbox", &self.xbox_dirs)] {
+            if dirs.is_empty() {
+                writeln!(f, "{name} save directories: none found")?;
+                continue;
+            }
+
+            writeln!(f, "{name} save directories:")?;
+            for dir in dirs {
+                let access = match (dir.readable, dir.writable) {
+                    (true, true) => "read/write",
+                    (true, false) => "read-only",
+                    (false, _) => "not readable",
+                };
+                writeln!(f, "  {:?} ({access})", dir.path)?;
+            }
+        }
+
+        for index in &self.xbox_indexes {
+            match index {
+                Ok(report) if report.is_consistent() => {
+                    writeln!(f, "Xbox index {:?}: consistent", report.index_path)?;
+                }
+                Ok(report) => writeln!(
+                    f,
+                    "Xbox index {:?}: {} dangling containers, {} missing blobs, {} orphans",
+                    report.index_path,
+                    report.dangling.len(),
+                    report.missing_blobs.len(),
+                    report.orphans.len(),
+                )?,
+                Err(err) => writeln!(f, "Xbox index: FAILED: {err}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Run the self-test in a new temporary directory, and remove it afterward.
+pub(crate) fn run() -> SelfTestReport {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let root =
+        std::env::temp_dir().join(format!("steeve-self-test-{}-{nanos}", std::process::id(),));
+
+    let stages = run_stages(&root);
+    let _ = std::fs::remove_dir_all(&root);
+
+    let xbox_dirs = discover_xbox_save_dirs();
+    let xbox_indexes = xbox_dirs
+        .iter()
+        .filter(|dir| dir.join(wgs::INDEX_FILE).is_file())
+        .map(|dir| wgs::check(dir).map_err(|err| err.to_string()))
+        .collect();
+
+    SelfTestReport {
+        stages,
+        steam_dirs: discover_steam_save_dirs().iter().map(dir_report).collect(),
+        xbox_dirs: xbox_dirs.iter().map(dir_report).collect(),
+        xbox_indexes,
+    }
+}
+
+/// Run each stage in order, until one fails.
+fn run_stages(root: &Path) -> Vec<(SelfTestStage, StageStatus)> {
+    let mut pipeline: Option<Pipeline> = None;
+    let mut failed = false;
+
+    SelfTestStage::ALL
+        .into_iter()
+        .map(|stage| {
+            if failed {
+                return (stage, StageStatus::NotRun);
+            }
+
+            let result = match (stage, &pipeline) {
+                (SelfTestStage::Setup, _) => Pipeline::setup(root).map(Some),
+                (SelfTestStage::Sync, Some(p)) => p.sync().map(|()| None),
+                (SelfTestStage::Dedupe, Some(p)) => p.dedupe().map(|()| None),
+                (SelfTestStage::Restore, Some(p)) => p.restore().map(|()| None),
+                (SelfTestStage::Prune, Some(p)) => p.prune().map(|()| None),
+                (_, None) => Err("setup did not run".to_string()),
+            };
+
+            match result {
+                Ok(setup) => {
+                    if setup.is_some() {
+                        pipeline = setup;
+                    }
+                    (stage, StageStatus::Passed)
+                }
+                Err(reason) => {
+                    failed = true;
+                    (stage, StageStatus::Failed(reason))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Fake saves and the save managers under test.
+struct Pipeline {
+    steam_save: SteamSave,
+    xbox_save: XboxSave,
+    steam_path: PathBuf,
+    xbox_path: PathBuf,
+    steam_bytes: Vec<u8>,
+    xbox_bytes: Vec<u8>,
+    scratch_dir: PathBuf,
+}
+
+impl Pipeline {
+    fn setup(root: &Path) -> Result<Self, String> {
+        // The default settings, without waiting for the fake saves to settle
+        let options = SaveOptions {
+            max_backups: 2,
+            settle_time: Duration::ZERO,
+            ..SaveOptions::from_builder(&SteeveBuilder::default())
+        };
+
+        let steam_dir = root.join("Steam");
+        let xbox_dir = root.join("Xbox");
+        let scratch_dir = root.join("Scratch");
+        for dir in [&steam_dir, &xbox_dir, &scratch_dir] {
+            std::fs::create_dir_all(dir).map_err(|err| format!("{dir:?}: {err}"))?;
+        }
+
+        let backup_dir = root.join("Backups");
+        let steam_save =
+            SteamSave::new(options.clone(), steam_dir.clone(), backup_dir.join("Steam"))
+                .map_err(|err| err.to_string())?;
+        let xbox_save = XboxSave::new(options, xbox_dir.clone(), backup_dir.join("Xbox"))
+            .map_err(|err| err.to_string())?;
+
+        // The Xbox save is older, so the Steam save wins
+        let steam_path = steam_dir.join(STEAM_NAME);
+        let xbox_path = xbox_dir.join(XBOX_NAME);
+        let steam_bytes = fake_save(b"steam");
+        let xbox_bytes = fake_save(b"xbox");
+        write_file(&xbox_path, &xbox_bytes, Duration::from_secs(60 * 60))?;
+        write_file(&steam_path, &steam_bytes, Duration::ZERO)?;
+
+        Ok(Self {
+            steam_save,
+            xbox_save,
+            steam_path,
+            xbox_path,
+            steam_bytes,
+            xbox_bytes,
+            scratch_dir,
+        })
+    }
+
+    fn sync(&self) -> Result<(), String> {
+        let outcome = self
+            .xbox_save
+            .copy_save(&self.steam_path, |_| SyncDecision::Proceed)
+            .map_err(|err| err.to_string())?;
+        if !matches!(outcome, SyncOutcome::Copied { .. }) {
+            return Err(format!("expected a copy, got {outcome:?}"));
+        }
+
+        check_contents(&self.xbox_path, &self.steam_bytes)?;
+
+        match self.backups(&self.xbox_save)?.len() {
+            1 => Ok(()),
+            count => Err(format!("expected 1 Xbox backup, found {count}")),
+        }
+    }
+
+    fn dedupe(&self) -> Result<(), String> {
+        // Same contents as the backup made during the sync
+        let path = self.scratch_dir.join(XBOX_NAME);
+        write_file(&path, &self.xbox_bytes, Duration::ZERO)?;
+
+        let backed_up = self
+            .xbox_save
+            .backup(&path, XBOX_NAME)
+            .map_err(|err| err.to_string())?;
+        if backed_up.is_some() {
+            return Err("duplicate save was backed up".to_string());
+        }
+
+        Ok(())
+    }
+
+    fn restore(&self) -> Result<(), String> {
+        let backups = self.backups(&self.xbox_save)?;
+        let backup = backups
+            .first()
+            .ok_or_else(|| "no Xbox backup to restore".to_string())?;
+
+        self.xbox_save
+            .restore(backup)
+            .map_err(|err| format!("{:?}: {err}", backup.path))?;
+        check_contents(&self.xbox_path, &self.xbox_bytes)
+    }
+
+    fn prune(&self) -> Result<(), String> {
+        // Make older backups with distinct contents, so they are not de-duped
+        let backup_dir = self.steam_save.backup_dir();
+        let now = SystemTime::now();
+        for age in 1..=3 {
+            let age = Duration::from_secs(age * 60);
+            let name = self.steam_save.backup_name(STEAM_NAME, now - age);
+            let body = format!("old backup {}", age.as_secs());
+            write_file(&backup_dir.join(name), &fake_save(body.as_bytes()), age)?;
+        }
+
+        self.steam_save
+            .remove_old_backups()
+            .map_err(|err| err.to_string())?;
+
+        let max_backups = self.steam_save.options().max_backups;
+        match self.backups(&self.steam_save)?.len() {
+            count if count == max_backups => Ok(()),
+            count => Err(format!(
+                "expected {max_backups} Steam backups, found {count}"
+            )),
+        }
+    }
+
+    /// Get the backups for a side.
+    fn backups(&self, save: &impl SteeveSave) -> Result<Vec<BackupEntry>, String> {
+        save.list_backups().map_err(|err| err.to_string())
+    }
+}
+
+/// Create a fake save with a GVAS header, padded to the minimum save size.
+pub(crate) fn fake_save(body: &[u8]) -> Vec<u8> {
+    let mut bytes = GVAS_MAGIC.to_vec();
+    bytes.resize(MIN_SAVE_SIZE as usize, 0);
+    bytes.extend_from_slice(body);
+
+    bytes
+}
+
+/// Write a file with a modify time `age` in the past.
+pub(crate) fn write_file(path: &Path, bytes: &[u8], age: Duration) -> Result<(), String> {
+    let write = || {
+        std::fs::write(path, bytes)?;
+        File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(SystemTime::now() - age)
+    };
+
+    write().map_err(|err| format!("{path:?}: {err}"))
+}
+
+/// Check that a file has the expected contents.
+fn check_contents(path: &Path, expected: &[u8]) -> Result<(), String> {
+    let bytes = std::fs::read(path).map_err(|err| format!("{path:?}: {err}"))?;
+    if bytes != expected {
+        return Err(format!("{path:?} has unexpected contents"));
+    }
+
+    Ok(())
+}
+
+/// Check how a save directory can be accessed.
+fn dir_report(path: &PathBuf) -> DirReport {
+    let readable = std::fs::read_dir(path).is_ok();
+    let writable = std::fs::metadata(path)
+        .map(|meta| !meta.permissions().readonly())
+        .unwrap_or(false);
+
+    DirReport {
+        path: path.clone(),
+        readable,
+        writable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_test_passes() {
+        let root = tempfile::TempDir::new().unwrap();
+        let stages = run_stages(root.path());
+
+        for (stage, status) in stages {
+            assert_eq!(status, StageStatus::Passed, "{stage:?}");
+        }
+    }
+}
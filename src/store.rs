@@ -0,0 +1,314 @@
+//! Storage for saves and backups.
+//!
+//! The sync, backup, and de-dupe logic in [`SteeveSave`](crate::saves::SteeveSave) goes through a
+//! [`SaveStore`], so it does not depend on the local file system. Watching for changes, staged
+//! renames, and the trash still use the file system directly.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// A file in a [`SaveStore`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct StoreEntry {
+    /// Path to the file.
+    pub(crate) path: PathBuf,
+
+    /// Size of the file, in bytes.
+    pub(crate) len: u64,
+
+    /// When the file was last modified.
+    pub(crate) modified: SystemTime,
+}
+
+/// The storage operations needed for syncing and backing up saves.
+pub(crate) trait SaveStore: Debug + Send + Sync {
+    /// Open a file for reading.
+    fn open(&self, path: &Path) -> std::io::Result<Box<dyn Read + '_>>;
+
+    /// Create or replace a file with `bytes`.
+    fn write(&self, path: &Path, bytes: &[u8]) -> std::io::Result<()>;
+
+    /// Get the size and modify time of a file.
+    fn stat(&self, path: &Path) -> std::io::Result<StoreEntry>;
+
+    /// Set the modify time of a file.
+    fn set_modified(&self, path: &Path, modified: SystemTime) -> std::io::Result<()>;
+
+    /// List the files directly inside a directory, from oldest to newest modify time.
+    fn list(&self, dir: &Path) -> std::io::Result<Vec<StoreEntry>>;
+
+    /// List all files inside a directory and its subdirectories.
+    fn walk(&self, dir: &Path) -> std::io::Result<Vec<StoreEntry>>;
+
+    /// Remove a file.
+    fn remove(&self, path: &Path) -> std::io::Result<()>;
+
+    /// Move a file, replacing any file at `to`.
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()>;
+
+    /// Check if a directory exists.
+    fn is_dir(&self, dir: &Path) -> bool;
+
+    /// Create a directory and its parents, if they do not exist.
+    fn create_dir_all(&self, dir: &Path) -> std::io::Result<()>;
+
+    /// Read a whole file.
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.open(path)?.read_to_end(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Copy a file, returning the number of bytes copied.
+    fn copy(&self, from: &Path, to: &Path) -> std::io::Result<u64> {
+        let bytes = self.read(from)?;
+        self.write(to, &bytes)?;
+
+        Ok(bytes.len() as u64)
+    }
+}
+
+/// The local file system.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct FsStore {
+    /// Follow symbolic links (and junctions) when listing directories.
+    follow_links: bool,
+}
+
+impl FsStore {
+    pub(crate) fn new(follow_links: bool) -> Self {
+        Self { follow_links }
+    }
+
+    /// Get the files found by `walker`.
+    ///
+    /// Link loops and files that vanish while walking are skipped. Only an error for the
+    /// directory itself, e.g. when it does not exist, is returned.
+    fn entries(&self, walker: WalkDir) -> std::io::Result<Vec<StoreEntry>> {
+        let mut entries = Vec::new();
+
+        for entry in walker.follow_links(self.follow_links) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) if err.depth() == 0 => return Err(err.into()),
+                Err(_) => continue,
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+
+            entries.push(StoreEntry {
+                path: entry.into_path(),
+                len: meta.len(),
+                modified: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+impl SaveStore for FsStore {
+    fn open(&self, path: &Path) -> std::io::Result<Box<dyn Read + '_>> {
+        Ok(Box::new(File::open(path)?))
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+        std::fs::write(path, bytes)
+    }
+
+    fn stat(&self, path: &Path) -> std::io::Result<StoreEntry> {
+        let meta = path.metadata()?;
+
+        Ok(StoreEntry {
+            path: path.to_path_buf(),
+            len: meta.len(),
+            modified: meta.modified()?,
+        })
+    }
+
+    fn set_modified(&self, path: &Path, modified: SystemTime) -> std::io::Result<()> {
+        File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(modified)
+    }
+
+    fn list(&self, dir: &Path) -> std::io::Result<Vec<StoreEntry>> {
+        let mut entries = self.entries(WalkDir::new(dir).max_depth(1))?;
+        entries.sort_by_key(|entry| entry.modified);
+
+        Ok(entries)
+    }
+
+    fn walk(&self, dir: &Path) -> std::io::Result<Vec<StoreEntry>> {
+        self.entries(WalkDir::new(dir))
+    }
+
+    fn remove(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    fn is_dir(&self, dir: &Path) -> bool {
+        dir.is_dir()
+    }
+
+    fn create_dir_all(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> std::io::Result<u64> {
+        std::fs::copy(from, to)
+    }
+}
+
+/// An in-memory store for unit tests.
+///
+/// Tests run without temporary directories, and control modify times exactly.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct InMemoryStore {
+    files: parking_lot::Mutex<std::collections::BTreeMap<PathBuf, (Vec<u8>, SystemTime)>>,
+
+    /// Make copies and writes into this directory write half of the file and then fail, like a
+    /// full disk.
+    fail_writes_into: parking_lot::Mutex<Option<PathBuf>>,
+
+    /// Number of copies left to fail without writing anything, like a file that is in use.
+    transient_failures: parking_lot::Mutex<usize>,
+}
+
+#[cfg(test)]
+impl InMemoryStore {
+    /// Add a file with the given modify time.
+    pub(crate) fn insert(&self, path: impl Into<PathBuf>, bytes: &[u8], modified: SystemTime) {
+        self.files
+            .lock()
+            .insert(path.into(), (bytes.to_vec(), modified));
+    }
+
+    /// Make copies and writes into `dir` fail halfway through, or stop failing with `None`.
+    pub(crate) fn fail_writes_into(&self, dir: Option<&Path>) {
+        *self.fail_writes_into.lock() = dir.map(Path::to_path_buf);
+    }
+
+    /// Make the next `count` copies fail with an error that clears up when retried.
+    pub(crate) fn fail_next_copies(&self, count: usize) {
+        *self.transient_failures.lock() = count;
+    }
+}
+
+#[cfg(test)]
+impl SaveStore for InMemoryStore {
+    fn open(&self, path: &Path) -> std::io::Result<Box<dyn Read + '_>> {
+        let bytes = self.files.lock().get(path).map(|(bytes, _)| bytes.clone());
+        match bytes {
+            Some(bytes) => Ok(Box::new(std::io::Cursor::new(bytes))),
+            None => Err(std::io::ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+        if self.fail_writes_into.lock().as_deref() == path.parent() {
+            self.insert(path, &bytes[..bytes.len() / 2], SystemTime::now());
+            return Err(std::io::Error::other("disk full"));
+        }
+        self.insert(path, bytes, SystemTime::now());
+
+        Ok(())
+    }
+
+    fn stat(&self, path: &Path) -> std::io::Result<StoreEntry> {
+        match self.files.lock().get(path) {
+            Some((bytes, modified)) => Ok(StoreEntry {
+                path: path.to_path_buf(),
+                len: bytes.len() as u64,
+                modified: *modified,
+            }),
+            None => Err(std::io::ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn set_modified(&self, path: &Path, modified: SystemTime) -> std::io::Result<()> {
+        match self.files.lock().get_mut(path) {
+            Some(file) => {
+                file.1 = modified;
+                Ok(())
+            }
+            None => Err(std::io::ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn list(&self, dir: &Path) -> std::io::Result<Vec<StoreEntry>> {
+        let mut entries = self.walk(dir)?;
+        entries.retain(|entry| entry.path.parent() == Some(dir));
+        entries.sort_by_key(|entry| entry.modified);
+
+        Ok(entries)
+    }
+
+    fn walk(&self, dir: &Path) -> std::io::Result<Vec<StoreEntry>> {
+        let entries = self
+            .files
+            .lock()
+            .iter()
+            .filter(|(path, _)| path.starts_with(dir))
+            .map(|(path, (bytes, modified))| StoreEntry {
+                path: path.clone(),
+                len: bytes.len() as u64,
+                modified: *modified,
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    fn remove(&self, path: &Path) -> std::io::Result<()> {
+        match self.files.lock().remove(path) {
+            Some(_) => Ok(()),
+            None => Err(std::io::ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        let mut files = self.files.lock();
+        let file = files.remove(from).ok_or(std::io::ErrorKind::NotFound)?;
+        files.insert(to.to_path_buf(), file);
+
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> std::io::Result<u64> {
+        let bytes = self.read(from)?;
+        let mut transient_failures = self.transient_failures.lock();
+        if *transient_failures > 0 {
+            *transient_failures -= 1;
+            return Err(std::io::ErrorKind::WouldBlock.into());
+        }
+        drop(transient_failures);
+        self.write(to, &bytes)?;
+
+        Ok(bytes.len() as u64)
+    }
+
+    fn is_dir(&self, _dir: &Path) -> bool {
+        // Directories exist implicitly
+        true
+    }
+
+    fn create_dir_all(&self, _dir: &Path) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+//! Fixtures shared by the unit tests.
+
+use crate::saves::{SaveOptions, MIN_SAVE_SIZE};
+use crate::{BackupTimestamp, HashAlgo, SteeveBuilder};
+use std::path::Path;
+use std::time::Duration;
+
+pub(crate) use crate::self_test::{fake_save, STEAM_NAME, XBOX_NAME};
+
+impl SaveOptions {
+    /// Settings for tests: three backups with Unix timestamps, and no waiting for saves to
+    /// settle.
+    pub(crate) fn for_test() -> Self {
+        Self {
+            max_backups: 3,
+            backup_timestamp: BackupTimestamp::Unix,
+            hash_algo: HashAlgo::Blake3,
+            settle_time: Duration::ZERO,
+            mtime_tolerance: Duration::ZERO,
+            ..Self::from_builder(&SteeveBuilder::default())
+        }
+    }
+}
+
+/// Write a file and set its modify time to `age` before now.
+pub(crate) fn write_file(path: &Path, bytes: &[u8], age: Duration) {
+    crate::self_test::write_file(path, bytes, age).unwrap();
+}
+
+/// Write a plausible save file with the given contents appended to the header.
+pub(crate) fn write_save(path: &Path, body: &[u8], age: Duration) {
+    write_file(path, &fake_save(body), age);
+}
+
+/// Read the contents of a save file written by [`write_save`], without the header.
+pub(crate) fn read_body(path: &Path) -> Vec<u8> {
+    std::fs::read(path).unwrap()[MIN_SAVE_SIZE as usize..].to_vec()
+}
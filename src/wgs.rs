@@ -0,0 +1,678 @@
+//! Diagnostics for the Xbox "wgs" (Windows Gaming Services) save container format.
+//!
+//! An Xbox save directory holds a `containers.index` file that lists the save containers. Each
+//! container is a directory named after a GUID, holding a `container.{seq}` file that lists the
+//! blobs in the container. Each blob is a file named after another GUID. The game save is one of
+//! these blobs.
+//!
+//! All integers are little-endian, strings are a `u32` length in UTF-16 code units followed by
+//! the UTF-16 text, and times are Windows `FILETIME`s (100 ns ticks since 1601).
+//!
+//! `containers.index`:
+//!
+//! | Field            | Type       | Notes                                 |
+//! |------------------|------------|---------------------------------------|
+//! | Version          | `u32`      | 14                                    |
+//! | Count            | `u32`      | Number of container entries           |
+//! | Flags            | `u32`      |                                       |
+//! | Package name     | string     |                                       |
+//! | Modify time      | `FILETIME` | Updated when any container changes    |
+//! | Flags            | `u32`      |                                       |
+//! | Container ID     | string     |                                       |
+//! | Unknown          | 8 bytes    |                                       |
+//! | Entries          | ...        | `Count` container entries             |
+//!
+//! Container entry:
+//!
+//! | Field            | Type       | Notes                                 |
+//! |------------------|------------|---------------------------------------|
+//! | Name             | string     | `..._Player` for the player save      |
+//! | Name, again      | string     |                                       |
+//! | ID               | string     |                                       |
+//! | Sequence         | `u8`       | The file is `container.{seq}`        |
+//! | Flags            | `u32`      |                                       |
+//! | Directory GUID   | 16 bytes   | See [`format_guid`]                   |
+//! | Modify time      | `FILETIME` |                                       |
+//! | Unknown          | 8 bytes    |                                       |
+//! | Size             | `u64`      | Total size of the blobs, in bytes     |
+//!
+//! `container.{seq}`:
+//!
+//! | Field            | Type       | Notes                                 |
+//! |------------------|------------|---------------------------------------|
+//! | Version          | `u32`      | 4                                     |
+//! | Count            | `u32`      | Number of blobs                       |
+//! | Blobs            | ...        | `Count` blob entries                  |
+//!
+//! Blob entry: a 128 byte, zero-padded UTF-16 name, a 16 byte cloud GUID, and the 16 byte GUID
+//! that names the blob file.
+//!
+//! The player save blob holds the same GVAS bytes as a Steam `_Player.sav`, so a save converts
+//! between the editions by copying the payload as-is. Only the index needs updating after a blob
+//! is rewritten, so its size and modify time match the blob.
+
+use crate::saves::SaveError;
+use log::{debug, info};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// File name of the container index.
+pub(crate) const INDEX_FILE: &str = "containers.index";
+
+/// Size of the fixed-length UTF-16 blob name in a container file, in bytes.
+const BLOB_NAME_SIZE: usize = 128;
+
+/// Suffix of the container name for the player save.
+const PLAYER_SUFFIX: &str = "_Player";
+
+/// Seconds from the `FILETIME` epoch (1601) to the Unix epoch.
+const FILETIME_UNIX_OFFSET: u64 = 11_644_473_600;
+
+/// The result of checking a `containers.index` against the blobs on disk.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IndexReport {
+    /// Path to the `containers.index` file.
+    pub index_path: PathBuf,
+
+    /// Number of containers listed in the index.
+    pub containers: usize,
+
+    /// Containers listed in the index whose directory or container file is missing.
+    pub dangling: Vec<PathBuf>,
+
+    /// Blobs listed in a container file that do not exist.
+    pub missing_blobs: Vec<PathBuf>,
+
+    /// Container directories and blobs on disk that are not listed anywhere.
+    pub orphans: Vec<PathBuf>,
+
+    /// Number of dangling containers removed from the index by a repair.
+    pub removed: usize,
+}
+
+impl IndexReport {
+    /// Check if the index and the blobs on disk agree.
+    pub fn is_consistent(&self) -> bool {
+        self.dangling.is_empty() && self.missing_blobs.is_empty() && self.orphans.is_empty()
+    }
+}
+
+/// A parsed `containers.index`.
+struct Index {
+    bytes: Vec<u8>,
+
+    /// Byte offset of the container count.
+    count_offset: usize,
+
+    /// Byte offset of the index modify time.
+    modified_offset: usize,
+
+    /// Byte offset where the first container entry starts.
+    entries_offset: usize,
+
+    entries: Vec<IndexEntry>,
+}
+
+/// A container entry in the index.
+struct IndexEntry {
+    /// Byte range of the whole entry in the index.
+    range: std::ops::Range<usize>,
+
+    /// Container name.
+    name: String,
+
+    /// Byte offset of the container modify time.
+    modified_offset: usize,
+
+    /// Byte offset of the container size.
+    size_offset: usize,
+
+    /// Sequence number of the container file, `container.{seq}`.
+    seq: u8,
+
+    /// Container directory name.
+    dir_name: String,
+}
+
+/// Check that the index in `save_dir` and the containers and blobs on disk agree.
+pub(crate) fn check(save_dir: &Path) -> Result<IndexReport, SaveError> {
+    let index_path = save_dir.join(INDEX_FILE);
+    let index = Index::read(&index_path)?;
+
+    let mut report = IndexReport {
+        index_path,
+        containers: index.entries.len(),
+        ..IndexReport::default()
+    };
+    let mut listed_dirs = HashSet::new();
+
+    for entry in &index.entries {
+        let dir = save_dir.join(&entry.dir_name);
+        listed_dirs.insert(dir.clone());
+
+        let container_path = dir.join(format!("container.{}", entry.seq));
+        let blobs = match std::fs::read(&container_path) {
+            Ok(bytes) => parse_container(&bytes).ok_or(SaveError::BadIndex(container_path))?,
+            Err(_) => {
+                report.dangling.push(dir);
+                continue;
+            }
+        };
+
+        for blob in &blobs {
+            let path = dir.join(blob);
+            if !path.is_file() {
+                report.missing_blobs.push(path);
+            }
+        }
+
+        // Anything else in the container directory is an orphan, except for stale container
+        // files, which the Xbox app leaves behind
+        for path in read_files(&dir) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if !name.starts_with("container.") && !blobs.iter().any(|blob| *blob == name) {
+                report.orphans.push(path);
+            }
+        }
+    }
+
+    for path in read_dirs(save_dir) {
+        if is_guid_name(&path) && !listed_dirs.contains(&path) {
+            report.orphans.push(path);
+        }
+    }
+
+    report.dangling.sort();
+    report.missing_blobs.sort();
+    report.orphans.sort();
+    debug!("Xbox containers index report: {:?}", report);
+
+    Ok(report)
+}
+
+/// Remove dangling containers from the index in `save_dir`.
+///
+/// The index is first copied into `backup_dir`. Returns the report from before the repair, with
+/// the number of removed containers. Missing blobs and orphans are reported but left alone.
+pub(crate) fn repair(save_dir: &Path, backup_dir: &Path) -> Result<IndexReport, SaveError> {
+    let mut report = check(save_dir)?;
+    if report.dangling.is_empty() {
+        return Ok(report);
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let backup_path = backup_dir.join(format!("{INDEX_FILE}.{timestamp}.bak"));
+    info!(
+        "Backup Xbox containers index: {:?} -> {:?}",
+        report.index_path, backup_path,
+    );
+    std::fs::copy(&report.index_path, &backup_path)?;
+
+    let index = Index::read(&report.index_path)?;
+    let (bytes, removed) =
+        index.without(|entry| report.dangling.contains(&save_dir.join(&entry.dir_name)));
+
+    // Write the new index next to the old one, then replace it
+    let temp_path = report.index_path.with_extension("index.tmp");
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(&temp_path, &report.index_path)?;
+
+    info!("Removed {removed} dangling containers from the Xbox containers index");
+    report.removed = removed;
+
+    Ok(report)
+}
+
+impl Index {
+    fn read(path: &Path) -> Result<Self, SaveError> {
+        let bytes = std::fs::read(path)?;
+
+        Self::parse(bytes).ok_or_else(|| SaveError::BadIndex(path.to_path_buf()))
+    }
+
+    fn parse(bytes: Vec<u8>) -> Option<Self> {
+        let mut reader = Reader::new(&bytes);
+
+        // Header: version, count, flags, package name, modify time, flags, container ID, unknown
+        reader.u32()?;
+        let count_offset = reader.offset;
+        let count = reader.u32()?;
+        reader.u32()?;
+        reader.string()?;
+        let modified_offset = reader.offset;
+        reader.skip(8 + 4)?;
+        reader.string()?;
+        reader.skip(8)?;
+
+        let entries_offset = reader.offset;
+        let entries = (0..count)
+            .map(|_| {
+                // Entry: names, ID, seq, flags, GUID, modify time, unknown, size
+                let start = reader.offset;
+                let name = reader.string()?;
+                reader.string()?;
+                reader.string()?;
+                let seq = reader.u8()?;
+                reader.skip(4)?;
+                let dir_name = format_guid(reader.take(16)?);
+                let modified_offset = reader.offset;
+                reader.skip(8 + 8)?;
+                let size_offset = reader.offset;
+                reader.skip(8)?;
+
+                Some(IndexEntry {
+                    range: start..reader.offset,
+                    name,
+                    modified_offset,
+                    size_offset,
+                    seq,
+                    dir_name,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            bytes,
+            count_offset,
+            modified_offset,
+            entries_offset,
+            entries,
+        })
+    }
+
+    /// Write a `u64` field at `offset`.
+    fn set_u64(&mut self, offset: usize, value: u64) {
+        self.bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Serialize the index without the entries matching `remove`.
+    ///
+    /// Returns the new index bytes and the number of removed entries.
+    fn without(&self, remove: impl Fn(&IndexEntry) -> bool) -> (Vec<u8>, usize) {
+        let kept = self
+            .entries
+            .iter()
+            .filter(|entry| !remove(entry))
+            .collect::<Vec<_>>();
+
+        let mut bytes = self.bytes[..self.entries_offset].to_vec();
+        let count = u32::try_from(kept.len()).unwrap_or(u32::MAX);
+        bytes[self.count_offset..self.count_offset + 4].copy_from_slice(&count.to_le_bytes());
+        for entry in &kept {
+            bytes.extend_from_slice(&self.bytes[entry.range.clone()]);
+        }
+
+        // Keep any trailing data
+        let end = self
+            .entries
+            .last()
+            .map_or(self.entries_offset, |e| e.range.end);
+        bytes.extend_from_slice(&self.bytes[end..]);
+
+        (bytes, self.entries.len() - kept.len())
+    }
+}
+
+/// Find the blobs that hold the player save, using the index in `save_dir`.
+///
+/// Returns `Ok(None)` when there is no index, or no container is named like a player save, so
+/// the caller can fall back to looking at the files.
+pub(crate) fn locate_save_blobs(save_dir: &Path) -> Result<Option<Vec<PathBuf>>, SaveError> {
+    let index_path = save_dir.join(INDEX_FILE);
+    if !index_path.is_file() {
+        return Ok(None);
+    }
+
+    let index = Index::read(&index_path)?;
+    let players = index
+        .entries
+        .iter()
+        .filter(|entry| is_player_container(&entry.name))
+        .collect::<Vec<_>>();
+    if players.is_empty() {
+        debug!("No player save container in {index_path:?}");
+        return Ok(None);
+    }
+
+    let mut blobs = Vec::new();
+    for entry in players {
+        let dir = save_dir.join(&entry.dir_name);
+        let Ok(bytes) = std::fs::read(dir.join(format!("container.{}", entry.seq))) else {
+            continue;
+        };
+        let names = parse_container(&bytes).ok_or_else(|| SaveError::BadIndex(dir.clone()))?;
+        blobs.extend(
+            names
+                .into_iter()
+                .map(|name| dir.join(name))
+                .filter(|path| path.is_file()),
+        );
+    }
+
+    Ok(Some(blobs))
+}
+
+/// Record a rewritten blob in the index in `save_dir`.
+///
+/// The size and modify time of the blob's container, and the modify time of the index, are set
+/// from the blob files. Nothing changes when there is no index, or it does not list the blob's
+/// container.
+pub(crate) fn update_blob(save_dir: &Path, blob: &Path) -> Result<(), SaveError> {
+    let index_path = save_dir.join(INDEX_FILE);
+    if !index_path.is_file() {
+        return Ok(());
+    }
+
+    let mut index = Index::read(&index_path)?;
+    let dir = blob.parent().unwrap_or(save_dir);
+    let dir_name = dir.file_name().unwrap_or_default().to_string_lossy();
+    let Some(entry) = index
+        .entries
+        .iter()
+        .find(|entry| entry.dir_name.eq_ignore_ascii_case(&dir_name))
+    else {
+        return Ok(());
+    };
+
+    let container_path = dir.join(format!("container.{}", entry.seq));
+    let bytes = std::fs::read(&container_path)?;
+    let names = parse_container(&bytes).ok_or(SaveError::BadIndex(container_path))?;
+    let size = names
+        .iter()
+        .filter_map(|name| dir.join(name).metadata().ok())
+        .map(|meta| meta.len())
+        .sum();
+    let modified = filetime(blob.metadata()?.modified()?);
+
+    let (size_offset, entry_offset) = (entry.size_offset, entry.modified_offset);
+    index.set_u64(size_offset, size);
+    index.set_u64(entry_offset, modified);
+    index.set_u64(index.modified_offset, modified);
+    debug!("Updated Xbox containers index for {blob:?}: {size} bytes");
+
+    // Write the new index next to the old one, then replace it
+    let temp_path = index_path.with_extension("index.tmp");
+    std::fs::write(&temp_path, &index.bytes)?;
+    std::fs::rename(&temp_path, &index_path)?;
+
+    Ok(())
+}
+
+/// Check if a container name is the player save's.
+fn is_player_container(name: &str) -> bool {
+    let suffix = name.len().checked_sub(PLAYER_SUFFIX.len());
+
+    suffix.is_some_and(|at| {
+        name.get(at..)
+            .is_some_and(|end| end.eq_ignore_ascii_case(PLAYER_SUFFIX))
+    })
+}
+
+/// Convert a time to a Windows `FILETIME`.
+fn filetime(time: SystemTime) -> u64 {
+    let since_unix = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let since_1601 = since_unix + Duration::from_secs(FILETIME_UNIX_OFFSET);
+
+    u64::try_from(since_1601.as_nanos() / 100).unwrap_or(u64::MAX)
+}
+
+/// Parse a `container.{seq}` file into the blob file names it lists.
+fn parse_container(bytes: &[u8]) -> Option<Vec<String>> {
+    let mut reader = Reader::new(bytes);
+
+    // Header: version, count
+    reader.u32()?;
+    let count = reader.u32()?;
+
+    (0..count)
+        .map(|_| {
+            // Blob: name, cloud GUID, file GUID
+            reader.skip(BLOB_NAME_SIZE + 16)?;
+            reader.take(16).map(format_guid)
+        })
+        .collect()
+}
+
+/// Format a GUID the way the Xbox app names files: mixed-endian, uppercase hex, no dashes.
+fn format_guid(bytes: &[u8]) -> String {
+    let order = [3, 2, 1, 0, 5, 4, 7, 6, 8, 9, 10, 11, 12, 13, 14, 15];
+
+    order.iter().map(|&i| format!("{:02X}", bytes[i])).collect()
+}
+
+/// Check if a path has a file name formatted like [`format_guid`].
+fn is_guid_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.len() == 32 && name.chars().all(|ch| ch.is_ascii_hexdigit()))
+        .unwrap_or(false)
+}
+
+/// Get all subdirectories of a directory.
+fn read_dirs(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    read_entries(dir).filter(|path| path.is_dir())
+}
+
+/// Get all files in a directory.
+fn read_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    read_entries(dir).filter(|path| path.is_file())
+}
+
+fn read_entries(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+}
+
+/// A little-endian reader over a byte slice.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn take(&mut self, size: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset.checked_add(size)?)?;
+        self.offset += size;
+
+        Some(bytes)
+    }
+
+    fn skip(&mut self, size: usize) -> Option<()> {
+        self.take(size).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)?.try_into().ok().map(u32::from_le_bytes)
+    }
+
+    /// Read a length-prefixed UTF-16 string.
+    fn string(&mut self) -> Option<String> {
+        let len = usize::try_from(self.u32()?).ok()?;
+        let units = self
+            .take(len.checked_mul(2)?)?
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect::<Vec<_>>();
+
+        String::from_utf16(&units).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn string(bytes: &mut Vec<u8>, s: &str) {
+        let units = s.encode_utf16().collect::<Vec<_>>();
+        bytes.extend_from_slice(&(units.len() as u32).to_le_bytes());
+        for unit in units {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+
+    /// Build a `containers.index` listing containers with the given GUID bytes.
+    fn index(guids: &[[u8; 16]]) -> Vec<u8> {
+        let containers = guids.iter().map(|&guid| ("SaveGame", guid));
+
+        named_index(&containers.collect::<Vec<_>>())
+    }
+
+    /// Build a `containers.index` listing containers with the given names and GUID bytes.
+    fn named_index(containers: &[(&str, [u8; 16])]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&14_u32.to_le_bytes());
+        bytes.extend_from_slice(&(containers.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        string(&mut bytes, "CoffeeStainStudios.DeepRockGalactic");
+        bytes.extend_from_slice(&[0; 8 + 4]);
+        string(&mut bytes, "container-id");
+        bytes.extend_from_slice(&[0; 8]);
+
+        for (name, guid) in containers {
+            string(&mut bytes, name);
+            string(&mut bytes, name);
+            string(&mut bytes, "0x8D9");
+            bytes.push(1);
+            bytes.extend_from_slice(&[0; 4]);
+            bytes.extend_from_slice(guid);
+            bytes.extend_from_slice(&[0; 8 + 8 + 8]);
+        }
+
+        bytes
+    }
+
+    /// Build a `container.1` file listing blobs with the given GUID bytes.
+    fn container(guids: &[[u8; 16]]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&4_u32.to_le_bytes());
+        bytes.extend_from_slice(&(guids.len() as u32).to_le_bytes());
+        for guid in guids {
+            bytes.extend_from_slice(&[0; BLOB_NAME_SIZE + 16]);
+            bytes.extend_from_slice(guid);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn format_guid_is_mixed_endian() {
+        let guid = (0..16).collect::<Vec<u8>>();
+
+        assert_eq!(format_guid(&guid), "030201000504070608090A0B0C0D0E0F");
+    }
+
+    #[test]
+    fn check_and_repair() {
+        let dir = TempDir::new().unwrap();
+        let save_dir = dir.path().join("wgs");
+        let backup_dir = dir.path().join("backup");
+        std::fs::create_dir_all(&backup_dir).unwrap();
+
+        let [good, dangling, orphan, blob, missing] = [1, 2, 3, 4, 5].map(|n| [n; 16]);
+        let good_dir = save_dir.join(format_guid(&good));
+        std::fs::create_dir_all(&good_dir).unwrap();
+        std::fs::create_dir_all(save_dir.join(format_guid(&orphan))).unwrap();
+        std::fs::write(good_dir.join("container.1"), container(&[blob, missing])).unwrap();
+        std::fs::write(good_dir.join(format_guid(&blob)), b"save").unwrap();
+        std::fs::write(save_dir.join(INDEX_FILE), index(&[good, dangling])).unwrap();
+
+        let report = check(&save_dir).unwrap();
+        assert_eq!(report.containers, 2);
+        assert_eq!(report.dangling, [save_dir.join(format_guid(&dangling))]);
+        assert_eq!(report.missing_blobs, [good_dir.join(format_guid(&missing))]);
+        assert_eq!(report.orphans, [save_dir.join(format_guid(&orphan))]);
+        assert!(!report.is_consistent());
+
+        let report = repair(&save_dir, &backup_dir).unwrap();
+        assert_eq!(report.removed, 1);
+        assert_eq!(std::fs::read_dir(&backup_dir).unwrap().count(), 1);
+
+        let report = check(&save_dir).unwrap();
+        assert_eq!(report.containers, 1);
+        assert!(report.dangling.is_empty());
+        assert_eq!(
+            std::fs::read(save_dir.join(INDEX_FILE)).unwrap(),
+            index(&[good]),
+        );
+    }
+
+    #[test]
+    fn save_blob_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let save_dir = dir.path().join("wgs");
+        let [player, settings, player_blob, settings_blob] = [1, 2, 3, 4].map(|n| [n; 16]);
+        let player_dir = save_dir.join(format_guid(&player));
+        let settings_dir = save_dir.join(format_guid(&settings));
+        for (dir, blob) in [(&player_dir, player_blob), (&settings_dir, settings_blob)] {
+            std::fs::create_dir_all(dir).unwrap();
+            std::fs::write(dir.join("container.1"), container(&[blob])).unwrap();
+            std::fs::write(dir.join(format_guid(&blob)), b"GVAS old").unwrap();
+        }
+        let containers = [("2533274_Player", player), ("Settings", settings)];
+        std::fs::write(save_dir.join(INDEX_FILE), named_index(&containers)).unwrap();
+
+        // Only the player container holds the save
+        let blob_path = player_dir.join(format_guid(&player_blob));
+        let blobs = locate_save_blobs(&save_dir).unwrap();
+        assert_eq!(blobs, Some(vec![blob_path.clone()]));
+
+        // A Steam save goes into the blob as-is, and the index follows
+        let steam_save = b"GVAS steam save payload";
+        std::fs::write(&blob_path, steam_save).unwrap();
+        update_blob(&save_dir, &blob_path).unwrap();
+
+        let parsed = Index::read(&save_dir.join(INDEX_FILE)).unwrap();
+        let read_u64 = |offset: usize| {
+            u64::from_le_bytes(parsed.bytes[offset..offset + 8].try_into().unwrap())
+        };
+        let [player_entry, settings_entry] = &parsed.entries[..] else {
+            panic!("The index lost a container");
+        };
+        let modified = filetime(blob_path.metadata().unwrap().modified().unwrap());
+        assert_eq!(read_u64(player_entry.size_offset), steam_save.len() as u64);
+        assert_eq!(read_u64(player_entry.modified_offset), modified);
+        assert_eq!(read_u64(parsed.modified_offset), modified);
+        assert_eq!(read_u64(settings_entry.size_offset), 0);
+        assert!(check(&save_dir).unwrap().is_consistent());
+
+        // And comes back out unchanged
+        let blobs = locate_save_blobs(&save_dir).unwrap().unwrap();
+        assert_eq!(std::fs::read(&blobs[0]).unwrap(), steam_save);
+
+        // Without a player container, the files are searched instead
+        std::fs::write(save_dir.join(INDEX_FILE), index(&[settings])).unwrap();
+        assert_eq!(locate_save_blobs(&save_dir).unwrap(), None);
+        std::fs::remove_file(save_dir.join(INDEX_FILE)).unwrap();
+        assert_eq!(locate_save_blobs(&save_dir).unwrap(), None);
+    }
+
+    #[test]
+    fn truncated_index_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let mut bytes = index(&[[1; 16]]);
+        bytes.truncate(bytes.len() - 1);
+        std::fs::write(dir.path().join(INDEX_FILE), bytes).unwrap();
+
+        assert!(matches!(check(dir.path()), Err(SaveError::BadIndex(_))));
+    }
+}